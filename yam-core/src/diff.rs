@@ -0,0 +1,609 @@
+use crate::prelude::path::map_value;
+use crate::prelude::{MappingLike, NodeType, SequenceLike, Span, Tag, YamlDocAccess, YamlEntry};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::{Display, Formatter, Write};
+
+///
+/// Options for comparing two documents with [`YamlDiff`].
+///
+/// By default sequences are compared position by position. Setting an
+/// [`identity_key`](DiffOptions::identity_key) matches sequence elements by the value of that
+/// key instead, so inserting or reordering `containers` doesn't show up as every later container
+/// having changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    identity_key: Option<String>,
+}
+
+impl DiffOptions {
+    /// Creates options that compare sequences by position.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches sequence elements by the scalar value of `key`, e.g. `name`.
+    ///
+    /// This only applies to sequences where every element on both sides is a mapping with a
+    /// distinct scalar value for `key`; other sequences are still compared by position.
+    #[must_use]
+    pub fn identity_key(mut self, key: impl Into<String>) -> Self {
+        self.identity_key = Some(key.into());
+        self
+    }
+
+    /// Compares `old` against `new` with these options.
+    pub fn diff<'n, 'input, N>(&self, old: &'n N, new: &'n N) -> YamlDiff<'n, N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        let mut differ = Differ {
+            options: self,
+            changes: Vec::new(),
+        };
+        differ.node(&mut String::from("$"), old, new);
+        YamlDiff {
+            changes: differ.changes,
+        }
+    }
+}
+
+/// What happened to the value at a [`Change`]'s path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The value only exists in the new document.
+    Added,
+    /// The value only exists in the old document.
+    Removed,
+    /// The value exists in both documents but differs.
+    Changed,
+}
+
+///
+/// One difference between two documents.
+///
+/// `old` and `new` point into the compared trees, so for
+/// [`SpannedYaml`](crate::prelude::SpannedYaml) they carry the location of the value on each
+/// side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change<'n, N> {
+    /// Whether the value was added, removed or changed.
+    pub kind: ChangeKind,
+    /// Where the value is, written as a [`YamlPath`](crate::prelude::YamlPath) expression such as
+    /// `$.spec.containers[?(@.name == 'web')].image`.
+    pub path: String,
+    /// The value in the old document, `None` if it was added.
+    pub old: Option<&'n N>,
+    /// The value in the new document, `None` if it was removed.
+    pub new: Option<&'n N>,
+}
+
+///
+/// Structural difference between two [`YamlDocAccess`] trees.
+///
+/// Mappings are matched by key regardless of key order, sequences by position or by an identity
+/// key (see [`DiffOptions`]), and scalars by type and value, so `1` and `1.0` differ. Changes
+/// are reported at the deepest path where the two trees disagree, in document order.
+///
+/// The [`Display`] impl renders one line per change, with `+`, `-` and `~` for added, removed
+/// and changed values, and source positions (`line:col`) when the nodes have them.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::diff::{ChangeKind, DiffOptions};
+/// use yam_core::prelude::{SpannedYaml, YamlLoader};
+///
+/// let old = "containers:\n- name: web\n  image: nginx:1.25\n- name: db\n  image: pg:16\n";
+/// let new = "containers:\n- name: db\n  image: pg:16\n- name: web\n  image: nginx:1.27\n";
+/// let old = &YamlLoader::<SpannedYaml>::load_from(old).unwrap()[0];
+/// let new = &YamlLoader::<SpannedYaml>::load_from(new).unwrap()[0];
+///
+/// let diff = DiffOptions::new().identity_key("name").diff(old, new);
+/// assert_eq!(diff.len(), 1);
+/// let change = &diff.changes()[0];
+/// assert_eq!(change.kind, ChangeKind::Changed);
+/// assert_eq!(change.path, "$.containers[?(@.name == 'web')].image");
+/// assert_eq!(change.new.unwrap().span.start.line, 5);
+/// assert_eq!(
+///     diff.to_string(),
+///     "~ $.containers[?(@.name == 'web')].image: nginx:1.25 -> nginx:1.27 (3:10 -> 5:10)\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlDiff<'n, N> {
+    changes: Vec<Change<'n, N>>,
+}
+
+impl<'n, N> YamlDiff<'n, N> {
+    /// Compares `old` against `new` with the default [`DiffOptions`].
+    pub fn new<'input>(old: &'n N, new: &'n N) -> Self
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        DiffOptions::new().diff(old, new)
+    }
+
+    /// The changes found, in document order.
+    #[must_use]
+    pub fn changes(&self) -> &[Change<'n, N>] {
+        &self.changes
+    }
+
+    /// Number of changes found.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Whether the two documents are structurally equal.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<'a, 'n, N> IntoIterator for &'a YamlDiff<'n, N> {
+    type Item = &'a Change<'n, N>;
+    type IntoIter = core::slice::Iter<'a, Change<'n, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+impl<'n, 'input, N> Display for YamlDiff<'n, N>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let sign = match change.kind {
+                ChangeKind::Added => '+',
+                ChangeKind::Removed => '-',
+                ChangeKind::Changed => '~',
+            };
+            write!(f, "{sign} {}: ", change.path)?;
+            if let Some(old) = change.old {
+                write_inline(f, old)?;
+            }
+            if change.kind == ChangeKind::Changed {
+                f.write_str(" -> ")?;
+            }
+            if let Some(new) = change.new {
+                write_inline(f, new)?;
+            }
+            let old_span = change.old.and_then(N::source_span);
+            let new_span = change.new.and_then(N::source_span);
+            match (old_span, new_span) {
+                (Some(old), Some(new)) => write!(f, " ({} -> {})", Pos(old), Pos(new))?,
+                (Some(span), None) | (None, Some(span)) => write!(f, " ({})", Pos(span))?,
+                (None, None) => {}
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+struct Pos(Span);
+
+impl Display for Pos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.0.start.line, self.0.start.col)
+    }
+}
+
+struct Differ<'o, 'n, N> {
+    options: &'o DiffOptions,
+    changes: Vec<Change<'n, N>>,
+}
+
+impl<'n, 'input, N> Differ<'_, 'n, N>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    fn push(&mut self, kind: ChangeKind, path: &str, old: Option<&'n N>, new: Option<&'n N>) {
+        self.changes.push(Change {
+            kind,
+            path: String::from(path),
+            old,
+            new,
+        });
+    }
+
+    fn node(&mut self, path: &mut String, old: &'n N, new: &'n N) {
+        if let (Ok(old_map), Ok(new_map)) = (old.as_mapping(), new.as_mapping()) {
+            self.mapping(path, old_map.entries(), new_map.entries());
+        } else if let (Ok(old_seq), Ok(new_seq)) = (old.as_sequence(), new.as_sequence()) {
+            self.sequence(path, old_seq.vec(), new_seq.vec());
+        } else if let (Some(old_inner), Some(new_inner)) = (old.tagged_node(), new.tagged_node())
+            && old_inner.is_collection()
+            && old.get_tag() == new.get_tag()
+        {
+            self.node(path, old_inner, new_inner);
+        } else if !same(old, new) {
+            self.push(ChangeKind::Changed, path, Some(old), Some(new));
+        }
+    }
+
+    fn mapping(
+        &mut self,
+        path: &mut String,
+        old: &'n [YamlEntry<'n, N>],
+        new: &'n [YamlEntry<'n, N>],
+    ) {
+        let len = path.len();
+        for entry in old {
+            push_key(path, &entry.key);
+            match new.iter().find(|e| same(&e.key, &entry.key)) {
+                Some(other) => self.node(path, &entry.value, &other.value),
+                None => self.push(ChangeKind::Removed, path, Some(&entry.value), None),
+            }
+            path.truncate(len);
+        }
+        for entry in new {
+            if !old.iter().any(|e| same(&e.key, &entry.key)) {
+                push_key(path, &entry.key);
+                self.push(ChangeKind::Added, path, None, Some(&entry.value));
+                path.truncate(len);
+            }
+        }
+    }
+
+    fn sequence(&mut self, path: &mut String, old: &'n [N], new: &'n [N]) {
+        let len = path.len();
+        if let Some(key) = self.options.identity_key.as_deref()
+            && let (Some(old_ids), Some(new_ids)) = (identities(old, key), identities(new, key))
+        {
+            for (elem, id) in old.iter().zip(&old_ids) {
+                push_identity(path, key, *id);
+                match new_ids.iter().position(|other| same(*other, *id)) {
+                    Some(i) => self.node(path, elem, &new[i]),
+                    None => self.push(ChangeKind::Removed, path, Some(elem), None),
+                }
+                path.truncate(len);
+            }
+            for (elem, id) in new.iter().zip(&new_ids) {
+                if !old_ids.iter().any(|other| same(*other, *id)) {
+                    push_identity(path, key, *id);
+                    self.push(ChangeKind::Added, path, None, Some(elem));
+                    path.truncate(len);
+                }
+            }
+            return;
+        }
+
+        for i in 0..old.len().max(new.len()) {
+            let _ = write!(path, "[{i}]");
+            match (old.get(i), new.get(i)) {
+                (Some(old), Some(new)) => self.node(path, old, new),
+                (Some(old), None) => self.push(ChangeKind::Removed, path, Some(old), None),
+                (None, Some(new)) => self.push(ChangeKind::Added, path, None, Some(new)),
+                (None, None) => {}
+            }
+            path.truncate(len);
+        }
+    }
+}
+
+/// The value of `key` in every element, if each is a mapping with a distinct scalar there.
+pub(crate) fn identities<'n, 'input, N>(elems: &'n [N], key: &str) -> Option<Vec<&'n N>>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    let mut ids: Vec<&N> = Vec::with_capacity(elems.len());
+    for elem in elems {
+        let id = map_value(elem, |k| k.as_str() == Some(key))?;
+        if id.is_collection() || ids.iter().any(|other| same(*other, id)) {
+            return None;
+        }
+        ids.push(id);
+    }
+    Some(ids)
+}
+
+/// Structural equality that ignores spans, presentation and mapping order.
+pub(crate) fn same<'n, 'input, N>(a: &'n N, b: &'n N) -> bool
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    if a.get_type() != b.get_type() {
+        return false;
+    }
+    match a.get_type() {
+        NodeType::Bool => a.as_bool() == b.as_bool(),
+        NodeType::Integer => a.as_i64() == b.as_i64(),
+        NodeType::Floating => match (a.as_f64(), b.as_f64()) {
+            (Some(x), Some(y)) => x == y || (x.is_nan() && y.is_nan()),
+            _ => false,
+        },
+        NodeType::String => a.as_str() == b.as_str(),
+        NodeType::Sequence => {
+            let (a, b) = (a.sequence().vec(), b.sequence().vec());
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y))
+        }
+        NodeType::Mapping => {
+            let (a, b) = (a.mapping().entries(), b.mapping().entries());
+            a.len() == b.len()
+                && a.iter().all(|x| {
+                    b.iter()
+                        .any(|y| same(&x.key, &y.key) && same(&x.value, &y.value))
+                })
+        }
+        NodeType::Null | NodeType::Alias | NodeType::Bad => {
+            a.get_tag() == b.get_tag()
+                && match (a.tagged_node(), b.tagged_node()) {
+                    (Some(x), Some(y)) => same(x, y),
+                    (x, y) => x.is_none() && y.is_none(),
+                }
+        }
+    }
+}
+
+fn push_key<'n, 'input, N>(path: &mut String, key: &'n N)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    match (key.as_str(), key.as_i64()) {
+        (Some(name), _) => push_name(path, name),
+        (None, Some(i)) => {
+            let _ = write!(path, ".{i}");
+        }
+        (None, None) => {
+            path.push('[');
+            let _ = write_inline(path, key);
+            path.push(']');
+        }
+    }
+}
+
+/// Writes `.name`, or `['name']` when `name` isn't a valid shorthand name.
+fn push_name(path: &mut String, name: &str) {
+    let shorthand = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-') || !c.is_ascii());
+    if shorthand {
+        path.push('.');
+        path.push_str(name);
+    } else {
+        path.push('[');
+        push_quoted(path, name);
+        path.push(']');
+    }
+}
+
+fn push_identity<'n, 'input, N>(path: &mut String, key: &str, id: &'n N)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    path.push_str("[?(@");
+    push_name(path, key);
+    path.push_str(" == ");
+    match id.as_str() {
+        Some(s) => push_quoted(path, s),
+        None => {
+            let _ = write_inline(path, id);
+        }
+    }
+    path.push_str(")]");
+}
+
+fn push_quoted(out: &mut String, s: &str) {
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\'' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out.push('\'');
+}
+
+/// Writes `node` on one line in flow style.
+pub(crate) fn write_inline<'n, 'input, N>(out: &mut impl Write, node: &'n N) -> fmt::Result
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    if let Some(tag) = node.get_tag() {
+        write_tag(out, &tag)?;
+        out.write_char(' ')?;
+        return match node.tagged_node() {
+            Some(inner) => write_inline(out, inner),
+            None => Ok(()),
+        };
+    }
+    match node.get_type() {
+        NodeType::Null => out.write_str("null"),
+        NodeType::Bool => write!(out, "{}", node.as_bool().unwrap_or_default()),
+        NodeType::Integer => write!(out, "{}", node.as_i64().unwrap_or_default()),
+        NodeType::Floating => match node.as_f64().unwrap_or_default() {
+            x if x.is_nan() => out.write_str(".nan"),
+            x if x.is_infinite() => out.write_str(if x > 0.0 { ".inf" } else { "-.inf" }),
+            x => write!(out, "{x:?}"),
+        },
+        NodeType::String => write_str(out, node.as_str().unwrap_or_default()),
+        NodeType::Sequence => {
+            out.write_char('[')?;
+            for (i, elem) in node.sequence().vec().iter().enumerate() {
+                if i > 0 {
+                    out.write_str(", ")?;
+                }
+                write_inline(out, elem)?;
+            }
+            out.write_char(']')
+        }
+        NodeType::Mapping => {
+            out.write_char('{')?;
+            for (i, entry) in node.mapping().entries().iter().enumerate() {
+                if i > 0 {
+                    out.write_str(", ")?;
+                }
+                write_inline(out, &entry.key)?;
+                out.write_str(": ")?;
+                write_inline(out, &entry.value)?;
+            }
+            out.write_char('}')
+        }
+        NodeType::Alias => out.write_str("*alias"),
+        NodeType::Bad => out.write_str("<bad value>"),
+    }
+}
+
+fn write_tag(out: &mut impl Write, tag: &Tag) -> fmt::Result {
+    if tag.is_yaml_core_schema() {
+        write!(out, "!!{}", tag.suffix)
+    } else {
+        write!(out, "{}{}", tag.handle, tag.suffix)
+    }
+}
+
+/// Writes a string plain when it can't be mistaken for another scalar, double-quoted otherwise.
+fn write_str(out: &mut impl Write, s: &str) -> fmt::Result {
+    let reserved = matches!(
+        s.to_ascii_lowercase().as_str(),
+        "" | "~" | "null" | "true" | "false" | ".nan" | ".inf" | "-.inf"
+    );
+    let plain = !reserved
+        && s.parse::<f64>().is_err()
+        && !s.starts_with([
+            '-', '?', '!', '&', '*', '@', '`', '\'', '"', '#', '%', '|', '>',
+        ])
+        && !s.ends_with(':')
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && s.chars()
+            .all(|c| !c.is_control() && !matches!(c, ',' | '[' | ']' | '{' | '}'))
+        && s.trim() == s;
+    if plain {
+        out.write_str(s)
+    } else {
+        write!(out, "{s:?}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::diff::{ChangeKind, DiffOptions, YamlDiff};
+    use crate::prelude::{Yaml, YamlDocAccess, YamlPath};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    const OLD: &str = "
+name: app
+replicas: 2
+labels: {tier: web, team: core}
+ports: [80, 443]
+containers:
+  - name: web
+    image: nginx:1.25
+  - name: sidecar
+    image: envoy:1.30
+ratio: 1
+'a.b': !custom x
+";
+
+    const NEW: &str = "
+name: app
+labels: {team: core, tier: 'web'}
+ports: [80]
+containers:
+  - name: sidecar
+    image: envoy:1.30
+  - name: web
+    image: nginx:1.27
+  - name: log
+    image: fluentd
+ratio: 1.0
+'a.b': !custom y
+debug: true
+";
+
+    #[test]
+    fn test_diff_by_index() {
+        let old = Yaml::load_single(OLD).unwrap();
+        let new = Yaml::load_single(NEW).unwrap();
+        let diff = YamlDiff::new(&old, &new);
+        let kinds: Vec<_> = diff.changes().iter().map(|c| (c.kind, &*c.path)).collect();
+        assert_eq!(
+            kinds,
+            [
+                (ChangeKind::Removed, "$.replicas"),
+                (ChangeKind::Removed, "$.ports[1]"),
+                (ChangeKind::Changed, "$.containers[0].name"),
+                (ChangeKind::Changed, "$.containers[0].image"),
+                (ChangeKind::Changed, "$.containers[1].name"),
+                (ChangeKind::Changed, "$.containers[1].image"),
+                (ChangeKind::Added, "$.containers[2]"),
+                (ChangeKind::Changed, "$.ratio"),
+                (ChangeKind::Changed, "$['a.b']"),
+                (ChangeKind::Added, "$.debug"),
+            ]
+        );
+        assert!(YamlDiff::new(&old, &old).is_empty());
+
+        for change in &diff {
+            let path = YamlPath::compile(&change.path).unwrap();
+            if let Some(old_value) = change.old {
+                assert_eq!(path.query(&old), [old_value]);
+            }
+            if let Some(new_value) = change.new {
+                assert_eq!(path.query(&new), [new_value]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_by_identity() {
+        let old = Yaml::load_single(OLD).unwrap();
+        let new = Yaml::load_single(NEW).unwrap();
+        let diff = DiffOptions::new().identity_key("name").diff(&old, &new);
+        let text = diff.to_string();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "- $.replicas: 2",
+                "- $.ports[1]: 443",
+                "~ $.containers[?(@.name == 'web')].image: nginx:1.25 -> nginx:1.27",
+                "+ $.containers[?(@.name == 'log')]: {name: log, image: fluentd}",
+                "~ $.ratio: 1 -> 1.0",
+                "~ $['a.b']: !custom x -> !custom y",
+                "+ $.debug: true",
+            ]
+        );
+
+        let dup = Yaml::load_single("[{name: a}, {name: a, v: 1}]").unwrap();
+        let other = Yaml::load_single("[{name: a}, {name: a, v: 2}]").unwrap();
+        let diff = DiffOptions::new().identity_key("name").diff(&dup, &other);
+        assert_eq!(diff.changes()[0].path, "$[1].v");
+        assert_eq!(diff.changes()[0].old.and_then(Yaml::as_i64), Some(1));
+    }
+}
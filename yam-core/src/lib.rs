@@ -37,9 +37,14 @@ pub mod escaper;
 #[doc(hidden)]
 pub use lazy_expander::{Expanable, LazyExpander, LazyExpanderMap, LazyExpanderVec};
 
+pub mod diff;
 mod lazy_expander;
+pub mod merge;
 pub mod node;
 pub mod parsing;
+pub mod patch;
 pub mod prelude;
+pub mod query;
 mod saphyr_emitter;
 mod util;
+pub mod visit;
@@ -0,0 +1,410 @@
+use crate::diff::{identities, same};
+use crate::prelude::path::{key_matches, map_value};
+use crate::prelude::{MappingLike, SequenceLike, Span, YamlDocAccess};
+use crate::visit::key_name;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// How [`MergeOptions::merge`] combines a sequence with the same sequence from a later layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SequenceMerge {
+    /// The later sequence replaces the earlier one.
+    #[default]
+    Replace,
+    /// The later sequence's elements are appended to the earlier one.
+    Append,
+    /// Mappings are matched by the value of the given key and deep-merged; unmatched elements
+    /// are appended. Falls back to [`SequenceMerge::Replace`] unless every element of the later
+    /// sequence is a mapping with a distinct scalar value for the key.
+    MergeByKey(String),
+}
+
+///
+/// Options for deep-merging layered documents such as `base.yaml`, `env/prod.yaml` and
+/// `local.yaml`, where each layer overrides the ones before it.
+///
+/// Mappings are always merged key by key. Scalars and mismatched types are replaced by the later
+/// layer. Sequences follow [`SequenceMerge`], and by default a `null` in a later layer deletes
+/// the key it's under.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::merge::{MergeOptions, SequenceMerge};
+/// use yam_core::prelude::{SpannedYaml, YamlDocAccess, YamlLoader};
+///
+/// let base = "server: {port: 80, debug: true}\nplugins: [{name: auth, level: 1}]\n";
+/// let prod = "server:\n  port: 443\n  debug: null\nplugins: [{name: auth, level: 2}]\n";
+/// let layers: Vec<SpannedYaml> = [base, prod]
+///     .iter()
+///     .map(|input| YamlLoader::load_from(input).unwrap().remove(0))
+///     .collect();
+///
+/// let merged = MergeOptions::new()
+///     .sequences(SequenceMerge::MergeByKey("name".into()))
+///     .merge(&layers);
+/// assert_eq!(merged.value.get_path("server.port").unwrap().as_i64(), Some(443));
+/// assert!(merged.value.get_path("server.debug").is_none());
+///
+/// let origin = merged.origin("/plugins/0/level").unwrap();
+/// assert_eq!(origin.layer, 1);
+/// assert_eq!(origin.span.unwrap().start.line, 4);
+/// assert_eq!(merged.origin("/plugins/0").unwrap().layer, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOptions {
+    sequences: SequenceMerge,
+    null_deletes: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            sequences: SequenceMerge::Replace,
+            null_deletes: true,
+        }
+    }
+}
+
+impl MergeOptions {
+    /// Creates the default options: sequences replace, `null` deletes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how sequences from later layers are combined with earlier ones.
+    #[must_use]
+    pub fn sequences(mut self, policy: SequenceMerge) -> Self {
+        self.sequences = policy;
+        self
+    }
+
+    /// Sets whether a `null` mapping value in a later layer deletes the key, or is kept as a
+    /// plain `null` value.
+    #[must_use]
+    pub fn null_deletes(mut self, value: bool) -> Self {
+        self.null_deletes = value;
+        self
+    }
+
+    /// Merges `layers` in order, each overriding the ones before it.
+    ///
+    /// An empty list of layers merges to `null`.
+    pub fn merge<'a, 'input, N>(&self, layers: impl IntoIterator<Item = &'a N>) -> Merged<N>
+    where
+        N: YamlDocAccess<'input> + 'a,
+        N::SequenceNode: SequenceLike<N>,
+        N::MappingNode: MappingLike<N>,
+    {
+        let mut value = N::null();
+        let mut origins = Origins::new();
+        let mut path = String::new();
+        for (layer, node) in layers.into_iter().enumerate() {
+            if layer == 0 {
+                origins.replace(&mut value, &mut path, node, layer);
+            } else {
+                self.merge_node(&mut origins, &mut value, &mut path, node, layer);
+            }
+        }
+        Merged {
+            value,
+            origins: origins.0,
+        }
+    }
+
+    fn merge_node<'input, N>(
+        &self,
+        origins: &mut Origins,
+        target: &mut N,
+        path: &mut String,
+        node: &N,
+        layer: usize,
+    ) where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N>,
+        N::MappingNode: MappingLike<N>,
+    {
+        let len = path.len();
+        if let (Ok(entries), Ok(map)) = (
+            node.as_mapping().map(MappingLike::entries),
+            target.as_mapping_mut(),
+        ) {
+            for entry in entries {
+                push_key(path, &entry.key);
+                if self.null_deletes && entry.value.is_null() {
+                    let index = map.keys().position(|k| same(k, &entry.key));
+                    if let Some(index) = index {
+                        map.remove_at(index);
+                        origins.forget(path);
+                    }
+                } else if let Some(existing) = map.find_value_mut(|k| same(k, &entry.key)) {
+                    self.merge_node(origins, existing, path, &entry.value, layer);
+                } else {
+                    map.push_mapping(entry.key.clone(), entry.value.clone());
+                    origins.record(path, &entry.value, layer);
+                }
+                path.truncate(len);
+            }
+            return;
+        }
+
+        let key = match &self.sequences {
+            SequenceMerge::Replace => None,
+            SequenceMerge::Append => Some(None),
+            SequenceMerge::MergeByKey(key) => Some(Some(key.as_str())),
+        };
+        let (Some(key), Ok(elems), Ok(seq)) = (key, node.as_sequence(), target.as_sequence_mut())
+        else {
+            origins.replace(target, path, node, layer);
+            return;
+        };
+        if key.is_some_and(|key| identities(elems.vec(), key).is_none()) {
+            origins.replace(target, path, node, layer);
+            return;
+        }
+        for elem in elems.elems() {
+            let matched = key.and_then(|key| {
+                let id = map_value(elem, |k| key_matches(k, key));
+                seq.elems().position(|existing| {
+                    map_value(existing, |k| key_matches(k, key))
+                        .zip(id)
+                        .is_some_and(|(a, b)| same(a, b))
+                })
+            });
+            let index = matched.unwrap_or(seq.elem_count());
+            let _ = write!(path, "/{index}");
+            match matched.and_then(|index| seq.elem_mut(index)) {
+                Some(existing) => self.merge_node(origins, existing, path, elem, layer),
+                None => {
+                    seq.push_elem(elem.clone());
+                    origins.record(path, elem, layer);
+                }
+            }
+            path.truncate(len);
+        }
+    }
+}
+
+/// Origins of the nodes in a tree being merged, keyed by JSON pointer.
+struct Origins(BTreeMap<String, Origin>);
+
+impl Origins {
+    fn new() -> Self {
+        Origins(BTreeMap::new())
+    }
+
+    /// Replaces `target`, found at `path`, with a copy of `node` from `layer`.
+    fn replace<'input, N>(&mut self, target: &mut N, path: &mut String, node: &N, layer: usize)
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N>,
+        N::MappingNode: MappingLike<N>,
+    {
+        *target = node.clone();
+        self.forget(path);
+        self.record(path, node, layer);
+    }
+
+    /// Records `layer` as the origin of `node`, placed at `path`, and everything below it.
+    fn record<'input, N>(&mut self, path: &mut String, node: &N, layer: usize)
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N>,
+        N::MappingNode: MappingLike<N>,
+    {
+        let origin = Origin {
+            layer,
+            span: node.source_span(),
+        };
+        self.0.insert(path.clone(), origin);
+        let len = path.len();
+        if let Ok(map) = node.as_mapping() {
+            for entry in map.entries() {
+                push_key(path, &entry.key);
+                self.record(path, &entry.value, layer);
+                path.truncate(len);
+            }
+        } else if let Ok(seq) = node.as_sequence() {
+            for (i, elem) in seq.elems().enumerate() {
+                let _ = write!(path, "/{i}");
+                self.record(path, elem, layer);
+                path.truncate(len);
+            }
+        }
+    }
+
+    /// Removes the origins of the node at `path` and everything below it.
+    fn forget(&mut self, path: &str) {
+        self.0.remove(path);
+        let prefix = format!("{path}/");
+        let below: Vec<String> = self
+            .0
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in below {
+            self.0.remove(&key);
+        }
+    }
+}
+
+/// Where a value in a [`Merged`] tree came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Origin {
+    /// Index of the layer the value was taken from.
+    pub layer: usize,
+    /// The value's span in that layer, if the node type records spans.
+    pub span: Option<Span>,
+}
+
+///
+/// The result of [`MergeOptions::merge`]: the merged tree, plus the [`Origin`] of every node in it.
+///
+/// Origins are keyed by JSON pointer into [`Merged::value`], with keys that are neither strings
+/// nor integers written in flow style. A mapping or sequence that several
+/// layers contributed to keeps the origin of the layer that introduced it, while the values
+/// inside it record the layer that last set them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Merged<N> {
+    /// The merged tree.
+    pub value: N,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl<N> Merged<N> {
+    /// Returns where the node at `pointer` came from, or `None` if there's no node there.
+    #[must_use]
+    pub fn origin(&self, pointer: &str) -> Option<&Origin> {
+        self.origins.get(pointer)
+    }
+
+    /// Iterates over the origin of every node in the merged tree, ordered by JSON pointer.
+    pub fn origins(&self) -> impl Iterator<Item = (&str, &Origin)> {
+        self.origins
+            .iter()
+            .map(|(path, origin)| (path.as_str(), origin))
+    }
+}
+
+/// Appends `key` to a JSON pointer, escaped.
+fn push_key<'input, N>(path: &mut String, key: &N)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    path.push('/');
+    path.push_str(&key_name(key).replace('~', "~0").replace('/', "~1"));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::merge::{MergeOptions, SequenceMerge};
+    use crate::prelude::{Marker, SpannedYaml, Yaml, YamlDocAccess, YamlLoader};
+    use alloc::vec::Vec;
+
+    const BASE: &str = "
+name: app
+server: {port: 80, debug: true, tls: {cert: a.pem}}
+hosts: [a, b]
+plugins:
+  - {name: auth, level: 1}
+  - {name: log, level: 1}
+";
+
+    const PROD: &str = "
+server: {port: 443, debug: null, tls: null}
+hosts: [c]
+plugins:
+  - {name: log, level: 3}
+  - {name: cache}
+";
+
+    const LOCAL: &str = "
+server: {debug: false}
+plugins: [{name: auth, level: 9}]
+";
+
+    fn layers() -> Vec<Yaml<'static>> {
+        [BASE, PROD, LOCAL]
+            .iter()
+            .map(|input| Yaml::load_single(input).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let layers = layers();
+        let merged = MergeOptions::new().merge(&layers);
+        let expected = Yaml::load_single(
+            "{name: app, server: {port: 443, debug: false}, hosts: [c], plugins: [{name: auth, level: 9}]}",
+        )
+        .unwrap();
+        assert_eq!(merged.value, expected);
+
+        let merged = MergeOptions::new()
+            .sequences(SequenceMerge::Append)
+            .null_deletes(false)
+            .merge(&layers[..2]);
+        assert_eq!(
+            merged.value["hosts"],
+            Yaml::load_single("[a, b, c]").unwrap()
+        );
+        assert_eq!(merged.value["plugins"].sequence().len(), 4);
+        assert!(merged.value["server"]["tls"].is_null());
+
+        let merged = MergeOptions::new()
+            .sequences(SequenceMerge::MergeByKey("name".into()))
+            .merge(&layers);
+        let plugins = "[{name: auth, level: 9}, {name: log, level: 3}, {name: cache}]";
+        assert_eq!(merged.value["plugins"], Yaml::load_single(plugins).unwrap());
+        assert_eq!(merged.value["hosts"], Yaml::load_single("[c]").unwrap());
+
+        let unkeyed = Yaml::load_single("plugins: [{level: 9}]").unwrap();
+        let merged = MergeOptions::new()
+            .sequences(SequenceMerge::MergeByKey("name".into()))
+            .merge([&layers[0], &unkeyed]);
+        assert_eq!(merged.value["plugins"], unkeyed["plugins"]);
+
+        assert!(MergeOptions::new().merge::<Yaml>([]).value.is_null());
+    }
+
+    #[test]
+    fn test_merge_provenance() {
+        let layers: Vec<SpannedYaml> = [BASE, PROD, LOCAL]
+            .iter()
+            .map(|input| YamlLoader::load_from(input).unwrap().remove(0))
+            .collect();
+        let merged = MergeOptions::new()
+            .sequences(SequenceMerge::MergeByKey("name".into()))
+            .merge(&layers);
+
+        let layer = |pointer| merged.origin(pointer).map(|origin| origin.layer);
+        assert_eq!(layer(""), Some(0));
+        assert_eq!(layer("/name"), Some(0));
+        assert_eq!(layer("/server"), Some(0));
+        assert_eq!(layer("/server/port"), Some(1));
+        assert_eq!(layer("/server/debug"), Some(2));
+        assert_eq!(layer("/server/tls"), None);
+        assert_eq!(layer("/server/tls/cert"), None);
+        assert_eq!(layer("/hosts"), Some(1));
+        assert_eq!(layer("/hosts/0"), Some(1));
+        assert_eq!(layer("/hosts/1"), None);
+        assert_eq!(layer("/plugins/0/name"), Some(2));
+        assert_eq!(layer("/plugins/0/level"), Some(2));
+        assert_eq!(layer("/plugins/1/level"), Some(1));
+        assert_eq!(layer("/plugins/2"), Some(1));
+
+        let debug = merged.origin("/server/debug").unwrap();
+        assert_eq!(debug.span.unwrap().start, Marker::new(17, 17, 2));
+        for (pointer, origin) in merged.origins() {
+            let node = merged.value.pointer(pointer).unwrap();
+            assert_eq!(node.span, origin.span.unwrap(), "{pointer}");
+        }
+    }
+}
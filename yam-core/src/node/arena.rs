@@ -0,0 +1,726 @@
+use crate::parsing::{Event, Parser, ScalarValue, SpannedEventReceiver};
+use crate::prelude::{Marker, NodeType, ScalarType, Schema, Span, Tag, YamlError, YamlScalar};
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::ops::Index;
+
+///
+/// Index of a node in a [`YamlArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// Position of the node in [`YamlArena`].
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+///
+/// The children of a collection, stored next to each other in [`YamlArena`]. Mappings store
+/// each key followed by its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Children {
+    start: u32,
+    len: u32,
+}
+
+///
+/// Value of an [`ArenaNode`]. Collections refer to their children by [`NodeId`], see
+/// [`YamlArena::children`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaData<'input> {
+    /// Bad value encountered during loading, e.g. an alias that couldn't be resolved.
+    BadValue,
+    /// Scalar value, resolved with the schema of its document.
+    Scalar(YamlScalar<'input>),
+    /// Sequence of nodes.
+    Sequence(Children),
+    /// Mapping, stored as alternating keys and values.
+    Mapping(Children),
+    /// Node tagged with a [`Tag`] outside the YAML core schema.
+    Tagged(Cow<'input, Tag>, NodeId),
+}
+
+///
+/// A node of a [`YamlArena`] with its position in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaNode<'input> {
+    pub data: ArenaData<'input>,
+    pub span: Span,
+}
+
+///
+/// Documents whose nodes are all stored in a single [`Vec`] and refer to each other by
+/// [`NodeId`].
+///
+/// Loading a [`Yaml`](crate::prelude::Yaml) allocates a `Vec` for every collection and copies
+/// the anchored node for every alias. A `YamlArena` only grows a few vectors, and an alias is
+/// the [`NodeId`] of its anchored node, so the same node can appear more than once in a document.
+///
+/// # Example
+/// ```rust
+/// use yam_core::prelude::YamlArena;
+///
+/// let arena = YamlArena::load_from("base: &b {x: 1}\nitems: [*b, *b]").unwrap();
+/// let doc = arena.document(0).unwrap();
+/// assert_eq!(doc.get_key("base").and_then(|b| b.get_key("x")).and_then(|x| x.as_i64()), Some(1));
+///
+/// let items = doc.get_key("items").unwrap();
+/// assert_eq!(items.len(), 2);
+/// assert_eq!(items.get(0).unwrap().id(), items.get(1).unwrap().id());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct YamlArena<'input> {
+    nodes: Vec<ArenaNode<'input>>,
+    children: Vec<NodeId>,
+    documents: Vec<NodeId>,
+}
+
+impl<'input> YamlArena<'input> {
+    ///
+    /// Loads all documents of `input` with an [`ArenaLoader`] using its default options.
+    ///
+    /// # Errors
+    /// Returns a [`YamlError`] if `input` isn't valid YAML or an alias can't be resolved.
+    pub fn load_from(input: &'input str) -> Result<Self, YamlError> {
+        let mut loader = ArenaLoader::default();
+        Parser::new_from_str(input).load(&mut loader, true)?;
+        loader.into_arena()
+    }
+
+    /// Number of nodes in the arena, in all documents.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the arena has no nodes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Root node of each document.
+    pub fn documents(&self) -> impl ExactSizeIterator<Item = ArenaRef<'_, 'input>> {
+        self.documents.iter().map(|&id| self.node(id))
+    }
+
+    /// Root node of the document at `index`.
+    #[must_use]
+    pub fn document(&self, index: usize) -> Option<ArenaRef<'_, 'input>> {
+        self.documents.get(index).map(|&id| self.node(id))
+    }
+
+    ///
+    /// Returns the node `id`.
+    ///
+    /// # Panics
+    /// Panics if `id` is not a node of this arena.
+    #[must_use]
+    pub fn node(&self, id: NodeId) -> ArenaRef<'_, 'input> {
+        assert!(id.index() < self.nodes.len(), "{id:?} is not in the arena");
+        ArenaRef { arena: self, id }
+    }
+
+    /// Children of a collection.
+    #[must_use]
+    pub fn children(&self, children: Children) -> &[NodeId] {
+        let start = children.start as usize;
+        &self.children[start..start + children.len as usize]
+    }
+
+    fn push(&mut self, data: ArenaData<'input>, span: Span) -> NodeId {
+        let id = u32::try_from(self.nodes.len()).expect("too many nodes for a YamlArena");
+        self.nodes.push(ArenaNode { data, span });
+        NodeId(id)
+    }
+}
+
+impl<'input> Index<NodeId> for YamlArena<'input> {
+    type Output = ArenaNode<'input>;
+
+    fn index(&self, index: NodeId) -> &Self::Output {
+        &self.nodes[index.index()]
+    }
+}
+
+///
+/// A node of a [`YamlArena`], with accessors similar to
+/// [`YamlDocAccess`](crate::prelude::YamlDocAccess).
+#[derive(Clone, Copy)]
+pub struct ArenaRef<'a, 'input> {
+    arena: &'a YamlArena<'input>,
+    id: NodeId,
+}
+
+impl Debug for ArenaRef<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArenaRef")
+            .field("id", &self.id)
+            .field("data", self.data())
+            .finish()
+    }
+}
+
+impl<'a, 'input> ArenaRef<'a, 'input> {
+    #[must_use]
+    pub fn id(self) -> NodeId {
+        self.id
+    }
+
+    #[must_use]
+    pub fn data(self) -> &'a ArenaData<'input> {
+        &self.arena[self.id].data
+    }
+
+    #[must_use]
+    pub fn span(self) -> Span {
+        self.arena[self.id].span
+    }
+
+    #[must_use]
+    pub fn get_type(self) -> NodeType {
+        match self.data() {
+            ArenaData::Mapping(_) => NodeType::Mapping,
+            ArenaData::Sequence(_) => NodeType::Sequence,
+            ArenaData::Scalar(YamlScalar::Bool(_)) => NodeType::Bool,
+            ArenaData::Scalar(YamlScalar::Integer(_)) => NodeType::Integer,
+            ArenaData::Scalar(YamlScalar::FloatingPoint(_)) => NodeType::Floating,
+            ArenaData::Scalar(YamlScalar::String(_)) => NodeType::String,
+            ArenaData::Scalar(YamlScalar::Null(_)) => NodeType::Null,
+            ArenaData::BadValue | ArenaData::Tagged(..) => NodeType::Bad,
+        }
+    }
+
+    #[must_use]
+    pub fn is_bad_value(self) -> bool {
+        matches!(self.data(), ArenaData::BadValue)
+    }
+
+    #[must_use]
+    pub fn is_null(self) -> bool {
+        self.get_type() == NodeType::Null
+    }
+
+    #[must_use]
+    pub fn is_sequence(self) -> bool {
+        self.get_type() == NodeType::Sequence
+    }
+
+    #[must_use]
+    pub fn is_mapping(self) -> bool {
+        self.get_type() == NodeType::Mapping
+    }
+
+    #[must_use]
+    pub fn as_bool(self) -> Option<bool> {
+        match self.data() {
+            ArenaData::Scalar(YamlScalar::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_i64(self) -> Option<i64> {
+        match self.data() {
+            ArenaData::Scalar(YamlScalar::Integer(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_f64(self) -> Option<f64> {
+        match self.data() {
+            ArenaData::Scalar(YamlScalar::FloatingPoint(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> Option<&'a str> {
+        match self.data() {
+            ArenaData::Scalar(YamlScalar::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Tag of a [`ArenaData::Tagged`] node.
+    #[must_use]
+    pub fn get_tag(self) -> Option<&'a Tag> {
+        match self.data() {
+            ArenaData::Tagged(tag, _) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// The node a [`ArenaData::Tagged`] node wraps, or `self` for other nodes.
+    #[must_use]
+    pub fn untagged(self) -> Self {
+        match self.data() {
+            ArenaData::Tagged(_, id) => self.arena.node(*id),
+            _ => self,
+        }
+    }
+
+    /// Number of items of a sequence or entries of a mapping, `0` for other nodes.
+    #[must_use]
+    pub fn len(self) -> usize {
+        match self.data() {
+            ArenaData::Sequence(children) => children.len as usize,
+            ArenaData::Mapping(children) => children.len as usize / 2,
+            _ => 0,
+        }
+    }
+
+    /// Whether [`ArenaRef::len`] is `0`.
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items of a sequence, none for other nodes.
+    pub fn iter(self) -> impl ExactSizeIterator<Item = ArenaRef<'a, 'input>> {
+        let children = match self.data() {
+            ArenaData::Sequence(children) => self.arena.children(*children),
+            _ => &[],
+        };
+        children.iter().map(move |&id| self.arena.node(id))
+    }
+
+    /// Keys and values of a mapping, none for other nodes.
+    pub fn entries(
+        self,
+    ) -> impl ExactSizeIterator<Item = (ArenaRef<'a, 'input>, ArenaRef<'a, 'input>)> {
+        let children = match self.data() {
+            ArenaData::Mapping(children) => self.arena.children(*children),
+            _ => &[],
+        };
+        children
+            .chunks_exact(2)
+            .map(move |pair| (self.arena.node(pair[0]), self.arena.node(pair[1])))
+    }
+
+    /// Item `index` of a sequence, or the value of the integer key `index` of a mapping.
+    #[must_use]
+    pub fn get(self, index: usize) -> Option<Self> {
+        if self.is_sequence() {
+            return self.iter().nth(index);
+        }
+        let index = i64::try_from(index).ok()?;
+        self.entries()
+            .find(|(key, _)| key.as_i64() == Some(index))
+            .map(|(_, value)| value)
+    }
+
+    /// Value of the string key `key` of a mapping.
+    #[must_use]
+    pub fn get_key(self, key: &str) -> Option<Self> {
+        self.entries()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, value)| value)
+    }
+}
+
+/// A collection whose children are still being loaded.
+struct OpenCollection<'input> {
+    mapping: bool,
+    /// Position of its first child in [`ArenaLoader::pending`].
+    first: usize,
+    start: Marker,
+    anchor_id: usize,
+    tag: Option<Cow<'input, Tag>>,
+    /// Whether the next value belongs to a `<<` merge key.
+    merge_pending: bool,
+    merges: Vec<NodeId>,
+}
+
+///
+/// Loads events into a [`YamlArena`].
+///
+/// It supports the same schema, merge key and alias options as
+/// [`YamlLoader`](crate::prelude::YamlLoader), but not tag resolvers or the standard tags.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::Parser;
+/// use yam_core::prelude::{ArenaLoader, Schema};
+///
+/// let mut parser = Parser::new_from_str("base: &b {x: 1}\nuse: {<<: *b, enabled: yes}");
+/// let mut loader = ArenaLoader::default().schema(Schema::Yaml11);
+/// parser.load(&mut loader, true).unwrap();
+///
+/// let arena = loader.into_arena().unwrap();
+/// let doc = arena.document(0).unwrap().get_key("use").unwrap();
+/// assert_eq!(doc.get_key("x").unwrap().as_i64(), Some(1));
+/// assert_eq!(doc.get_key("enabled").unwrap().as_bool(), Some(true));
+/// ```
+pub struct ArenaLoader<'input> {
+    arena: YamlArena<'input>,
+    stack: Vec<OpenCollection<'input>>,
+    /// Children of the open collections, moved to the arena once a collection ends.
+    pending: Vec<NodeId>,
+    root: Option<NodeId>,
+    anchors: BTreeMap<usize, NodeId>,
+    allow_undefined_aliases: bool,
+    schema: Schema,
+    detect_schema: bool,
+    doc_schema: Schema,
+    error: Option<YamlError>,
+}
+
+impl Default for ArenaLoader<'_> {
+    fn default() -> Self {
+        Self {
+            arena: YamlArena::default(),
+            stack: Vec::new(),
+            pending: Vec::new(),
+            root: None,
+            anchors: BTreeMap::new(),
+            allow_undefined_aliases: false,
+            schema: Schema::Core,
+            detect_schema: false,
+            doc_schema: Schema::Core,
+            error: None,
+        }
+    }
+}
+
+impl<'input> ArenaLoader<'input> {
+    /// See [`YamlLoader::allow_undefined_aliases`](crate::prelude::YamlLoader::allow_undefined_aliases).
+    #[must_use]
+    pub fn allow_undefined_aliases(mut self, value: bool) -> Self {
+        self.allow_undefined_aliases = value;
+        self
+    }
+
+    /// See [`YamlLoader::schema`](crate::prelude::YamlLoader::schema).
+    #[must_use]
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = schema;
+        self.doc_schema = schema;
+        self
+    }
+
+    /// See [`YamlLoader::detect_schema`](crate::prelude::YamlLoader::detect_schema).
+    #[must_use]
+    pub fn detect_schema(mut self, value: bool) -> Self {
+        self.detect_schema = value;
+        self
+    }
+
+    ///
+    /// Returns the documents loaded so far.
+    ///
+    /// # Errors
+    /// Returns the first error encountered while loading, e.g. a [`YamlError::UndefinedAlias`].
+    pub fn into_arena(self) -> Result<YamlArena<'input>, YamlError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.arena),
+        }
+    }
+
+    fn record_error(&mut self, err: YamlError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+
+    fn tagged(&mut self, id: NodeId, tag: Option<Cow<'input, Tag>>, span: Span) -> NodeId {
+        match tag {
+            Some(tag) if !tag.is_yaml_core_schema() => {
+                self.arena.push(ArenaData::Tagged(tag, id), span)
+            }
+            _ => id,
+        }
+    }
+
+    fn insert_new_node(&mut self, id: NodeId, anchor_id: usize) {
+        if anchor_id > 0 {
+            self.anchors.insert(anchor_id, id);
+        }
+        match self.stack.last_mut() {
+            Some(parent) if parent.merge_pending => {
+                parent.merge_pending = false;
+                parent.merges.push(id);
+            }
+            Some(_) => self.pending.push(id),
+            None => self.root = Some(id),
+        }
+    }
+
+    fn is_merge_key(&self, value: &str, scalar_type: ScalarType, tag: Option<&Cow<Tag>>) -> bool {
+        self.doc_schema == Schema::Yaml11
+            && value == "<<"
+            && scalar_type == ScalarType::Plain
+            && tag.is_none()
+            && self.stack.last().is_some_and(|open| {
+                open.mapping && (self.pending.len() - open.first).is_multiple_of(2)
+            })
+    }
+
+    /// Adds the entries of the values merged with `<<` to the pending entries of a mapping,
+    /// skipping keys it already has. Entries of earlier merged mappings take precedence.
+    fn apply_merges(&mut self, first: usize, merges: &[NodeId], mark: Marker) {
+        for &merge in merges {
+            let merge = self.arena.node(merge);
+            let sources: Vec<ArenaRef> = if merge.is_mapping() {
+                alloc::vec![merge]
+            } else if merge.is_sequence() && merge.iter().all(ArenaRef::is_mapping) {
+                merge.iter().collect()
+            } else {
+                self.record_error(YamlError::ResolveErr {
+                    mark,
+                    info: String::from(
+                        "merge key value must be a mapping or a sequence of mappings",
+                    ),
+                });
+                continue;
+            };
+            let mut entries = Vec::new();
+            for (key, value) in sources.into_iter().flat_map(ArenaRef::entries) {
+                let exists = self.pending[first..]
+                    .chunks_exact(2)
+                    .chain(entries.chunks_exact(2))
+                    .any(|pair| same_scalar_key(self.arena.node(pair[0]), key));
+                if !exists {
+                    entries.extend([key.id(), value.id()]);
+                }
+            }
+            self.pending.extend(entries);
+        }
+    }
+}
+
+impl<'input> SpannedEventReceiver<'input> for ArenaLoader<'input> {
+    fn on_event(&mut self, ev: Event<'input>, span: Span) {
+        let mark = span.start;
+        let mapping = matches!(ev, Event::MappingStart(..));
+        match ev {
+            Event::DocumentStart(_, directives) => {
+                self.doc_schema = directives
+                    .version
+                    .filter(|_| self.detect_schema)
+                    .and_then(Schema::from_version)
+                    .unwrap_or(self.schema);
+            }
+            Event::Nothing | Event::StreamStart | Event::StreamEnd | Event::Comment(_) => {
+                // do nothing
+            }
+            Event::DocumentEnd => {
+                let root = match self.root.take() {
+                    Some(root) => root,
+                    // empty document
+                    None => self.arena.push(ArenaData::BadValue, span),
+                };
+                self.arena.documents.push(root);
+            }
+            Event::SequenceStart(anchor_id, _, tag, _)
+            | Event::MappingStart(anchor_id, _, tag, _) => {
+                self.stack.push(OpenCollection {
+                    mapping,
+                    first: self.pending.len(),
+                    start: mark,
+                    anchor_id,
+                    tag,
+                    merge_pending: false,
+                    merges: Vec::new(),
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                let open = self.stack.pop().unwrap();
+                if !open.merges.is_empty() {
+                    self.apply_merges(open.first, &open.merges, mark);
+                }
+                let children = Children {
+                    start: u32::try_from(self.arena.children.len())
+                        .expect("too many nodes for a YamlArena"),
+                    len: u32::try_from(self.pending.len() - open.first)
+                        .expect("too many nodes for a YamlArena"),
+                };
+                self.arena.children.extend(self.pending.drain(open.first..));
+                let data = if open.mapping {
+                    ArenaData::Mapping(children)
+                } else {
+                    ArenaData::Sequence(children)
+                };
+                let span = Span::new(open.start, span.end);
+                let id = self.arena.push(data, span);
+                let id = self.tagged(id, open.tag, span);
+                self.insert_new_node(id, open.anchor_id);
+            }
+            Event::Scalar(ScalarValue {
+                value,
+                scalar_type,
+                anchor_id,
+                tag,
+                ..
+            }) => {
+                if self.is_merge_key(&value, scalar_type, tag.as_ref()) {
+                    if anchor_id > 0 {
+                        let info = String::from("a merge key can't have an anchor");
+                        self.record_error(YamlError::ResolveErr { mark, info });
+                    }
+                    self.stack.last_mut().unwrap().merge_pending = true;
+                    return;
+                }
+                let core_tag = tag.clone().filter(|tag| tag.is_yaml_core_schema());
+                let data = YamlScalar::parse_from_cow_and_metadata_with_schema(
+                    value,
+                    scalar_type,
+                    core_tag,
+                    self.doc_schema,
+                )
+                .map_or(ArenaData::BadValue, ArenaData::Scalar);
+                let id = self.arena.push(data, span);
+                let id = self.tagged(id, tag, span);
+                self.insert_new_node(id, anchor_id);
+            }
+            Event::Alias(anchor_id, name) => {
+                let id = match self.anchors.get(&anchor_id) {
+                    Some(&id) => id,
+                    None => {
+                        if !self.allow_undefined_aliases {
+                            self.record_error(YamlError::UndefinedAlias {
+                                mark,
+                                name: name.into_owned(),
+                            });
+                        }
+                        self.arena.push(ArenaData::BadValue, span)
+                    }
+                };
+                self.insert_new_node(id, 0);
+            }
+        }
+    }
+}
+
+/// Whether two keys are equal scalars.
+fn same_scalar_key(a: ArenaRef, b: ArenaRef) -> bool {
+    match (a.data(), b.data()) {
+        (ArenaData::Scalar(a), ArenaData::Scalar(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parsing::Parser;
+    use crate::prelude::{
+        ArenaData, ArenaLoader, ArenaRef, Marker, Schema, Yaml, YamlArena, YamlData, YamlDocAccess,
+        YamlError, YamlLoader,
+    };
+
+    /// Checks that `node` holds the same values as `yaml`.
+    fn assert_same(node: ArenaRef, yaml: &Yaml) {
+        match &yaml.0 {
+            YamlData::Sequence(items) => {
+                assert_eq!(node.len(), items.len());
+                for (node, yaml) in node.iter().zip(items) {
+                    assert_same(node, yaml);
+                }
+            }
+            YamlData::Mapping(entries) => {
+                assert_eq!(node.len(), entries.len());
+                for ((key, value), entry) in node.entries().zip(entries) {
+                    assert_same(key, &entry.key);
+                    assert_same(value, &entry.value);
+                }
+            }
+            YamlData::Tagged(tag, yaml) => {
+                assert_eq!(node.get_tag(), Some(&**tag));
+                assert_same(node.untagged(), yaml);
+            }
+            YamlData::Scalar(scalar) => {
+                assert_eq!(node.data(), &ArenaData::Scalar(scalar.clone()));
+            }
+            _ => assert_eq!(node.is_bad_value(), yaml.is_bad_value()),
+        }
+    }
+
+    #[test]
+    fn test_arena_matches_yaml() {
+        let input = "
+a: &a [1, 2.5, true, ~, text]
+b: *a
+c: !custom {x: !!str 1, y: !local z}
+'d': [{e: f}, [], {}]
+--- plain
+---
+";
+        let arena = YamlArena::load_from(input).unwrap();
+        let docs = YamlLoader::<Yaml>::load_from(input).unwrap();
+        assert_eq!(arena.documents().len(), docs.len());
+        for (node, yaml) in arena.documents().zip(&docs) {
+            assert_same(node, yaml);
+        }
+
+        let doc = arena.document(0).unwrap();
+        assert_eq!(
+            doc.get_key("a").unwrap().id(),
+            doc.get_key("b").unwrap().id()
+        );
+        assert_eq!(
+            doc.get_key("a").unwrap().get(1).unwrap().as_f64(),
+            Some(2.5)
+        );
+        assert_eq!(
+            doc.get_key("c").unwrap().span().start,
+            Marker::new(48, 12, 4)
+        );
+        assert!(doc.get_key("missing").is_none());
+    }
+
+    #[test]
+    fn test_arena_merge_keys() {
+        let input = "
+base: &base {a: 1, b: 2}
+other: &other {b: 3, c: 4}
+list:
+  <<: [*base, *other]
+  d: 6
+";
+        let mut parser = Parser::new_from_str(input);
+        let mut loader = ArenaLoader::default().schema(Schema::Yaml11);
+        parser.load(&mut loader, true).unwrap();
+        let arena = loader.into_arena().unwrap();
+
+        let mut parser = Parser::new_from_str(input);
+        let mut loader = YamlLoader::<Yaml>::default().schema(Schema::Yaml11);
+        parser.load(&mut loader, true).unwrap();
+        let docs = loader.into_documents().unwrap();
+        assert_same(arena.document(0).unwrap(), &docs[0]);
+        assert_eq!(arena.document(0).unwrap().get_key("list").unwrap().len(), 4);
+
+        for (input, info) in [
+            (
+                "{<<: [1]}",
+                "merge key value must be a mapping or a sequence of mappings",
+            ),
+            ("{&m <<: {a: 1}}", "a merge key can't have an anchor"),
+        ] {
+            let mut parser = Parser::new_from_str(input);
+            let mut loader = ArenaLoader::default().schema(Schema::Yaml11);
+            parser.load(&mut loader, true).unwrap();
+            let err = loader.into_arena().unwrap_err();
+            assert!(matches!(err, YamlError::ResolveErr { info: i, .. } if i == info));
+        }
+    }
+
+    #[test]
+    fn test_arena_undefined_alias() {
+        let err = YamlArena::load_from("&a [*a]").unwrap_err();
+        assert!(matches!(err, YamlError::UndefinedAlias { name, .. } if name == "a"));
+
+        let mut parser = Parser::new_from_str("&a [*a]");
+        let mut loader = ArenaLoader::default().allow_undefined_aliases(true);
+        parser.load(&mut loader, true).unwrap();
+        let arena = loader.into_arena().unwrap();
+        assert!(arena.document(0).unwrap().get(0).unwrap().is_bad_value());
+    }
+}
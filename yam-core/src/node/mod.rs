@@ -1,12 +1,23 @@
 //! Import this module to use various `yam_core` nodes.
+pub use arena::{ArenaData, ArenaLoader, ArenaNode, ArenaRef, Children, NodeId, YamlArena};
+pub use ord_float::OrdFloat;
+pub use presentation::Presentation;
 pub use scalar::YamlScalar;
 pub use scalar::parse_i64_from_cow;
+pub use schema::Schema;
+pub use schema::TagResolver;
 pub use spanned_yaml::SpannedYaml;
-pub use yaml::Yaml;
+pub use timestamp::Timestamp;
+pub use yaml::{NodeName, Yaml};
 pub use yaml_data::YamlData;
 
+pub(crate) mod arena;
+pub(crate) mod ord_float;
+pub(crate) mod presentation;
 pub(crate) mod scalar;
+pub(crate) mod schema;
 pub(crate) mod spanned_yaml;
+pub(crate) mod timestamp;
 pub(crate) mod yaml;
 pub(crate) mod yaml_data;
 pub mod yaml_owned;
@@ -0,0 +1,153 @@
+use crate::prelude::ToMut;
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
+
+///
+/// An `f64` with a total order, so that nodes using it as their float type implement [`Eq`],
+/// [`Ord`] and [`Hash`], e.g. `Yaml<'_, OrdFloat>`.
+///
+/// - All NaNs are equal to each other and greater than every other value, including infinity.
+/// - `-0.0` and `0.0` are equal and hash the same.
+/// - Everything else compares like `f64`.
+///
+/// Node types keep integers and floats apart: `1` and `1.0` are different values, and in a
+/// [`YamlScalar`](crate::node::YamlScalar) every integer sorts before every float.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::{OrdFloat, Yaml, YamlDocAccess, YamlLoader};
+/// use std::collections::{BTreeSet, HashSet};
+///
+/// let input = "[.nan, 0.0, -0.0, .NaN, 1, 1.0]";
+/// let items = YamlLoader::<Yaml<OrdFloat>>::load_single(input).unwrap().into_sequence().unwrap();
+///
+/// let unique: HashSet<_> = items.iter().collect();
+/// assert_eq!(unique.len(), 4);
+///
+/// let sorted: Vec<_> = items.iter().collect::<BTreeSet<_>>().into_iter().collect();
+/// assert_eq!(sorted[0].as_i64(), Some(1));
+/// assert_eq!(sorted[1].as_f64(), Some(0.0));
+/// assert_eq!(sorted[2].as_f64(), Some(1.0));
+/// assert!(sorted[3].as_f64().unwrap().is_nan());
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrdFloat(pub f64);
+
+impl Ord for OrdFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal),
+        }
+    }
+}
+
+impl PartialOrd for OrdFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OrdFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrdFloat {}
+
+impl Hash for OrdFloat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let bits = if self.0.is_nan() {
+            f64::NAN.to_bits()
+        } else if self.0 == 0.0 {
+            0
+        } else {
+            self.0.to_bits()
+        };
+        bits.hash(state);
+    }
+}
+
+impl Display for OrdFloat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<f64> for OrdFloat {
+    fn from(value: f64) -> Self {
+        OrdFloat(value)
+    }
+}
+
+impl From<OrdFloat> for f64 {
+    fn from(value: OrdFloat) -> Self {
+        value.0
+    }
+}
+
+impl ToMut<f64> for OrdFloat {
+    #[inline]
+    fn as_mut_val(&mut self) -> &mut f64 {
+        &mut self.0
+    }
+
+    #[inline]
+    fn as_owned(&self) -> f64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{OrdFloat, Yaml, YamlData, YamlDocAccess, YamlLoader, YamlScalar};
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_ord_float() {
+        let mut floats = [2.0, f64::NAN, -0.0, f64::INFINITY, 0.0, -f64::NAN].map(OrdFloat);
+        floats.sort();
+        assert_eq!(floats[0], OrdFloat(-0.0));
+        assert_eq!(floats[1], OrdFloat(0.0));
+        assert_eq!(floats[3], OrdFloat(f64::INFINITY));
+        assert!(floats[4].0.is_nan() && floats[5].0.is_nan());
+        assert_eq!(OrdFloat(-0.0), OrdFloat(0.0));
+        assert!(OrdFloat(f64::NAN) > OrdFloat(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_ord_yaml() {
+        let load = |input: &str| {
+            let doc = YamlLoader::<Yaml<OrdFloat>>::load_single(input).unwrap();
+            doc.into_sequence().unwrap()
+        };
+        let items = load("[b, 2.0, null, {k: v}, 1, [x], 2, true, a, 1, {k: v}, .nan, .NaN]");
+        let sorted: Vec<_> = items
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            sorted,
+            load("[null, true, 1, 2, 2.0, .nan, a, b, [x], {k: v}]")
+        );
+
+        let complex = "{[a, b]: 1, {c: d}: 2, 1.5: 3}";
+        let doc = YamlLoader::<Yaml<OrdFloat>>::load_single(complex).unwrap();
+        let entries = doc.into_mapping().unwrap();
+        let by_key: BTreeMap<_, _> = entries.into_iter().map(|e| (e.key, e.value)).collect();
+        let key = Yaml(
+            YamlData::Scalar(YamlScalar::FloatingPoint(OrdFloat(1.5))),
+            None,
+        );
+        assert_eq!(by_key[&key].as_i64(), Some(3));
+        let key = &load("[[a, b]]")[0];
+        assert_eq!(by_key[key].as_i64(), Some(1));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::parsing::BlockIndicators;
+use crate::prelude::{CollectionStyle, ScalarType};
+
+///
+/// Describes how a node was written in the source, as opposed to what it contains.
+///
+/// Formatters and round-trippers can use it to preserve the author's style. It is retained by
+/// [`SpannedYaml`](crate::node::SpannedYaml), while [`Yaml`](crate::node::Yaml) drops it.
+///
+/// # Fields
+/// - `scalar_type`: The style a scalar was written in (plain, quoted, literal or folded).
+///   Always [`ScalarType::Plain`] for collections.
+/// - `collection_style`: Whether a collection was written in block or flow style (`[a, b]` or
+///   `{a: b}`). Always [`CollectionStyle::Block`] for scalars.
+/// - `block_indicators`: Chomping and indentation indicators of a block scalar (`|` and `>`).
+///
+/// # Example
+/// ```rust
+/// use yam_core::prelude::{CollectionStyle, ScalarType, SpannedYaml, YamlDocAccess, YamlLoader};
+///
+/// let docs = YamlLoader::<SpannedYaml>::load_from("a: 'x'\nb: [1]").unwrap();
+/// let entries = docs[0].mapping();
+/// assert_eq!(entries[0].value.presentation.scalar_type, ScalarType::SingleQuote);
+/// assert_eq!(entries[1].value.presentation.collection_style, CollectionStyle::Flow);
+/// assert_eq!(docs[0].presentation.collection_style, CollectionStyle::Block);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Presentation {
+    pub scalar_type: ScalarType,
+    pub collection_style: CollectionStyle,
+    pub block_indicators: Option<BlockIndicators>,
+}
+
+impl Presentation {
+    /// Presentation of a scalar written in the given style.
+    #[must_use]
+    pub fn scalar(scalar_type: ScalarType, block_indicators: Option<BlockIndicators>) -> Self {
+        Presentation {
+            scalar_type,
+            collection_style: CollectionStyle::Block,
+            block_indicators,
+        }
+    }
+
+    /// Presentation of a collection written in the given style.
+    #[must_use]
+    pub fn collection(collection_style: CollectionStyle) -> Self {
+        Presentation {
+            collection_style,
+            ..Presentation::default()
+        }
+    }
+}
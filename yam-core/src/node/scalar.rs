@@ -1,7 +1,12 @@
+use crate::node::schema::Schema;
 use crate::parsing::ScalarValue;
 use crate::prelude::{ScalarType, Tag};
 use alloc::borrow::Cow;
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
+use core::mem;
 
 /// An enumeration representing a YAML scalar value.
 ///
@@ -60,6 +65,35 @@ impl<'a> YamlScalar<'a> {
     pub fn parse_from_scalar(value: ScalarValue<'a>) -> Option<Self> {
         Self::parse_from_cow_and_metadata(value.value, value.scalar_type, value.tag)
     }
+
+    #[must_use]
+    /// Parse a scalar node representation into a [`YamlScalar`], resolving plain scalars with
+    /// the given [`Schema`].
+    ///
+    /// # Return
+    /// Returns the parsed [`YamlScalar`].
+    ///
+    pub fn parse_from_scalar_with_schema(value: ScalarValue<'a>, schema: Schema) -> Option<Self> {
+        Self::parse_from_cow_and_metadata_with_schema(
+            value.value,
+            value.scalar_type,
+            value.tag,
+            schema,
+        )
+    }
+
+    /// Converts the scalar into one that owns its string, as stored in a
+    /// [`YamlOwned`](crate::prelude::YamlOwned).
+    #[must_use]
+    pub fn into_owned(self) -> YamlScalar<'static, f64, i64, String> {
+        match self {
+            YamlScalar::Null(_) => YamlScalar::Null(PhantomData),
+            YamlScalar::String(s) => YamlScalar::String(s.into_owned()),
+            YamlScalar::Bool(b) => YamlScalar::Bool(b),
+            YamlScalar::FloatingPoint(f) => YamlScalar::FloatingPoint(f),
+            YamlScalar::Integer(i) => YamlScalar::Integer(i),
+        }
+    }
 }
 
 impl<'a, F, S, I> YamlScalar<'a, F, I, S>
@@ -83,18 +117,44 @@ where
         v: Cow<'a, str>,
         scalar_type: ScalarType,
         tag: Option<Cow<'a, Tag>>,
+    ) -> Option<Self> {
+        Self::parse_from_cow_and_metadata_with_schema(v, scalar_type, tag, Schema::Core)
+    }
+
+    #[must_use]
+    /// Parse a scalar node representation into a [`YamlScalar`], resolving plain scalars with
+    /// the given [`Schema`].
+    ///
+    /// Behaves like [`YamlScalar::parse_from_cow_and_metadata`]. Tagged `!!bool`, `!!int`,
+    /// `!!float` and `!!null` scalars are parsed with the rules of `schema`, or with the core
+    /// schema rules when `schema` is [`Schema::Failsafe`].
+    ///
+    /// # Return
+    /// Returns the parsed [`YamlScalar`].
+    ///
+    pub fn parse_from_cow_and_metadata_with_schema(
+        v: Cow<'a, str>,
+        scalar_type: ScalarType,
+        tag: Option<Cow<'a, Tag>>,
+        schema: Schema,
     ) -> Option<Self> {
         if scalar_type != ScalarType::Plain {
             // Any quoted scalar is a string.
             Some(Self::String(v.into()))
         } else if let Some(tag) = tag {
             if tag.is_yaml_core_schema() {
+                let tag_schema = match schema {
+                    Schema::Failsafe => Schema::Core,
+                    _ => schema,
+                };
                 match tag.suffix.as_ref() {
-                    "bool" => v.parse::<bool>().ok().map(|x| Self::Bool(x)),
-                    "int" => parse_i64_from_cow(&v).ok().map(|x| Self::Integer(x.into())),
-                    "float" => parse_core_schema_fp(&v).map(|x| Self::FloatingPoint(x.into())),
+                    "bool" => tag_schema.parse_bool(&v).map(|x| Self::Bool(x)),
+                    "int" => tag_schema.parse_int(&v).map(|x| Self::Integer(x.into())),
+                    "float" => tag_schema
+                        .parse_float(&v)
+                        .map(|x| Self::FloatingPoint(x.into())),
                     "null" => {
-                        if ScalarValue::is_null_tagged(&v, &tag) {
+                        if v.is_empty() || tag_schema.parse_null(&v) {
                             Some(Self::Null(PhantomData))
                         } else {
                             None
@@ -108,11 +168,35 @@ where
                 // If we have a tag we do not recognize, parse it regularly.
                 // This will sound more intuitive when instance reading tagged scalars like
                 // `!degree 50`.
-                Some(Self::parse_from_cow(v))
+                Some(Self::parse_from_cow_with_schema(v, schema))
             }
         } else {
             // No tag means we have to guess.
-            Some(Self::parse_from_cow(v))
+            Some(Self::parse_from_cow_with_schema(v, schema))
+        }
+    }
+
+    /// Parse a plain scalar into a [`YamlScalar`] using the given [`Schema`].
+    ///
+    /// This function cannot fail. It will fall back to [`YamlScalar::String`] if everything else fails.
+    ///
+    /// # Return
+    /// Returns the parsed [`YamlScalar`].
+    #[must_use]
+    pub fn parse_from_cow_with_schema(v: Cow<'a, str>, schema: Schema) -> Self {
+        if schema == Schema::Core {
+            return Self::parse_from_cow(v);
+        }
+        if schema.parse_null(&v) {
+            Self::Null(PhantomData)
+        } else if let Some(b) = schema.parse_bool(&v) {
+            Self::Bool(b)
+        } else if let Some(i) = schema.parse_int(&v) {
+            Self::Integer(i.into())
+        } else if let Some(f) = schema.parse_float(&v) {
+            Self::FloatingPoint(f.into())
+        } else {
+            Self::String(v.into())
         }
     }
 
@@ -202,6 +286,53 @@ pub fn parse_i64_from_cow(v: &str) -> Result<i64, core::num::ParseIntError> {
     v.parse::<i64>()
 }
 
+impl<F: Eq, S: Eq, I: Eq> Eq for YamlScalar<'_, F, S, I> {}
+
+impl<F: Hash, INT: Hash, STR: Hash> Hash for YamlScalar<'_, F, INT, STR> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            YamlScalar::Null(_) => {}
+            YamlScalar::String(s) => s.hash(state),
+            YamlScalar::Bool(b) => b.hash(state),
+            YamlScalar::FloatingPoint(f) => f.hash(state),
+            YamlScalar::Integer(i) => i.hash(state),
+        }
+    }
+}
+
+/// Scalars of different kinds sort as null, booleans, integers, floats and then strings.
+impl<F: Ord, INT: Ord, STR: Ord> Ord for YamlScalar<'_, F, INT, STR> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (YamlScalar::Null(_), YamlScalar::Null(_)) => Ordering::Equal,
+            (YamlScalar::Bool(a), YamlScalar::Bool(b)) => a.cmp(b),
+            (YamlScalar::Integer(a), YamlScalar::Integer(b)) => a.cmp(b),
+            (YamlScalar::FloatingPoint(a), YamlScalar::FloatingPoint(b)) => a.cmp(b),
+            (YamlScalar::String(a), YamlScalar::String(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl<F: Ord, INT: Ord, STR: Ord> PartialOrd for YamlScalar<'_, F, INT, STR> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F, INT, STR> YamlScalar<'_, F, INT, STR> {
+    fn rank(&self) -> u8 {
+        match self {
+            YamlScalar::Null(_) => 0,
+            YamlScalar::Bool(_) => 1,
+            YamlScalar::Integer(_) => 2,
+            YamlScalar::FloatingPoint(_) => 3,
+            YamlScalar::String(_) => 4,
+        }
+    }
+}
+
 impl<F, STR, INT> Clone for YamlScalar<'_, F, INT, STR>
 where
     F: Copy,
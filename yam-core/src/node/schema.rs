@@ -0,0 +1,481 @@
+use crate::node::scalar::{parse_core_schema_fp, parse_i64_from_cow};
+use crate::prelude::{
+    MappingLike, ScalarType, SequenceLike, Tag, YamlData, YamlDocAccess, YamlScalar,
+};
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+///
+/// Resolves scalars and tagged collections into values before the [`Schema`] gets to them.
+///
+/// Implement it to support application-specific tags like `!env HOME` or `!duration 5m`, or to
+/// change how some plain scalars are resolved. [`YamlLoader`] and the `yam-serde` deserializer
+/// call [`TagResolver::resolve`] for every scalar, with its tag if it has one. [`YamlLoader`]
+/// also calls [`TagResolver::resolve_collection`] for every tagged sequence and mapping.
+///
+/// A node resolved by a `TagResolver` is loaded as the returned value, without its tag.
+///
+/// # Example
+/// ```rust
+/// use std::borrow::Cow;
+/// use yam_core::prelude::{ScalarType, Tag, TagResolver, Yaml, YamlLoader, YamlScalar};
+/// use yam_core::parsing::Parser;
+///
+/// struct Duration;
+///
+/// impl TagResolver for Duration {
+///     fn resolve<'a>(
+///         &self,
+///         value: &Cow<'a, str>,
+///         _scalar_type: ScalarType,
+///         tag: Option<&Tag>,
+///     ) -> Result<Option<YamlScalar<'a>>, String> {
+///         match tag {
+///             Some(tag) if tag.handle == "!" && tag.suffix == "duration" => {
+///                 let minutes = value.strip_suffix('m').ok_or("expected minutes")?;
+///                 let minutes: i64 = minutes.parse().map_err(|_| "invalid number")?;
+///                 Ok(Some(YamlScalar::Integer(minutes * 60)))
+///             }
+///             _ => Ok(None),
+///         }
+///     }
+/// }
+///
+/// let mut parser = Parser::new_from_str("timeout: !duration 5m");
+/// let mut loader = YamlLoader::<Yaml>::default().resolver(Duration);
+/// parser.load(&mut loader, true).unwrap();
+/// let docs = loader.into_documents().unwrap();
+/// assert_eq!(docs[0]["timeout"], Yaml::from(300));
+///
+/// let mut parser = Parser::new_from_str("timeout: !duration soon");
+/// let mut loader = YamlLoader::<Yaml>::default().resolver(Duration);
+/// parser.load(&mut loader, true).unwrap();
+/// assert!(loader.into_documents().is_err());
+/// ```
+///
+/// [`YamlLoader`]: crate::prelude::YamlLoader
+pub trait TagResolver {
+    /// Resolves a scalar written with `scalar_type` style and the given `tag`, if any.
+    ///
+    /// Returns `Ok(None)` to let the [`Schema`] resolve the scalar as usual.
+    ///
+    /// # Errors
+    /// Returns a description of the problem when `value` isn't valid for the tag.
+    fn resolve<'a>(
+        &self,
+        value: &Cow<'a, str>,
+        scalar_type: ScalarType,
+        tag: Option<&Tag>,
+    ) -> Result<Option<YamlScalar<'a>>, String>;
+
+    /// Resolves a sequence or mapping written with the given `tag`, once all its entries are
+    /// loaded, into the node returned in its place.
+    ///
+    /// Returns `Ok(None)` to keep the collection and its tag. Only [`YamlLoader`] calls it, the
+    /// `yam-serde` deserializer leaves tagged collections to the target type.
+    ///
+    /// # Errors
+    /// Returns a description of the problem when `node` isn't valid for the tag.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{
+    ///     MappingLike, ScalarType, SequenceLike, Tag, TagResolver, Yaml, YamlData, YamlDocAccess,
+    ///     YamlLoader, YamlScalar,
+    /// };
+    ///
+    /// struct Point;
+    ///
+    /// impl TagResolver for Point {
+    ///     fn resolve<'a>(
+    ///         &self,
+    ///         _value: &Cow<'a, str>,
+    ///         _scalar_type: ScalarType,
+    ///         _tag: Option<&Tag>,
+    ///     ) -> Result<Option<YamlScalar<'a>>, String> {
+    ///         Ok(None)
+    ///     }
+    ///
+    ///     fn resolve_collection<'a, N>(&self, node: &N, tag: &Tag) -> Result<Option<N>, String>
+    ///     where
+    ///         N: YamlDocAccess<'a, OutNode = N> + From<YamlData<'a, N>> + From<YamlScalar<'a>>,
+    ///         N::MappingNode: MappingLike<N>,
+    ///         N::SequenceNode: SequenceLike<N>,
+    ///     {
+    ///         if tag.suffix != "point" {
+    ///             return Ok(None);
+    ///         }
+    ///         let coord = |name| node.mapping().find_value(|k| k.as_str() == Some(name)).cloned();
+    ///         match (coord("x"), coord("y")) {
+    ///             (Some(x), Some(y)) => Ok(Some(YamlData::Sequence(vec![x, y]).into())),
+    ///             _ => Err("expected x and y".into()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut parser = Parser::new_from_str("at: !point {x: 1, y: 2}");
+    /// let mut loader = YamlLoader::<Yaml>::default().resolver(Point);
+    /// parser.load(&mut loader, true).unwrap();
+    /// let docs = loader.into_documents().unwrap();
+    /// assert_eq!(docs[0]["at"], Yaml::from(vec![Yaml::from(1), Yaml::from(2)]));
+    /// ```
+    fn resolve_collection<'a, N>(&self, _node: &N, _tag: &Tag) -> Result<Option<N>, String>
+    where
+        Self: Sized,
+        N: YamlDocAccess<'a, OutNode = N> + From<YamlData<'a, N>> + From<YamlScalar<'a>>,
+        N::MappingNode: MappingLike<N>,
+        N::SequenceNode: SequenceLike<N>,
+    {
+        Ok(None)
+    }
+}
+
+///
+/// The schema used to resolve plain scalars (and scalars with a `!!bool`, `!!int`, `!!float` or
+/// `!!null` tag) into booleans, numbers and nulls.
+///
+/// * `Failsafe` - Every plain scalar is a string. See [10.1 Failsafe Schema](https://yaml.org/spec/1.2.2/#101-failsafe-schema).
+/// * `Json` - Only JSON literals (`null`, `true`, `false` and JSON numbers) are resolved; other
+///   plain scalars are strings. See [10.2 JSON Schema](https://yaml.org/spec/1.2.2/#102-json-schema).
+/// * `Core` - The YAML 1.2 core schema, the default. See [10.3 Core Schema](https://yaml.org/spec/1.2.2/#103-core-schema).
+/// * `Yaml11` - The YAML 1.1 types: `yes/no/on/off` are booleans, `0777` is octal, `0b101` is
+///   binary, `1:30` is sexagesimal and `_` may separate digits. [`YamlLoader`] also applies
+///   merge keys (`<<`) in this mode.
+///
+/// # Example
+/// ```rust
+/// use yam_core::prelude::{Schema, YamlScalar};
+///
+/// let v: YamlScalar = YamlScalar::parse_from_cow_with_schema("on".into(), Schema::Yaml11);
+/// assert_eq!(v, YamlScalar::Bool(true));
+/// let v: YamlScalar = YamlScalar::parse_from_cow_with_schema("0777".into(), Schema::Yaml11);
+/// assert_eq!(v, YamlScalar::Integer(511));
+/// let v: YamlScalar = YamlScalar::parse_from_cow_with_schema("on".into(), Schema::Core);
+/// assert_eq!(v, YamlScalar::String("on".into()));
+/// ```
+///
+/// [`YamlLoader`]: crate::prelude::YamlLoader
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Schema {
+    Failsafe,
+    Json,
+    #[default]
+    Core,
+    Yaml11,
+}
+
+impl Schema {
+    /// Returns the schema a document declaring `%YAML major.minor` should be loaded with, if it
+    /// differs from the 1.2 schemas.
+    #[must_use]
+    pub fn from_version(version: (u8, u8)) -> Option<Schema> {
+        match version {
+            (1, 0 | 1) => Some(Schema::Yaml11),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse_null(self, v: &str) -> bool {
+        match self {
+            Schema::Failsafe => false,
+            Schema::Json => v == "null",
+            Schema::Core | Schema::Yaml11 => matches!(v, "" | "~" | "null" | "Null" | "NULL"),
+        }
+    }
+
+    pub(crate) fn parse_bool(self, v: &str) -> Option<bool> {
+        match self {
+            Schema::Failsafe => None,
+            Schema::Json => v.parse::<bool>().ok(),
+            Schema::Core => match v {
+                "true" | "True" | "TRUE" => Some(true),
+                "false" | "False" | "FALSE" => Some(false),
+                _ => None,
+            },
+            Schema::Yaml11 => match v {
+                "y" | "Y" | "yes" | "Yes" | "YES" | "true" | "True" | "TRUE" | "on" | "On"
+                | "ON" => Some(true),
+                "n" | "N" | "no" | "No" | "NO" | "false" | "False" | "FALSE" | "off" | "Off"
+                | "OFF" => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    pub(crate) fn parse_int(self, v: &str) -> Option<i64> {
+        match self {
+            Schema::Failsafe => None,
+            Schema::Json => Some(v).filter(|v| is_json_int(v))?.parse::<i64>().ok(),
+            Schema::Core => parse_i64_from_cow(v).ok(),
+            Schema::Yaml11 => parse_yaml11_int(v),
+        }
+    }
+
+    pub(crate) fn parse_float(self, v: &str) -> Option<f64> {
+        match self {
+            Schema::Failsafe => None,
+            Schema::Json => Some(v).filter(|v| is_json_float(v))?.parse::<f64>().ok(),
+            Schema::Core => parse_core_schema_fp(v),
+            Schema::Yaml11 => parse_yaml11_float(v),
+        }
+    }
+}
+
+/// Matches `-?(0|[1-9][0-9]*)`.
+fn is_json_int(v: &str) -> bool {
+    let digits = v.strip_prefix('-').unwrap_or(v).as_bytes();
+    match digits {
+        [b'0'] => true,
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    }
+}
+
+/// Matches `-?(0|[1-9][0-9]*)(\.[0-9]*)?([eE][-+]?[0-9]+)?`.
+fn is_json_float(v: &str) -> bool {
+    let (mantissa, exponent) = match v.find(['e', 'E']) {
+        Some(pos) => (&v[..pos], Some(&v[pos + 1..])),
+        None => (v, None),
+    };
+    let (int_part, frac_part) = match mantissa.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (mantissa, None),
+    };
+    let valid_exponent = exponent.is_none_or(|exp| {
+        let exp = exp.strip_prefix(['-', '+']).unwrap_or(exp);
+        !exp.is_empty() && exp.bytes().all(|b| b.is_ascii_digit())
+    });
+    is_json_int(int_part)
+        && frac_part.is_none_or(|frac| frac.bytes().all(|b| b.is_ascii_digit()))
+        && valid_exponent
+}
+
+/// Splits an optional leading sign from `v`, returning whether the number is negative.
+fn split_sign(v: &str) -> (bool, &str) {
+    match v.as_bytes().first() {
+        Some(b'-') => (true, &v[1..]),
+        Some(b'+') => (false, &v[1..]),
+        _ => (false, v),
+    }
+}
+
+/// Removes the `_` digit separators allowed by YAML 1.1.
+fn strip_underscores(v: &str) -> Cow<'_, str> {
+    if v.contains('_') {
+        Cow::Owned(v.chars().filter(|c| *c != '_').collect::<String>())
+    } else {
+        Cow::Borrowed(v)
+    }
+}
+
+fn parse_radix(digits: &str, radix: u32) -> Option<u64> {
+    let digits = strip_underscores(digits);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    u64::from_str_radix(&digits, radix).ok()
+}
+
+/// Parses base 60 parts like `1:30:15`, where every part but the first must be in `0..60`.
+fn parse_sexagesimal(v: &str) -> Option<u64> {
+    let mut parts = v.split(':');
+    let mut value = parse_radix(parts.next()?, 10)?;
+    for part in parts {
+        let part = part
+            .parse::<u8>()
+            .ok()
+            .filter(|x| *x < 60 && part.len() <= 2)?;
+        value = value.checked_mul(60)?.checked_add(u64::from(part))?;
+    }
+    Some(value)
+}
+
+/// Parses an integer according to the [YAML 1.1 int type](https://yaml.org/type/int.html).
+fn parse_yaml11_int(v: &str) -> Option<i64> {
+    let (negative, digits) = split_sign(v);
+    let value = if let Some(bin) = digits.strip_prefix("0b") {
+        parse_radix(bin, 2)?
+    } else if let Some(hex) = digits.strip_prefix("0x") {
+        parse_radix(hex, 16)?
+    } else if digits.contains(':') {
+        if !digits.starts_with(|c: char| matches!(c, '1'..='9')) {
+            return None;
+        }
+        parse_sexagesimal(digits)?
+    } else if let Some(oct) = digits.strip_prefix('0').filter(|oct| !oct.is_empty()) {
+        parse_radix(oct, 8)?
+    } else if digits.starts_with(|c: char| c.is_ascii_digit()) {
+        parse_radix(digits, 10)?
+    } else {
+        return None;
+    };
+    if negative {
+        0i64.checked_sub_unsigned(value)
+    } else {
+        i64::try_from(value).ok()
+    }
+}
+
+/// Parses a float according to the [YAML 1.1 float type](https://yaml.org/type/float.html).
+fn parse_yaml11_float(v: &str) -> Option<f64> {
+    let (negative, rest) = split_sign(v);
+    let value = match rest {
+        ".inf" | ".Inf" | ".INF" => f64::INFINITY,
+        ".nan" | ".NaN" | ".NAN" if rest.len() == v.len() => return Some(f64::NAN),
+        _ if rest.contains(':') => {
+            let (base, frac) = rest.split_once('.')?;
+            if !base.starts_with(|c: char| c.is_ascii_digit()) {
+                return None;
+            }
+            let frac = strip_underscores(frac);
+            if !frac.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let base = parse_sexagesimal(base)? as f64;
+            base + alloc::format!("0.{frac}").parse::<f64>().ok()?
+        }
+        _ => {
+            let (mantissa, exponent) = match rest.find(['e', 'E']) {
+                Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+                None => (rest, None),
+            };
+            let valid_mantissa = mantissa.contains('.')
+                && mantissa.bytes().any(|b| b.is_ascii_digit())
+                && !mantissa.starts_with('_')
+                && mantissa
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b == b'_' || b == b'.');
+            let valid_exponent = exponent.is_none_or(|exp| {
+                exp.len() > 1
+                    && exp.starts_with(['-', '+'])
+                    && exp[1..].bytes().all(|b| b.is_ascii_digit())
+            });
+            if !valid_mantissa || !valid_exponent {
+                return None;
+            }
+            strip_underscores(rest).parse::<f64>().ok()?
+        }
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Schema, YamlScalar};
+
+    fn resolve(v: &str, schema: Schema) -> YamlScalar<'_> {
+        YamlScalar::parse_from_cow_with_schema(v.into(), schema)
+    }
+
+    #[test]
+    fn test_failsafe() {
+        for v in ["null", "true", "12", "1.5", "~"] {
+            assert_eq!(resolve(v, Schema::Failsafe), YamlScalar::String(v.into()));
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            resolve("null", Schema::Json),
+            YamlScalar::Null(Default::default())
+        );
+        assert_eq!(resolve("true", Schema::Json), YamlScalar::Bool(true));
+        assert_eq!(resolve("-12", Schema::Json), YamlScalar::Integer(-12));
+        assert_eq!(
+            resolve("1.5e3", Schema::Json),
+            YamlScalar::FloatingPoint(1500.0)
+        );
+        for v in ["~", "True", "012", "+1", "0x1F", ".inf", ".5", "1e"] {
+            assert_eq!(
+                resolve(v, Schema::Json),
+                YamlScalar::String(v.into()),
+                "{v}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_yaml11_bool() {
+        for v in ["y", "Yes", "ON", "true"] {
+            assert_eq!(resolve(v, Schema::Yaml11), YamlScalar::Bool(true), "{v}");
+        }
+        for v in ["N", "no", "Off", "FALSE"] {
+            assert_eq!(resolve(v, Schema::Yaml11), YamlScalar::Bool(false), "{v}");
+        }
+        assert_eq!(
+            resolve("oN", Schema::Yaml11),
+            YamlScalar::String("oN".into())
+        );
+    }
+
+    #[test]
+    fn test_yaml11_int() {
+        let cases = [
+            ("0777", 0o777),
+            ("-0b1010_0111", -0b1010_0111),
+            ("0x_0A_74_AE", 0x0A_74_AE),
+            ("685_230", 685_230),
+            ("+190:20:30", 685_230),
+            ("0", 0),
+            ("-0x8000_0000_0000_0000", i64::MIN),
+            ("0x7FFF_FFFF_FFFF_FFFF", i64::MAX),
+            ("-9223372036854775808", i64::MIN),
+        ];
+        for (v, expected) in cases {
+            assert_eq!(
+                resolve(v, Schema::Yaml11),
+                YamlScalar::Integer(expected),
+                "{v}"
+            );
+        }
+        for v in [
+            "0789",
+            "1:60",
+            "0:30",
+            "_1",
+            "0b",
+            "0x8000_0000_0000_0000",
+            "-0x8000_0000_0000_0001",
+        ] {
+            assert_eq!(
+                resolve(v, Schema::Yaml11),
+                YamlScalar::String(v.into()),
+                "{v}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_yaml11_float() {
+        let cases = [
+            ("6.8523015e+5", 685_230.15),
+            ("685.230_15e+03", 685_230.15),
+            ("685_230.15", 685_230.15),
+            ("190:20:30.15", 685_230.15),
+            ("-.inf", f64::NEG_INFINITY),
+        ];
+        for (v, expected) in cases {
+            match resolve(v, Schema::Yaml11) {
+                YamlScalar::FloatingPoint(f) => {
+                    assert!(f == expected || (f - expected).abs() < 1e-6, "{v}");
+                }
+                other => panic!("{v} resolved to {other:?}"),
+            }
+        }
+        assert!(matches!(
+            resolve(".NaN", Schema::Yaml11),
+            YamlScalar::FloatingPoint(f) if f.is_nan()
+        ));
+        for v in ["1e+3", "1.0e3", "-.nan", "."] {
+            assert_eq!(
+                resolve(v, Schema::Yaml11),
+                YamlScalar::String(v.into()),
+                "{v}"
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::prelude::{
-    IsEmpty, NodeType, Span, Tag, ToMut, ToMutStr, YamlAccessError, YamlData, YamlDocAccess,
-    YamlEntry, YamlScalar,
+    IsEmpty, Marker, NodeType, Presentation, Span, Tag, Timestamp, ToMut, ToMutStr,
+    YamlAccessError, YamlData, YamlDocAccess, YamlEntry, YamlScalar,
 };
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
@@ -31,6 +31,11 @@ use core::marker::PhantomData;
 ///   of this YAML node. It is capable of holding information such as
 ///   mappings, sequences, scalars, and more. The `YamlData` is parameterized
 ///   to support recursive structures and type customization.
+/// - `anchor`: The name of the anchor (`&name`) attached to this node in the
+///   source, if any.
+/// - `alias`: The name of the alias (`*name`) this node was copied in place of, if
+///   any. Such copies have no `anchor` of their own.
+/// - `presentation`: How this node was written in the source, see [`Presentation`].
 ///
 /// # Example Usage
 /// ```rust
@@ -43,6 +48,9 @@ use core::marker::PhantomData;
 /// let spanned_yaml : SpannedYaml<'_> = SpannedYaml {
 ///     span,
 ///     yaml: yaml_data,
+///     anchor: None,
+///     alias: None,
+///     presentation: Default::default(),
 /// };
 ///
 /// println!("Span: {:?}", spanned_yaml.span);
@@ -54,6 +62,9 @@ use core::marker::PhantomData;
 pub struct SpannedYaml<'a, FP = f64> {
     pub span: Span,
     pub yaml: YamlData<'a, SpannedYaml<'a, FP>, FP>,
+    pub anchor: Option<Cow<'a, str>>,
+    pub alias: Option<Cow<'a, str>>,
+    pub presentation: Presentation,
 }
 
 impl<FP> Clone for SpannedYaml<'_, FP>
@@ -64,6 +75,33 @@ where
         SpannedYaml {
             span: self.span,
             yaml: self.yaml.clone(),
+            anchor: self.anchor.clone(),
+            alias: self.alias.clone(),
+            presentation: self.presentation,
+        }
+    }
+}
+
+impl<'a, FP> From<YamlData<'a, Self, FP>> for SpannedYaml<'a, FP> {
+    fn from(value: YamlData<'a, Self, FP>) -> Self {
+        SpannedYaml {
+            span: Span::default(),
+            yaml: value,
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
+        }
+    }
+}
+
+impl<'a, FP> From<YamlScalar<'a, FP>> for SpannedYaml<'a, FP> {
+    fn from(value: YamlScalar<'a, FP>) -> Self {
+        SpannedYaml {
+            span: Span::default(),
+            yaml: YamlData::Scalar(value),
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
         }
     }
 }
@@ -81,6 +119,9 @@ where
         SpannedYaml {
             span: Span::default(),
             yaml: YamlData::Scalar(YamlScalar::Integer(index.cast_signed() as i64)),
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
         }
     }
 
@@ -88,6 +129,9 @@ where
         SpannedYaml {
             span: Span::default(),
             yaml: YamlData::Scalar(YamlScalar::String(Cow::Owned(index.to_string()))),
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
         }
     }
 
@@ -183,6 +227,10 @@ where
         }
     }
 
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        self.yaml.as_timestamp()
+    }
+
     fn sequence_mut(&mut self) -> &mut Self::SequenceNode {
         match &mut self.yaml {
             YamlData::Sequence(s) => s,
@@ -218,6 +266,24 @@ where
         }
     }
 
+    fn tagged_node(&self) -> Option<&Self> {
+        match &self.yaml {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn tagged_node_mut(&mut self) -> Option<&mut Self> {
+        match &mut self.yaml {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn source_span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+
     fn get_type(&self) -> NodeType {
         self.yaml.get_type()
     }
@@ -246,6 +312,9 @@ where
     fn into_tagged(self, tag: Cow<'a, Tag>) -> Self {
         SpannedYaml {
             span: self.span,
+            anchor: None,
+            alias: None,
+            presentation: self.presentation,
             yaml: YamlData::Tagged(tag, Box::new(self)),
         }
     }
@@ -254,6 +323,9 @@ where
         SpannedYaml {
             span,
             yaml: YamlData::BadValue,
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
         }
     }
 
@@ -261,6 +333,52 @@ where
         SpannedYaml {
             yaml: YamlData::Scalar(YamlScalar::Null(PhantomData)),
             span: Span::default(),
+            anchor: None,
+            alias: None,
+            presentation: Presentation::default(),
         }
     }
+
+    fn with_start(mut self, marker: Marker) -> Self {
+        self.span.start = marker;
+        self
+    }
+
+    fn with_end(mut self, marker: Marker) -> Self {
+        self.span.end = marker;
+        self
+    }
+
+    fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    fn with_anchor(mut self, anchor: Cow<'a, str>) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    fn get_anchor(&self) -> Option<&str> {
+        self.anchor.as_deref()
+    }
+
+    fn with_alias(mut self, alias: Cow<'a, str>) -> Self {
+        self.anchor = None;
+        self.alias = Some(alias);
+        self
+    }
+
+    fn get_alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    fn with_presentation(mut self, presentation: Presentation) -> Self {
+        self.presentation = presentation;
+        self
+    }
+
+    fn get_presentation(&self) -> Option<&Presentation> {
+        Some(&self.presentation)
+    }
 }
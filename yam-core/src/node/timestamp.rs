@@ -0,0 +1,290 @@
+use core::fmt::{Display, Formatter};
+
+///
+/// A point in time read from a `!!timestamp` scalar.
+///
+/// Accepts the formats of the [timestamp type](https://yaml.org/type/timestamp.html): a
+/// date (`2002-12-14`), or a date and time with an optional fraction and time zone
+/// (`2001-12-14t21:59:43.10-05:00`, `2001-12-14 21:59:43.10 -5`, `2001-12-15T02:59:43.1Z`).
+/// A date is midnight UTC, and a time without a time zone is UTC.
+///
+/// # Fields
+/// - `year`, `month`, `day`: The calendar date, in the time zone of the timestamp.
+/// - `hour`, `minute`, `second`, `nanosecond`: The time of day, in the time zone of the timestamp.
+/// - `offset_minutes`: The offset of the time zone from UTC, in minutes (`-300` for `-05:00`).
+///
+/// # Example
+/// ```rust
+/// use yam_core::prelude::Timestamp;
+///
+/// let ts = Timestamp::parse("2001-12-14 21:59:43.10 -5").unwrap();
+/// assert_eq!((ts.year, ts.month, ts.day, ts.hour), (2001, 12, 14, 21));
+/// assert_eq!(ts.nanosecond, 100_000_000);
+/// assert_eq!(ts.offset_minutes, -300);
+/// assert_eq!(ts.to_string(), "2001-12-14T21:59:43.1-05:00");
+/// assert_eq!(ts.unix_seconds(), Timestamp::parse("2001-12-15T02:59:43Z").unwrap().unix_seconds());
+///
+/// assert!(Timestamp::parse("2001-02-29").is_none());
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Timestamp {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub offset_minutes: i16,
+}
+
+impl Timestamp {
+    /// Parses a `!!timestamp` scalar.
+    ///
+    /// # Return
+    /// Returns `None` if `value` isn't a timestamp or names a date or time that doesn't exist.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Timestamp> {
+        let mut cursor = Cursor {
+            bytes: value.as_bytes(),
+            pos: 0,
+        };
+        let year = cursor.digits(4, 4)?;
+        cursor.expect(b'-')?;
+        let month_start = cursor.pos;
+        let month = cursor.digits(1, 2)?;
+        let short_month = cursor.pos - month_start == 1;
+        cursor.expect(b'-')?;
+        let day_start = cursor.pos;
+        let day = cursor.digits(1, 2)?;
+        let short_day = cursor.pos - day_start == 1;
+
+        let mut ts = Timestamp {
+            year: u16::try_from(year).ok()?,
+            month: u8::try_from(month).ok()?,
+            day: u8::try_from(day).ok()?,
+            ..Timestamp::default()
+        };
+        if !(1..=12).contains(&ts.month) || ts.day == 0 || ts.day > days_in_month(year, ts.month) {
+            return None;
+        }
+
+        if cursor.is_done() {
+            // A bare date needs two-digit months and days.
+            return (!short_month && !short_day).then_some(ts);
+        }
+
+        if !cursor.eat(|b| b == b'T' || b == b't') && cursor.skip_blanks() == 0 {
+            return None;
+        }
+        ts.hour = u8::try_from(cursor.digits(1, 2)?).ok()?;
+        cursor.expect(b':')?;
+        ts.minute = u8::try_from(cursor.digits(2, 2)?).ok()?;
+        cursor.expect(b':')?;
+        ts.second = u8::try_from(cursor.digits(2, 2)?).ok()?;
+        if ts.hour > 23 || ts.minute > 59 || ts.second > 59 {
+            return None;
+        }
+
+        if cursor.eat(|b| b == b'.') {
+            let mut scale = 100_000_000;
+            while let Some(digit) = cursor.digit() {
+                ts.nanosecond += digit * scale;
+                scale /= 10;
+            }
+        }
+
+        cursor.skip_blanks();
+        if cursor.eat(|b| b == b'Z') {
+            ts.offset_minutes = 0;
+        } else if let Some(sign) = cursor.peek().filter(|b| matches!(b, b'+' | b'-')) {
+            cursor.pos += 1;
+            let hours = cursor.digits(1, 2)?;
+            let minutes = if cursor.eat(|b| b == b':') {
+                cursor.digits(2, 2)?
+            } else {
+                0
+            };
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = i16::try_from(hours * 60 + minutes).ok()?;
+            ts.offset_minutes = if sign == b'-' { -offset } else { offset };
+        }
+
+        cursor.is_done().then_some(ts)
+    }
+
+    /// Returns the number of seconds between the Unix epoch (`1970-01-01T00:00:00Z`) and this
+    /// timestamp, ignoring the fraction of a second.
+    #[must_use]
+    pub fn unix_seconds(&self) -> i64 {
+        let days = days_from_civil(i64::from(self.year), self.month, self.day);
+        days * 86_400
+            + i64::from(self.hour) * 3_600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+            - i64::from(self.offset_minutes) * 60
+    }
+}
+
+impl Display for Timestamp {
+    /// Writes the timestamp in its canonical form, e.g. `2001-12-14T21:59:43.1-05:00`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond > 0 {
+            let mut fraction = self.nanosecond;
+            let mut width = 9;
+            while fraction.is_multiple_of(10) {
+                fraction /= 10;
+                width -= 1;
+            }
+            write!(f, ".{fraction:0width$}")?;
+        }
+        if self.offset_minutes == 0 {
+            write!(f, "Z")
+        } else {
+            let sign = if self.offset_minutes < 0 { '-' } else { '+' };
+            let offset = self.offset_minutes.unsigned_abs();
+            write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+        }
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn eat(&mut self, f: impl Fn(u8) -> bool) -> bool {
+        let matched = self.peek().is_some_and(f);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.eat(|b| b == byte).then_some(())
+    }
+
+    fn skip_blanks(&mut self) -> usize {
+        let start = self.pos;
+        while self.eat(|b| b == b' ' || b == b'\t') {}
+        self.pos - start
+    }
+
+    fn digit(&mut self) -> Option<u32> {
+        let digit = self.peek().filter(u8::is_ascii_digit)?;
+        self.pos += 1;
+        Some(u32::from(digit - b'0'))
+    }
+
+    /// Reads between `min` and `max` decimal digits.
+    fn digits(&mut self, min: usize, max: usize) -> Option<u32> {
+        let mut value = 0;
+        let mut count = 0;
+        while count < max
+            && let Some(digit) = self.digit()
+        {
+            value = value * 10 + digit;
+            count += 1;
+        }
+        (count >= min).then_some(value)
+    }
+}
+
+fn days_in_month(year: u32, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod test {
+    use super::Timestamp;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_timestamp_formats() {
+        let date = Timestamp::parse("2002-12-14").unwrap();
+        assert_eq!(
+            (date.year, date.month, date.day, date.hour),
+            (2002, 12, 14, 0)
+        );
+        assert_eq!(date.to_string(), "2002-12-14T00:00:00Z");
+
+        let canonical = Timestamp::parse("2001-12-15T02:59:43.1Z").unwrap();
+        assert_eq!(canonical.nanosecond, 100_000_000);
+        assert_eq!(canonical.unix_seconds(), 1_008_385_183);
+
+        let iso = Timestamp::parse("2001-12-14t21:59:43.10-05:00").unwrap();
+        let spaced = Timestamp::parse("2001-12-14 21:59:43.10 -5").unwrap();
+        assert_eq!(iso, spaced);
+        assert_eq!(iso.unix_seconds(), canonical.unix_seconds());
+
+        let no_zone = Timestamp::parse("2001-12-15 2:59:43").unwrap();
+        assert_eq!(no_zone.unix_seconds(), canonical.unix_seconds());
+        assert_eq!(
+            Timestamp::parse("2001-1-2 0:00:00+05:30")
+                .unwrap()
+                .offset_minutes,
+            330
+        );
+        assert_eq!(
+            Timestamp::parse("1969-12-31T23:59:59Z")
+                .unwrap()
+                .unix_seconds(),
+            -1
+        );
+        assert!(Timestamp::parse("2000-02-29").is_some());
+    }
+
+    #[test]
+    fn test_invalid_timestamps() {
+        for value in [
+            "",
+            "2001",
+            "2001-1-2",
+            "2001-13-01",
+            "1900-02-29",
+            "2001-12-14 25:00:00",
+            "2001-12-14T21:59",
+            "2001-12-14T21:59:43 +",
+            "2001-12-14T21:59:43Zx",
+            "2001-12-14x21:59:43",
+        ] {
+            assert_eq!(Timestamp::parse(value), None, "{value}");
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use crate::prelude::YamlScalar::Null;
 use crate::prelude::{
-    IsEmpty, NodeType, Span, Tag, ToMut, ToMutStr, YamlAccessError, YamlData, YamlDocAccess,
-    YamlEntry, YamlError, YamlLoader, YamlScalar,
+    IsEmpty, NodeType, OrdFloat, Span, Tag, Timestamp, ToMut, ToMutStr, YamlAccessError, YamlData,
+    YamlDocAccess, YamlEntry, YamlError, YamlLoader, YamlOwned, YamlScalar,
 };
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
 use core::ops::{Index, IndexMut};
 
@@ -22,6 +24,12 @@ use core::ops::{Index, IndexMut};
 ///
 /// # Fields
 /// - `0`: A public field containing the `YamlData` associated with this `Yaml` instance.
+/// - `1`: The anchor the node was defined with, or the alias it was copied in place of, see
+///   [`NodeName`]. Aliases are resolved into copies of the anchored node, so this is the only
+///   trace of them.
+///
+/// Node names are not compared, hashed or ordered: two nodes with the same data are equal even
+/// if only one of them was anchored.
 ///
 /// # Derives
 /// - `PartialEq`: Enables equality and inequality comparisons for `Yaml`.
@@ -36,10 +44,24 @@ use core::ops::{Index, IndexMut};
 /// let yaml: Yaml = Yaml::from(3.1);
 ///
 /// // Example usage with custom floating-point type
-/// let yaml_custom: Yaml<f32> = Yaml(YamlData::Scalar(YamlScalar::FloatingPoint(2.3f32)));
+/// let yaml_custom: Yaml<f32> = Yaml(YamlData::Scalar(YamlScalar::FloatingPoint(2.3f32)), None);
 /// ```
-#[derive(PartialEq, Debug)]
-pub struct Yaml<'a, FP = f64, INT = i64, STR = Cow<'a, str>>(pub YamlData<'a, Self, FP, INT, STR>);
+#[derive(Debug)]
+pub struct Yaml<'a, FP = f64, INT = i64, STR = Cow<'a, str>>(
+    pub YamlData<'a, Self, FP, INT, STR>,
+    pub Option<Box<NodeName<'a>>>,
+);
+
+///
+/// The anchor or alias name a [`Yaml`] node was written with.
+///
+/// - `Anchor`: The node was defined as `&name`.
+/// - `Alias`: The node is a copy made in place of the alias `*name`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodeName<'a> {
+    Anchor(Cow<'a, str>),
+    Alias(Cow<'a, str>),
+}
 
 impl<FP, STR, INT> Clone for Yaml<'_, FP, INT, STR>
 where
@@ -48,7 +70,36 @@ where
     STR: Clone,
 {
     fn clone(&self) -> Self {
-        Yaml(self.0.clone())
+        Yaml(self.0.clone(), self.1.clone())
+    }
+}
+
+impl<FP: PartialEq, INT: PartialEq, STR: PartialEq> PartialEq for Yaml<'_, FP, INT, STR> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<FP: Eq, INT: Eq, STR: Eq> Eq for Yaml<'_, FP, INT, STR> {}
+
+/// With a float type like [`OrdFloat`], nodes can be deduplicated in hash sets.
+impl<FP: Hash, INT: Hash, STR: Hash> Hash for Yaml<'_, FP, INT, STR> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// With a float type like [`OrdFloat`], nodes can be sorted and used as `BTreeMap` keys.
+/// See [`YamlScalar`] and [`YamlData`] for how values of different kinds are ordered.
+impl<FP: Ord, INT: Ord, STR: Ord> Ord for Yaml<'_, FP, INT, STR> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<FP: Ord, INT: Ord, STR: Ord> PartialOrd for Yaml<'_, FP, INT, STR> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -63,15 +114,17 @@ where
 
     #[allow(clippy::cast_lossless)]
     fn key_from_usize(index: usize) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Integer(
-            (index.cast_signed() as i64).into(),
-        )))
+        Yaml(
+            YamlData::Scalar(YamlScalar::Integer((index.cast_signed() as i64).into())),
+            None,
+        )
     }
 
     fn key_from_str(index: &str) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::String(Cow::Owned(
-            index.to_string(),
-        ))))
+        Yaml(
+            YamlData::Scalar(YamlScalar::String(Cow::Owned(index.to_string()))),
+            None,
+        )
     }
 
     fn is_non_empty_collection(&self) -> bool {
@@ -166,6 +219,10 @@ where
         }
     }
 
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        self.0.as_timestamp()
+    }
+
     fn sequence_mut(&mut self) -> &mut Self::SequenceNode {
         match &mut self.0 {
             YamlData::Sequence(s) => s,
@@ -201,6 +258,20 @@ where
         }
     }
 
+    fn tagged_node(&self) -> Option<&Self> {
+        match &self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn tagged_node_mut(&mut self) -> Option<&mut Self> {
+        match &mut self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
     fn get_type(&self) -> NodeType {
         self.0.get_type()
     }
@@ -227,36 +298,98 @@ where
     }
 
     fn into_tagged(self, tag: Cow<'a, Tag>) -> Self {
-        Yaml(YamlData::Tagged(tag, Box::new(self)))
+        Yaml(YamlData::Tagged(tag, Box::new(self)), None)
     }
 
     fn bad_span_value(_span: Span) -> Self {
-        Yaml(YamlData::BadValue)
+        Yaml(YamlData::BadValue, None)
     }
 
     fn bad_value() -> Self {
-        Yaml(YamlData::BadValue)
+        Yaml(YamlData::BadValue, None)
+    }
+
+    fn with_anchor(mut self, anchor: Cow<'a, str>) -> Self {
+        self.1 = Some(Box::new(NodeName::Anchor(anchor)));
+        self
+    }
+
+    fn get_anchor(&self) -> Option<&str> {
+        match self.1.as_deref() {
+            Some(NodeName::Anchor(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn with_alias(mut self, alias: Cow<'a, str>) -> Self {
+        self.1 = Some(Box::new(NodeName::Alias(alias)));
+        self
+    }
+
+    fn get_alias(&self) -> Option<&str> {
+        match self.1.as_deref() {
+            Some(NodeName::Alias(name)) => Some(name),
+            _ => None,
+        }
     }
 
     #[inline]
     fn null() -> Self {
-        Yaml(YamlData::Scalar(Null(PhantomData)))
+        Yaml(YamlData::Scalar(Null(PhantomData)), None)
     }
 }
 
 impl<'a, FP> From<YamlData<'a, Self, FP>> for Yaml<'a, FP> {
     fn from(value: YamlData<'a, Self, FP>) -> Self {
-        Yaml(value)
+        Yaml(value, None)
     }
 }
 
 impl<'a, FP> From<YamlScalar<'a, FP>> for Yaml<'a, FP> {
     fn from(value: YamlScalar<'a, FP>) -> Self {
-        Yaml(YamlData::Scalar(value))
+        Yaml(YamlData::Scalar(value), None)
+    }
+}
+
+/// Lets [`YamlLoader`] load `Yaml<OrdFloat>`.
+impl<'a> From<YamlData<'a, Self>> for Yaml<'a, OrdFloat> {
+    fn from(value: YamlData<'a, Self>) -> Self {
+        Yaml(
+            match value {
+                YamlData::BadValue => YamlData::BadValue,
+                YamlData::Scalar(s) => return s.into(),
+                YamlData::Sequence(s) => YamlData::Sequence(s),
+                YamlData::Mapping(m) => YamlData::Mapping(m),
+                YamlData::Tagged(tag, node) => YamlData::Tagged(tag, node),
+                YamlData::Alias(a) => YamlData::Alias(a),
+            },
+            None,
+        )
+    }
+}
+
+impl<'a> From<YamlScalar<'a>> for Yaml<'a, OrdFloat> {
+    fn from(value: YamlScalar<'a>) -> Self {
+        Yaml(
+            YamlData::Scalar(match value {
+                YamlScalar::Null(p) => YamlScalar::Null(p),
+                YamlScalar::String(s) => YamlScalar::String(s),
+                YamlScalar::Bool(b) => YamlScalar::Bool(b),
+                YamlScalar::FloatingPoint(f) => YamlScalar::FloatingPoint(OrdFloat(f)),
+                YamlScalar::Integer(i) => YamlScalar::Integer(i),
+            }),
+            None,
+        )
     }
 }
 
 impl<'a> Yaml<'a> {
+    /// Converts the node into a [`YamlOwned`], copying the strings it borrows from the input.
+    #[must_use]
+    pub fn into_owned(self) -> YamlOwned {
+        self.into()
+    }
+
     ///
     /// Loads a vector of YAML document from the given input string.
     ///
@@ -383,7 +516,7 @@ where
                     .iter_mut()
                     .find(|x| x.key == ind)
                     .unwrap()
-                    .key
+                    .value
             }
             NodeType::Sequence => self.sequence_mut().index_mut(index),
             _ => panic!("Expected Mapping and Sequence got {0:?} instead", typ),
@@ -432,58 +565,64 @@ where
 
 impl<'a> From<&'a str> for Yaml<'a> {
     fn from(value: &'a str) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::String(Cow::Borrowed(value))))
+        Yaml(
+            YamlData::Scalar(YamlScalar::String(Cow::Borrowed(value))),
+            None,
+        )
     }
 }
 
 impl From<bool> for Yaml<'_> {
     fn from(value: bool) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Bool(value)))
+        Yaml(YamlData::Scalar(YamlScalar::Bool(value)), None)
     }
 }
 
 impl From<f64> for Yaml<'_> {
     fn from(value: f64) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::FloatingPoint(value)))
+        Yaml(YamlData::Scalar(YamlScalar::FloatingPoint(value)), None)
     }
 }
 
 #[allow(clippy::cast_lossless)]
 impl From<f32> for Yaml<'_> {
     fn from(value: f32) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::FloatingPoint(value as f64)))
+        Yaml(
+            YamlData::Scalar(YamlScalar::FloatingPoint(value as f64)),
+            None,
+        )
     }
 }
 
 #[allow(clippy::cast_lossless)]
 impl From<i8> for Yaml<'_> {
     fn from(value: i8) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)), None)
     }
 }
 
 #[allow(clippy::cast_lossless)]
 impl From<i16> for Yaml<'_> {
     fn from(value: i16) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)), None)
     }
 }
 
 #[allow(clippy::cast_lossless)]
 impl From<i32> for Yaml<'_> {
     fn from(value: i32) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+        Yaml(YamlData::Scalar(YamlScalar::Integer(value as i64)), None)
     }
 }
 
 impl From<i64> for Yaml<'_> {
     fn from(value: i64) -> Self {
-        Yaml(YamlData::Scalar(YamlScalar::Integer(value)))
+        Yaml(YamlData::Scalar(YamlScalar::Integer(value)), None)
     }
 }
 
 impl<'a> From<Vec<Yaml<'a>>> for Yaml<'a> {
     fn from(value: Vec<Yaml<'a>>) -> Self {
-        Yaml(YamlData::Sequence(value))
+        Yaml(YamlData::Sequence(value), None)
     }
 }
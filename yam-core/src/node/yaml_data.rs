@@ -1,7 +1,12 @@
-use crate::prelude::{MappingLike, NodeType, ScalarType, Tag, YamlEntry, YamlScalar};
+use crate::prelude::{
+    MappingLike, NodeType, ScalarType, Schema, Tag, Timestamp, YamlDocAccess, YamlEntry, YamlScalar,
+};
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::mem;
 
 /// Basic data structure used as backbone for all YAML nodes
 ///
@@ -37,12 +42,13 @@ pub enum YamlData<
     Alias(usize),
 }
 
-impl<NODE, FP, STR, INT> PartialEq for YamlData<'_, NODE, FP, INT, STR>
+impl<NODE, FP, STR, INT, MAP> PartialEq for YamlData<'_, NODE, FP, INT, STR, MAP>
 where
     NODE: PartialEq,
     FP: PartialEq,
     STR: PartialEq,
     INT: PartialEq,
+    MAP: MappingLike<NODE> + PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -57,6 +63,76 @@ where
     }
 }
 
+impl<NODE, FP, STR, INT, MAP> Eq for YamlData<'_, NODE, FP, INT, STR, MAP>
+where
+    NODE: Eq,
+    FP: Eq,
+    STR: Eq,
+    INT: Eq,
+    MAP: MappingLike<NODE> + Eq,
+{
+}
+
+impl<NODE, FP, STR, INT, MAP> Hash for YamlData<'_, NODE, FP, INT, STR, MAP>
+where
+    NODE: Hash,
+    FP: Hash,
+    STR: Hash,
+    INT: Hash,
+    MAP: MappingLike<NODE> + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            YamlData::BadValue => {}
+            YamlData::Scalar(s) => s.hash(state),
+            YamlData::Sequence(s) => s.hash(state),
+            YamlData::Mapping(m) => m.hash(state),
+            YamlData::Tagged(tag, node) => {
+                tag.hash(state);
+                node.hash(state);
+            }
+            YamlData::Alias(a) => a.hash(state),
+        }
+    }
+}
+
+/// Values of different kinds sort as bad values, scalars, sequences, mappings, tagged nodes and
+/// then aliases. Mappings compare their entries in order.
+impl<NODE, FP, STR, INT, MAP> Ord for YamlData<'_, NODE, FP, INT, STR, MAP>
+where
+    NODE: Ord,
+    FP: Ord,
+    STR: Ord,
+    INT: Ord,
+    MAP: MappingLike<NODE> + Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (YamlData::BadValue, YamlData::BadValue) => Ordering::Equal,
+            (YamlData::Scalar(s1), YamlData::Scalar(s2)) => s1.cmp(s2),
+            (YamlData::Sequence(s1), YamlData::Sequence(s2)) => s1.cmp(s2),
+            (YamlData::Mapping(m1), YamlData::Mapping(m2)) => m1.cmp(m2),
+            (YamlData::Tagged(t1, b1), YamlData::Tagged(t2, b2)) => t1.cmp(t2).then(b1.cmp(b2)),
+            (YamlData::Alias(a1), YamlData::Alias(a2)) => a1.cmp(a2),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl<NODE, FP, STR, INT, MAP> PartialOrd for YamlData<'_, NODE, FP, INT, STR, MAP>
+where
+    NODE: Ord,
+    FP: Ord,
+    STR: Ord,
+    INT: Ord,
+    MAP: MappingLike<NODE> + Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<'input, Node, FP, STR, INT> From<YamlScalar<'input, FP, INT, STR>>
     for YamlData<'input, Node, FP, INT, STR>
 {
@@ -69,8 +145,21 @@ impl<Node, FP, STR, INT, MAP> YamlData<'_, Node, FP, INT, STR, MAP>
 where
     MAP: MappingLike<Node>,
 {
+    fn rank(&self) -> u8 {
+        match self {
+            YamlData::BadValue => 0,
+            YamlData::Scalar(_) => 1,
+            YamlData::Sequence(_) => 2,
+            YamlData::Mapping(_) => 3,
+            YamlData::Tagged(..) => 4,
+            YamlData::Alias(_) => 5,
+        }
+    }
+
+    /// Returns the simplified [`NodeType`] of this value.
     #[inline]
-    pub(crate) fn get_type(&self) -> NodeType {
+    #[must_use]
+    pub fn get_type(&self) -> NodeType {
         match &self {
             YamlData::Mapping(_) => NodeType::Mapping,
             YamlData::Sequence(_) => NodeType::Sequence,
@@ -85,6 +174,24 @@ where
     }
 }
 
+impl<'input, Node, FP, STR, INT, MAP> YamlData<'input, Node, FP, INT, STR, MAP>
+where
+    Node: YamlDocAccess<'input>,
+    MAP: MappingLike<Node>,
+{
+    /// Returns the value of a `!!timestamp` node loaded with
+    /// [`YamlLoader::standard_tags`](crate::prelude::YamlLoader::standard_tags).
+    #[must_use]
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match self {
+            YamlData::Tagged(tag, node) if tag.is_yaml_tag("timestamp") => {
+                node.as_str().and_then(Timestamp::parse)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl<'input, Node, FP, STR, INT, MAP> YamlData<'input, Node, FP, INT, STR, MAP>
 where
     Node: From<YamlData<'input, Node, FP, INT, STR, MAP>>,
@@ -97,24 +204,31 @@ where
         v: Cow<'input, str>,
         style: ScalarType,
         tag: Option<&Cow<'input, Tag>>,
+        schema: Schema,
     ) -> Self {
         match tag {
             Some(tag) if !tag.is_yaml_core_schema() => Self::Tagged(
                 tag.clone(),
-                Box::new(Self::value_from_cow_and_metadata(v, style, None).into()),
+                Box::new(Self::value_from_cow_and_metadata(v, style, None, schema).into()),
             ),
-            _ => YamlScalar::<FP, INT, STR>::parse_from_cow_and_metadata(v, style, tag.cloned())
-                .map_or(YamlData::BadValue, |x| YamlData::Scalar(x)),
+            _ => YamlScalar::<FP, INT, STR>::parse_from_cow_and_metadata_with_schema(
+                v,
+                style,
+                tag.cloned(),
+                schema,
+            )
+            .map_or(YamlData::BadValue, |x| YamlData::Scalar(x)),
         }
     }
 }
 
-impl<Node, FP, STR, INT> Clone for YamlData<'_, Node, FP, INT, STR>
+impl<Node, FP, STR, INT, MAP> Clone for YamlData<'_, Node, FP, INT, STR, MAP>
 where
     Node: Clone,
     FP: Copy,
     STR: Clone,
     INT: Copy,
+    MAP: MappingLike<Node> + Clone,
 {
     fn clone(&self) -> Self {
         match self {
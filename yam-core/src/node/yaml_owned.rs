@@ -1,6 +1,34 @@
 use crate::node::YamlData;
-use alloc::string::String;
+use crate::prelude::YamlScalar::Null;
+use crate::prelude::{
+    IsEmpty, NodeType, Span, Tag, Timestamp, Yaml, YamlAccessError, YamlDocAccess, YamlEntry,
+    YamlError, YamlLoader, YamlScalar,
+};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
 
+///
+/// The version of [`Yaml`] which owns its data, so it can outlive the input it was loaded from.
+///
+/// It is loaded with [`YamlLoader<YamlOwned>`], or converted from a [`Yaml`] with
+/// [`Yaml::into_owned`].
+///
+/// # Examples
+/// ```rust
+/// use yam_core::prelude::{YamlDocAccess, YamlOwned};
+///
+/// let config = {
+///     let input = String::from("name: yam\nports: [80, 443]");
+///     YamlOwned::load_single(&input).unwrap()
+/// };
+/// assert_eq!(config["name"].as_str(), Some("yam"));
+/// assert_eq!(config["ports"][1], YamlOwned::from(443));
+/// ```
 #[derive(PartialEq, Debug)]
 pub struct YamlOwned(pub YamlData<'static, Self, f64, i64, String>);
 
@@ -16,3 +44,462 @@ impl Clone for YamlOwned {
         }
     }
 }
+
+impl<'input> YamlDocAccess<'input> for YamlOwned {
+    type OutNode = Self;
+    type SequenceNode = Vec<Self>;
+    type MappingNode = Vec<YamlEntry<'static, Self>>;
+
+    #[allow(clippy::cast_lossless)]
+    fn key_from_usize(index: usize) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Integer(
+            index.cast_signed() as i64
+        )))
+    }
+
+    fn key_from_str(index: &str) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::String(index.to_string())))
+    }
+
+    fn is_non_empty_collection(&self) -> bool {
+        match &self.0 {
+            YamlData::Sequence(s) => !s.is_collection_empty(),
+            YamlData::Mapping(m) => !m.is_collection_empty(),
+            _ => false,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn as_bool_mut(&mut self) -> Option<&mut bool> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::Bool(b)) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::Integer(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn as_i64_mut(&mut self) -> Option<&mut i64> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::Integer(i)) => Some(i),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::FloatingPoint(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn as_f64_mut(&mut self) -> Option<&mut f64> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::FloatingPoint(f)) => Some(f),
+            _ => None,
+        }
+    }
+
+    fn as_sequence(&self) -> Result<&Self::SequenceNode, YamlAccessError> {
+        match &self.0 {
+            YamlData::Sequence(s) => Ok(s),
+            _ => Err(YamlAccessError::ExpectedSequence),
+        }
+    }
+
+    fn as_sequence_mut(&mut self) -> Result<&mut Self::SequenceNode, YamlAccessError> {
+        match &mut self.0 {
+            YamlData::Sequence(s) => Ok(s),
+            _ => Err(YamlAccessError::ExpectedSequence),
+        }
+    }
+
+    fn as_mapping(&self) -> Result<&Self::MappingNode, YamlAccessError> {
+        match &self.0 {
+            YamlData::Mapping(m) => Ok(m),
+            _ => Err(YamlAccessError::ExpectedMapping),
+        }
+    }
+
+    fn as_mapping_mut(&mut self) -> Result<&mut Self::MappingNode, YamlAccessError> {
+        match &mut self.0 {
+            YamlData::Mapping(m) => Ok(m),
+            _ => Err(YamlAccessError::ExpectedMapping),
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_str_mut(&mut self) -> Option<&mut str> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        self.0.as_timestamp()
+    }
+
+    fn sequence_mut(&mut self) -> &mut Self::SequenceNode {
+        match &mut self.0 {
+            YamlData::Sequence(s) => s,
+            _ => core::panic!("YamlData::sequence_mut() called with non-sequence"),
+        }
+    }
+
+    fn sequence(&self) -> &Self::SequenceNode {
+        match &self.0 {
+            YamlData::Sequence(s) => s,
+            _ => core::panic!("YamlData::sequence() called with non-sequence"),
+        }
+    }
+
+    fn mapping_mut(&mut self) -> &mut Self::MappingNode {
+        match &mut self.0 {
+            YamlData::Mapping(m) => m,
+            _ => core::panic!("YamlData::mapping_mut() called with non-mapping"),
+        }
+    }
+
+    fn mapping(&self) -> &Self::MappingNode {
+        match &self.0 {
+            YamlData::Mapping(m) => m,
+            _ => core::panic!("YamlData::mapping() called with non-mapping"),
+        }
+    }
+
+    fn get_tag(&self) -> Option<Tag> {
+        match &self.0 {
+            YamlData::Tagged(tag, ..) => Some(tag.clone().into_owned()),
+            _ => None,
+        }
+    }
+
+    fn tagged_node(&self) -> Option<&Self> {
+        match &self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn tagged_node_mut(&mut self) -> Option<&mut Self> {
+        match &mut self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn get_type(&self) -> NodeType {
+        self.0.get_type()
+    }
+
+    fn into_string(self) -> Option<String> {
+        match self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn into_mapping(self) -> Option<Self::MappingNode> {
+        match self.0 {
+            YamlData::Mapping(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn into_sequence(self) -> Option<Self::SequenceNode> {
+        match self.0 {
+            YamlData::Sequence(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn into_tagged(self, tag: Cow<'input, Tag>) -> Self {
+        YamlOwned(YamlData::Tagged(
+            Cow::Owned(tag.into_owned()),
+            Box::new(self),
+        ))
+    }
+
+    fn bad_span_value(_span: Span) -> Self {
+        YamlOwned(YamlData::BadValue)
+    }
+
+    fn bad_value() -> Self {
+        YamlOwned(YamlData::BadValue)
+    }
+
+    #[inline]
+    fn null() -> Self {
+        YamlOwned(YamlData::Scalar(Null(PhantomData)))
+    }
+}
+
+impl<'input> From<YamlData<'input, Self>> for YamlOwned {
+    fn from(value: YamlData<'input, Self>) -> Self {
+        YamlOwned(match value {
+            YamlData::BadValue => YamlData::BadValue,
+            YamlData::Scalar(s) => YamlData::Scalar(s.into_owned()),
+            YamlData::Sequence(s) => YamlData::Sequence(s),
+            YamlData::Mapping(m) => YamlData::Mapping(
+                m.into_iter()
+                    .map(|e| YamlEntry::new(e.key, e.value))
+                    .collect(),
+            ),
+            YamlData::Tagged(tag, node) => YamlData::Tagged(Cow::Owned(tag.into_owned()), node),
+            YamlData::Alias(a) => YamlData::Alias(a),
+        })
+    }
+}
+
+impl<'input> From<YamlScalar<'input>> for YamlOwned {
+    fn from(value: YamlScalar<'input>) -> Self {
+        YamlOwned(YamlData::Scalar(value.into_owned()))
+    }
+}
+
+impl From<Yaml<'_>> for YamlOwned {
+    fn from(value: Yaml<'_>) -> Self {
+        YamlOwned(match value.0 {
+            YamlData::BadValue => YamlData::BadValue,
+            YamlData::Scalar(s) => YamlData::Scalar(s.into_owned()),
+            YamlData::Sequence(s) => YamlData::Sequence(s.into_iter().map(Into::into).collect()),
+            YamlData::Mapping(m) => YamlData::Mapping(
+                m.into_iter()
+                    .map(|e| YamlEntry::new(e.key.into(), e.value.into()))
+                    .collect(),
+            ),
+            YamlData::Tagged(tag, node) => {
+                YamlData::Tagged(Cow::Owned(tag.into_owned()), Box::new((*node).into()))
+            }
+            YamlData::Alias(a) => YamlData::Alias(a),
+        })
+    }
+}
+
+impl YamlOwned {
+    ///
+    /// Loads all YAML documents from the given input string.
+    ///
+    /// # Errors
+    /// Returns a `YamlError` if the input is invalid YAML.
+    ///
+    /// # Example
+    /// ```
+    /// use yam_core::prelude::YamlOwned;
+    ///
+    /// let docs = YamlOwned::load_from("--- a\n--- b").unwrap();
+    /// assert_eq!(docs, [YamlOwned::from("a"), YamlOwned::from("b")]);
+    /// ```
+    pub fn load_from<S: AsRef<str>>(input: S) -> Result<Vec<YamlOwned>, YamlError> {
+        YamlLoader::<YamlOwned>::load_from(input)
+    }
+
+    ///
+    /// Loads the first YAML document from the given input string.
+    ///
+    /// # Errors
+    /// - `YamlError::NoDocument`: Returned if no valid YAML documents are found in the input.
+    /// - Any other error encountered during parsing, as propagated from the parser.
+    pub fn load_single<S: AsRef<str>>(input: S) -> Result<YamlOwned, YamlError> {
+        YamlLoader::<YamlOwned>::load_single(input)
+    }
+}
+
+impl Index<usize> for YamlOwned {
+    type Output = Self;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let typ = self.get_type();
+        let ind = YamlOwned::key_from_usize(index);
+        match typ {
+            NodeType::Mapping => &self.mapping().iter().find(|x| x.key == ind).unwrap().value,
+            NodeType::Sequence => self.sequence().index(index),
+            _ => panic!("Expected Mapping and Sequence got {0:?} instead", typ),
+        }
+    }
+}
+
+impl IndexMut<usize> for YamlOwned {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let typ = self.get_type();
+        let ind = YamlOwned::key_from_usize(index);
+        match typ {
+            NodeType::Mapping => {
+                &mut self
+                    .mapping_mut()
+                    .iter_mut()
+                    .find(|x| x.key == ind)
+                    .unwrap()
+                    .value
+            }
+            NodeType::Sequence => self.sequence_mut().index_mut(index),
+            _ => panic!("Expected Mapping and Sequence got {0:?} instead", typ),
+        }
+    }
+}
+
+impl<'k> Index<&'k str> for YamlOwned {
+    type Output = Self;
+
+    fn index(&self, index: &'k str) -> &Self::Output {
+        let typ = self.get_type();
+        let ind = YamlOwned::key_from_str(index);
+        match typ {
+            NodeType::Mapping => &self.mapping().iter().find(|x| x.key == ind).unwrap().value,
+            _ => panic!("Expected Mapping and Sequence got {0:?} instead", typ),
+        }
+    }
+}
+
+impl<'k> IndexMut<&'k str> for YamlOwned {
+    fn index_mut(&mut self, index: &'k str) -> &mut Self::Output {
+        let typ = self.get_type();
+        let ind = YamlOwned::key_from_str(index);
+        match typ {
+            NodeType::Mapping => {
+                &mut self
+                    .mapping_mut()
+                    .iter_mut()
+                    .find(|x| x.key == ind)
+                    .unwrap()
+                    .value
+            }
+            _ => panic!("Expected Mapping and Sequence got {0:?} instead", typ),
+        }
+    }
+}
+
+impl From<&str> for YamlOwned {
+    fn from(value: &str) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::String(value.to_string())))
+    }
+}
+
+impl From<String> for YamlOwned {
+    fn from(value: String) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::String(value)))
+    }
+}
+
+impl From<bool> for YamlOwned {
+    fn from(value: bool) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Bool(value)))
+    }
+}
+
+impl From<f64> for YamlOwned {
+    fn from(value: f64) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::FloatingPoint(value)))
+    }
+}
+
+#[allow(clippy::cast_lossless)]
+impl From<f32> for YamlOwned {
+    fn from(value: f32) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::FloatingPoint(value as f64)))
+    }
+}
+
+#[allow(clippy::cast_lossless)]
+impl From<i8> for YamlOwned {
+    fn from(value: i8) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+    }
+}
+
+#[allow(clippy::cast_lossless)]
+impl From<i16> for YamlOwned {
+    fn from(value: i16) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+    }
+}
+
+#[allow(clippy::cast_lossless)]
+impl From<i32> for YamlOwned {
+    fn from(value: i32) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+    }
+}
+
+impl From<i64> for YamlOwned {
+    fn from(value: i64) -> Self {
+        YamlOwned(YamlData::Scalar(YamlScalar::Integer(value)))
+    }
+}
+
+impl From<Vec<YamlOwned>> for YamlOwned {
+    fn from(value: Vec<YamlOwned>) -> Self {
+        YamlOwned(YamlData::Sequence(value))
+    }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for YamlOwned
+where
+    K: Into<YamlOwned>,
+    V: Into<YamlOwned>,
+{
+    fn from(value: BTreeMap<K, V>) -> Self {
+        YamlOwned(YamlData::Mapping(
+            value
+                .into_iter()
+                .map(|(k, v)| YamlEntry::new(k.into(), v.into()))
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parsing::Parser;
+    use crate::prelude::{Yaml, YamlDocAccess, YamlLoader, YamlOwned};
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[test]
+    fn test_load_owned() {
+        let input = String::from("a: &x [1, 2.5, true, ~]\nb: *x\nc: !t {d: e}\n");
+        let mut parser = Parser::new_from_str(&input);
+        let owned = YamlLoader::<YamlOwned>::load_from_parser(&mut parser).unwrap();
+        let converted: YamlOwned = Yaml::load_single(&input).unwrap().into_owned();
+        drop(input);
+
+        assert_eq!(owned[0], converted);
+        let mut doc = converted;
+        assert_eq!(doc["a"], doc["b"]);
+        assert_eq!(doc["a"][1].as_f64(), Some(2.5));
+        assert_eq!(doc["c"].get_tag().unwrap().suffix, "t");
+
+        doc["b"][0] = YamlOwned::from("one");
+        assert_eq!(doc["b"][0].as_str(), Some("one"));
+        *doc["a"][0].as_i64_mut().unwrap() += 1;
+        assert_eq!(doc["a"][0], YamlOwned::from(2));
+    }
+
+    #[test]
+    fn test_from_collections() {
+        let map = BTreeMap::from([("a", vec![YamlOwned::from(1)]), ("b", vec![])]);
+        let yaml = YamlOwned::from(map);
+        assert_eq!(yaml["a"][0], YamlOwned::from(1));
+        assert!(yaml["b"].is_sequence());
+        assert_eq!(yaml, YamlOwned::load_single("{a: [1], b: []}").unwrap());
+    }
+}
@@ -2,104 +2,186 @@ use crate::parsing::Source;
 use crate::parsing::char_utils::is_break;
 use crate::parsing::scanner::SkipTabs;
 use crate::parsing::source::shared_skip_ws_to_eol;
+use crate::prelude::YamlError;
 use crate::util::{BitOps, HIGH_NIBBLE_WS, LOW_NIBBLE_WS, U8X16, U8X32};
 use alloc::vec::Vec;
 use core::iter::Copied;
-use core::mem::{MaybeUninit, transmute};
-use core::slice;
 use core::slice::Iter;
 
+/// Number of bytes past the current position that are always available to peek at, unless the
+/// input ends sooner.
 const MAX_LEN: usize = 32;
+/// Default number of bytes requested from the input at once.
+const BLOCK_LEN: usize = 8 * 1024;
+
+///
+/// A reader of raw bytes, used by [`BufferedBytesSource`] to fetch its input one block at a time.
+///
+/// Any `Iterator<Item = u8>` is a `ByteRead`. With the `std` feature, `yam-std` adapts
+/// `std::io::Read`.
+pub trait ByteRead {
+    /// Reads up to `buf.len()` bytes into `buf` and returns how many were read, `0` meaning the
+    /// end of the input.
+    ///
+    /// # Errors
+    /// Returns a `YamlError` (usually [`YamlError::Io`]) when the input can't be read. The source
+    /// stops reading after the first error.
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize, YamlError>;
+}
 
-#[allow(dead_code)]
+impl<T: Iterator<Item = u8>> ByteRead for T {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize, YamlError> {
+        let mut len = 0;
+        for (slot, byte) in buf.iter_mut().zip(self) {
+            *slot = byte;
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+///
+/// A [`Source`] that reads its input in blocks from a [`ByteRead`], keeping only the unread part
+/// of the current block in memory.
+///
+/// The input is checked to be UTF-8 as it is read. Invalid UTF-8 and read errors end the input
+/// early, and the [`crate::parsing::Parser`] returns them as [`YamlError::Utf8`] and
+/// [`YamlError::Io`] in place of its next event.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::{BufferedBytesSource, Parser};
+/// use yam_core::prelude::{Yaml, YamlLoader, YamlDocAccess};
+///
+/// let input = "key: [1, 2]\n".bytes();
+/// let mut parser = Parser::new(BufferedBytesSource::new(input));
+/// let docs = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap();
+/// assert_eq!(docs[0]["key"][1].as_i64(), Some(2));
+/// ```
 pub struct BufferedBytesSource<T> {
     input: T,
-    buf: [MaybeUninit<u8>; MAX_LEN],
-    len: usize,
+    /// Bytes read from `input` and not yet dropped.
+    buf: Vec<u8>,
+    /// Position of the next unread byte in `buf`.
+    pos: usize,
+    /// Bytes of `buf` before this position are valid UTF-8. Only these can be peeked at.
+    valid: usize,
+    block_len: usize,
+    /// Whether `input` ended or failed.
+    eof: bool,
+    error: Option<YamlError>,
 }
 
-impl<T: Iterator<Item = u8>> BufferedBytesSource<T> {
-    #[allow(dead_code)]
+impl<T: ByteRead> BufferedBytesSource<T> {
+    /// Creates a source that reads `input` in blocks of 8 KiB.
     pub fn new(input: T) -> Self {
+        Self::with_block_len(input, BLOCK_LEN)
+    }
+
+    /// Creates a source that reads `input` in blocks of `block_len` bytes, or of the minimum
+    /// lookahead of the scanner if `block_len` is smaller.
+    pub fn with_block_len(input: T, block_len: usize) -> Self {
+        let block_len = block_len.max(MAX_LEN);
         let mut x = Self {
             input,
-            buf: [MaybeUninit::uninit(); MAX_LEN],
-            len: 0,
+            buf: Vec::with_capacity(block_len + MAX_LEN),
+            pos: 0,
+            valid: 0,
+            block_len,
+            eof: false,
+            error: None,
         };
-        x.fill_buf_to_max();
+        x.fill_buf();
         x
     }
 
-    fn fill_buf_to_max(&mut self) {
-        for x in self.len..self.buf_max_len() {
-            match self.input.next() {
-                Some(byt) => {
-                    // SAFETY: This will always be in buffer len
-                    unsafe {
-                        self.buf.get_unchecked_mut(x).write(byt);
-                    }
-                    self.len += 1;
+    /// Reads blocks until at least [`MAX_LEN`] bytes can be peeked at or the input ends.
+    fn fill_buf(&mut self) {
+        while !self.eof && self.valid - self.pos < MAX_LEN {
+            // Drop the consumed bytes, moving at most `MAX_LEN` and an incomplete character.
+            self.buf.drain(..self.pos);
+            self.valid -= self.pos;
+            self.pos = 0;
+
+            let start = self.buf.len();
+            self.buf.resize(start + self.block_len, 0);
+            match self.input.read_bytes(&mut self.buf[start..]) {
+                Ok(0) => {
+                    self.buf.truncate(start);
+                    self.eof = true;
+                }
+                Ok(len) => self.buf.truncate(start + len),
+                Err(err) => {
+                    self.buf.truncate(start);
+                    self.fail(err);
                 }
-                None => break,
             }
+            self.validate();
         }
     }
 
-    pub fn get_max_buf(&self) -> Option<[u8; MAX_LEN]> {
-        if self.len < self.buf_max_len() {
-            return None;
+    /// Moves `valid` past the bytes that are valid UTF-8, leaving a character that may continue
+    /// in the next block.
+    fn validate(&mut self) {
+        match str::from_utf8(&self.buf[self.valid..]) {
+            Ok(_) => self.valid = self.buf.len(),
+            Err(err) if err.error_len().is_none() && !self.eof => self.valid += err.valid_up_to(),
+            Err(err) => {
+                self.valid += err.valid_up_to();
+                self.fail(YamlError::Utf8(err));
+            }
         }
-        let buf = unsafe { transmute::<[MaybeUninit<u8>; MAX_LEN], [u8; MAX_LEN]>(self.buf) };
-        Some(buf)
     }
 
-    pub fn get_buf(&self) -> &[u8] {
-        unsafe {
-            // SAFETY: This is safe because self.len guarantees that the buf is initialized
-            // up to self.len position
-            slice::from_raw_parts(self.buf.as_ptr().cast(), self.len)
-        }
+    fn fail(&mut self, err: YamlError) {
+        self.eof = true;
+        self.error.get_or_insert(err);
+    }
+
+    fn available(&self) -> &[u8] {
+        &self.buf[self.pos..self.valid]
+    }
+
+    fn get_max_buf(&self) -> Option<[u8; MAX_LEN]> {
+        self.available().first_chunk().copied()
     }
 }
 
-#[allow(dead_code)]
 impl<'a> BufferedBytesSource<Copied<Iter<'a, u8>>> {
+    #[must_use]
     pub fn from_bytes(input: &'a [u8]) -> Self {
-        let mut x = Self {
-            input: input.iter().copied(),
-            buf: [MaybeUninit::uninit(); MAX_LEN],
-            len: 0,
-        };
-        x.fill_buf_to_max();
-        x
+        Self::new(input.iter().copied())
     }
 
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &'a str) -> Self {
         Self::from_bytes(input.as_bytes())
     }
 }
-unsafe impl<T: Iterator<Item = u8>> Source for BufferedBytesSource<T> {
+
+unsafe impl<T: ByteRead> Source for BufferedBytesSource<T> {
     unsafe fn peek_unsafe(&self, n: usize) -> u8 {
-        unsafe { self.buf[n].assume_init() }
+        unsafe { *self.buf.get_unchecked(self.pos + n) }
     }
 
     fn peek_checked(&self, n: usize) -> Option<u8> {
         debug_assert!(n < self.buf_max_len());
-        if n >= self.len {
-            return None;
-        }
-        unsafe { Some(self.peek_unsafe(n)) }
+        self.available().get(n).copied()
     }
 
     fn peek_char(&self) -> char {
-        // SAFETY: This takes up to 4 bytes. `4.min(self.len)` guarantees it won't see any
-        // uninit values
-        let slice = unsafe { slice::from_raw_parts(self.buf.as_ptr().cast(), 4.min(self.len)) };
-        // SAFETY: Converts up to the next 4 bytes (currently UTF-8) maximum
-        // to a char using from_utf8_unchecked. It should contain at least one valid char, which is
-        // all we care about.
-        let mut bytes = unsafe { str::from_utf8_unchecked(slice).chars() };
-        bytes.next().unwrap()
+        let buf = self.available();
+        let len = match buf.first() {
+            Some(0xF0..) => 4,
+            Some(0xE0..) => 3,
+            Some(0xC0..) => 2,
+            _ => 1,
+        };
+        str::from_utf8(&buf[..len.min(buf.len())])
+            .ok()
+            .and_then(|chr| chr.chars().next())
+            .unwrap_or('\u{FFFD}')
     }
 
     fn buf_max_len(&self) -> usize {
@@ -107,69 +189,61 @@ unsafe impl<T: Iterator<Item = u8>> Source for BufferedBytesSource<T> {
     }
 
     fn buf_is_empty(&self) -> bool {
-        self.len == 0
+        self.pos == self.valid
     }
 
     fn push_non_breakz_chr(&mut self, vec: &mut Vec<u8>) {
-        let mut pos = None;
         while let Some(x) = self.get_max_buf() {
             // bitmask for \r or \n
             let fake_simd = U8X32::from_array(x);
             let break_bitmask = fake_simd.comp_to_bitmask(b'\r') | fake_simd.comp_to_bitmask(b'\n');
-            let first_nl = break_bitmask.trailing_zeros() as usize;
 
             if break_bitmask != 0 {
-                pos = Some(first_nl);
-                break;
+                let first_nl = break_bitmask.trailing_zeros() as usize;
+                vec.extend_from_slice(&x[..first_nl]);
+                self.skip(first_nl);
+                return;
             }
 
             vec.extend_from_slice(&x[..]);
             self.skip(MAX_LEN);
         }
-        let buf = self.get_buf();
 
-        // we get the value from while loop above, or we search remaining buf
-        let found_pos = pos.unwrap_or(buf.iter().position(|&c| is_break(c)).unwrap_or(0));
-        vec.extend_from_slice(&buf[..found_pos]);
-        self.skip(found_pos);
+        // Less than `MAX_LEN` bytes are left before the end of the input.
+        let buf = self.available();
+        let len = buf.iter().position(|&c| is_break(c)).unwrap_or(buf.len());
+        vec.extend_from_slice(&buf[..len]);
+        self.skip(len);
     }
 
-    fn skip(&mut self, n: usize) {
-        if n == 0 {
-            return;
-        }
-
-        let consume = n.min(self.len);
-        let skip = n.saturating_sub(self.buf_max_len() + 1);
-
-        if skip > 0 {
-            self.input.nth(skip);
+    fn skip(&mut self, mut n: usize) {
+        while n > 0 && !self.buf_is_empty() {
+            let consume = n.min(self.valid - self.pos);
+            self.pos += consume;
+            n -= consume;
+            self.fill_buf();
         }
+    }
 
-        self.buf.copy_within(consume..self.len, 0);
-
-        let new_len = self.len.saturating_sub(consume);
-        self.len = new_len;
-        self.fill_buf_to_max();
+    fn take_error(&mut self) -> Option<YamlError> {
+        self.error.take()
     }
 
-    #[allow(clippy::cast_possible_truncation)]
     fn skip_ws_to_eol(
         &mut self,
         skip_tab: bool,
         mut has_yaml_ws: bool,
     ) -> (u32, Result<SkipTabs, &'static str>) {
-        let any_tabs = false;
+        let mut any_tabs = false;
         let mut consumed_bytes = 0u32;
-        let mut consume = 0u32;
-        let mut skip_tabs_res = SkipTabs::No;
 
         let low_nib_mask = U8X16::splat(0xF);
         let high_nib_mask = U8X16::splat(0x7F);
-        let ws_flag = 0x04 + u8::from(skip_tab);
+        let tab_flag = u8::from(skip_tab);
 
+        // Skip runs of blanks `MAX_LEN` bytes at a time, then let the shared implementation deal
+        // with comments and the end of the line.
         while let Some(x) = self.get_max_buf() {
-            consume = 0;
             let (v0, v1) = U8X32::from_array(x).split();
 
             let v_v0 = LOW_NIBBLE_WS.swizzle(v0 & low_nib_mask)
@@ -177,33 +251,21 @@ unsafe impl<T: Iterator<Item = u8>> Source for BufferedBytesSource<T> {
             let v_v1 = LOW_NIBBLE_WS.swizzle(v1 & low_nib_mask)
                 & HIGH_NIBBLE_WS.swizzle((v1 >> 4) & high_nib_mask);
 
-            let sp = !U8X32::merge(v_v0 & ws_flag, v_v1 & ws_flag)
+            let sp = !U8X32::merge(v_v0 & 0x04, v_v1 & 0x04).comp(0).to_bitmask();
+            let tab = !U8X32::merge(v_v0 & tab_flag, v_v1 & tab_flag)
                 .comp(0)
                 .to_bitmask();
-            let nl = !U8X32::merge(v_v0 & 0x02, v_v1 & 0x02).comp(0).to_bitmask();
-            let hash = !U8X32::merge(v_v0 & 0x08, v_v1 & 0x08).comp(0).to_bitmask();
 
-            let end_of_line = (hash & !(sp << 1)) | nl;
+            let blank_len = (!(sp | tab)).trailing_zeros();
+            let leading = 1u32.checked_shl(blank_len).map_or(u32::MAX, |bit| bit - 1);
+            has_yaml_ws |= sp & leading != 0;
+            any_tabs |= tab & leading != 0;
 
-            has_yaml_ws |= sp != 0;
-
-            if end_of_line != 0 {
-                consume = end_of_line.trailing_zeros();
-                consumed_bytes += consume;
-                skip_tabs_res = SkipTabs::Result {
-                    any_tabs,
-                    has_yaml_ws,
-                };
+            self.skip(blank_len as usize);
+            consumed_bytes += blank_len;
+            if blank_len < u32::BITS {
                 break;
             }
-
-            self.skip(self.buf_max_len());
-            consumed_bytes += self.buf_max_len() as u32;
-        }
-        self.skip(consume as usize);
-
-        if matches!(skip_tabs_res, SkipTabs::Result { .. } | SkipTabs::Yes) {
-            return (consumed_bytes, Ok(skip_tabs_res));
         }
 
         shared_skip_ws_to_eol(self, skip_tab, consumed_bytes, any_tabs, has_yaml_ws)
@@ -212,14 +274,16 @@ unsafe impl<T: Iterator<Item = u8>> Source for BufferedBytesSource<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::parsing::Source;
-    use crate::parsing::buffered_source::{BufferedBytesSource, MAX_LEN};
+    use crate::parsing::buffered_source::{BufferedBytesSource, ByteRead, MAX_LEN};
+    use crate::parsing::{Event, Parser, Source};
+    use crate::prelude::{Span, YamlError};
+    use alloc::string::ToString;
     use alloc::vec::Vec;
 
     #[test]
     fn test_create() {
         let source = BufferedBytesSource::from_bytes(b"Hello, world!");
-        assert_eq!(source.len, 13);
+        assert_eq!(source.available().len(), 13);
         assert_eq!(source.peekz(0), b'H');
         assert_eq!(source.peekz(1), b'e');
     }
@@ -227,7 +291,7 @@ mod tests {
     #[test]
     fn test_skip() {
         let mut source = BufferedBytesSource::from_bytes(b"Hello, world!");
-        assert_eq!(source.len, 13);
+        assert_eq!(source.available().len(), 13);
         assert_eq!(source.peekz(0), b'H');
         assert_eq!(source.peekz(1), b'e');
 
@@ -242,15 +306,18 @@ mod tests {
 
     #[test]
     fn test_skip_big() {
-        let mut source = BufferedBytesSource::from_bytes(
+        let mut source = BufferedBytesSource::with_block_len(
             br#"Lorem ipsum dolor sit amet, 
             consectetur adipiscing elit. Sed dui nulla, consectetur in pretium sit amet, 
             ornare vitae erat. Aenean bibendum arcu et risus auctor, 
             nec finibus arcu finibus. Integer ut congue metus, non hendrerit nunc. 
             Ut ornare efficitur nisl, sed ullamcorper risus feugiat at. 
-            Aenean ut mi a nulla pellentesque aliquet quis vitae lorem. Vestibulum semper elit"#,
+            Aenean ut mi a nulla pellentesque aliquet quis vitae lorem. Vestibulum semper elit"#
+                .iter()
+                .copied(),
+            MAX_LEN,
         );
-        assert_eq!(source.len, MAX_LEN);
+        assert_eq!(source.available().len(), MAX_LEN);
         assert_eq!(source.peekz(0), b'L');
         assert_eq!(source.peekz(1), b'o');
 
@@ -264,7 +331,7 @@ mod tests {
     #[test]
     fn test_create_empty() {
         let mut source = BufferedBytesSource::from_bytes(b"");
-        assert_eq!(source.len, 0);
+        assert!(source.buf_is_empty());
         assert_eq!(source.peekz(0), b'\0');
         assert_eq!(source.peekz(1), b'\0');
 
@@ -319,4 +386,69 @@ mod tests {
         assert!(res2.1.is_ok());
         assert_eq!(res2.0, 72);
     }
+
+    /// Collects the events up to the end of the stream or the first error.
+    fn collect_events<T: Source>(
+        mut parser: Parser<'_, T>,
+    ) -> Vec<Result<(Event<'static>, Span), YamlError>> {
+        let mut events = Vec::new();
+        while let Some(res) = parser.next_event() {
+            let is_err = res.is_err();
+            events.push(res.map(|(ev, span)| (ev.into_owned(), span)));
+            if is_err {
+                break;
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let input = "%YAML 1.2\n--- &a\nkey: value ünïcödé\n  continued\nblock: |\n  ✓ line\n\n  next\n\
+            flow: [\"dq\\u00e9\", 'sq', *a]\n? complex\n: ~\n...\n--- !!str text # comment\n";
+        let expected = collect_events(Parser::new_from_str(input));
+        assert!(expected.iter().all(Result::is_ok));
+        for block_len in [1, 33, 35, 64, 100, 8192] {
+            let source = BufferedBytesSource::with_block_len(input.bytes(), block_len);
+            let events = collect_events(Parser::new(source));
+            assert_eq!(events, expected, "block length {block_len}");
+        }
+    }
+
+    struct FailingRead(usize);
+
+    impl ByteRead for FailingRead {
+        fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize, YamlError> {
+            if self.0 == 0 {
+                return Err(YamlError::Io("connection reset".to_string()));
+            }
+            let len = self.0.min(buf.len());
+            buf[..len].fill(b'a');
+            self.0 -= len;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let events = collect_events(Parser::new(BufferedBytesSource::new(FailingRead(100))));
+        assert_eq!(
+            events.last(),
+            Some(&Err(YamlError::Io("connection reset".to_string())))
+        );
+        assert!(
+            !events
+                .iter()
+                .any(|ev| matches!(ev, Ok((Event::Scalar(_), _))))
+        );
+
+        let input = b"- ok\n- \xFF\n".iter().copied();
+        let events = collect_events(Parser::new(BufferedBytesSource::new(input)));
+        assert!(matches!(events.last(), Some(Err(YamlError::Utf8(_)))));
+
+        // A character cut at the end of the input isn't waiting for the next block.
+        let input = "- é".as_bytes()[..3].iter().copied();
+        let events = collect_events(Parser::new(BufferedBytesSource::new(input)));
+        assert!(matches!(events.last(), Some(Err(YamlError::Utf8(_)))));
+    }
 }
@@ -29,6 +29,18 @@ pub(crate) fn is_uri_char(c: u8) -> bool {
     is_word_char(c) || b"#;/?:@&=+$,_.!~*\'()[]%".contains(&c)
 }
 
+/// Check whether the byte continues a multibyte UTF-8 character.
+#[inline]
+pub(crate) fn is_utf8_continuation(c: u8) -> bool {
+    c & 0xC0 == 0x80
+}
+
+/// Count the characters of UTF-8 encoded `bytes`.
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn count_chars(bytes: &[u8]) -> u32 {
+    bytes.iter().filter(|&&c| !is_utf8_continuation(c)).count() as u32
+}
+
 pub(crate) fn is_break(c: u8) -> bool {
     c == b'\r' || c == b'\n'
 }
@@ -0,0 +1,371 @@
+use crate::parsing::ByteRead;
+use crate::prelude::YamlError;
+use alloc::vec::Vec;
+
+/// Number of undecoded bytes requested from the input at once.
+const RAW_BLOCK_LEN: usize = 4 * 1024;
+
+/// Length of the longest UTF-8 character.
+const MAX_CHAR_LEN: usize = 4;
+
+///
+/// The character encodings a YAML stream may use.
+///
+/// See [5.2. Character Encodings](https://yaml.org/spec/1.2.2/#52-character-encodings).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    /// Detects the encoding of a stream from its first (up to 4) bytes, using the byte order mark
+    /// or, without one, the pattern of null bytes around the first ASCII character.
+    ///
+    /// # Return
+    /// Returns the encoding and the length of the byte order mark, `0` if there is none.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Encoding;
+    ///
+    /// assert_eq!(Encoding::detect(b"\xFF\xFEa\0"), (Encoding::Utf16Le, 2));
+    /// assert_eq!(Encoding::detect(b"\0\0\0a"), (Encoding::Utf32Be, 0));
+    /// assert_eq!(Encoding::detect(b"a: b"), (Encoding::Utf8, 0));
+    /// ```
+    #[must_use]
+    pub fn detect(start: &[u8]) -> (Encoding, usize) {
+        match start {
+            [0x00, 0x00, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, 4),
+            [0x00, 0x00, 0x00, _, ..] => (Encoding::Utf32Be, 0),
+            [0xFF, 0xFE, 0x00, 0x00, ..] => (Encoding::Utf32Le, 4),
+            [_, 0x00, 0x00, 0x00, ..] => (Encoding::Utf32Le, 0),
+            [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+            [0x00, _, ..] => (Encoding::Utf16Be, 0),
+            [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+            [_, 0x00, ..] => (Encoding::Utf16Le, 0),
+            [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+            _ => (Encoding::Utf8, 0),
+        }
+    }
+
+    /// Size in bytes of the code unit.
+    fn unit_len(self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf32Le | Encoding::Utf32Be => 4,
+        }
+    }
+}
+
+///
+/// A [`ByteRead`] that detects the [`Encoding`] of its input and converts it to UTF-8, block by
+/// block, dropping the byte order mark.
+///
+/// Invalid input is reported as [`YamlError::InvalidEncoding`], with the offset of the invalid
+/// bytes in the original input.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::{BufferedBytesSource, DecodingRead, Parser};
+/// use yam_core::prelude::{Yaml, YamlDocAccess, YamlLoader};
+///
+/// let utf16: Vec<u8> = "\u{FEFF}key: välue".encode_utf16().flat_map(u16::to_le_bytes).collect();
+/// let source = BufferedBytesSource::new(DecodingRead::new(utf16.into_iter()));
+/// let docs = YamlLoader::<Yaml>::load_from_parser(&mut Parser::new(source)).unwrap();
+/// assert_eq!(docs[0]["key"].as_str(), Some("välue"));
+/// ```
+pub struct DecodingRead<T> {
+    input: T,
+    /// Bytes read from `input` and not decoded yet, starting at `start`.
+    raw: Vec<u8>,
+    start: usize,
+    /// Position of `raw[start]` in the input.
+    offset: usize,
+    encoding: Option<Encoding>,
+    eof: bool,
+    /// Decoded bytes of a character that didn't fit in the caller's buffer, from `spill_start` to
+    /// `spill_end`.
+    spill: [u8; MAX_CHAR_LEN],
+    spill_start: usize,
+    spill_end: usize,
+}
+
+impl<T: ByteRead> DecodingRead<T> {
+    pub fn new(input: T) -> Self {
+        DecodingRead {
+            input,
+            raw: Vec::new(),
+            start: 0,
+            offset: 0,
+            encoding: None,
+            eof: false,
+            spill: [0; MAX_CHAR_LEN],
+            spill_start: 0,
+            spill_end: 0,
+        }
+    }
+
+    /// Returns the detected encoding, or `None` if nothing was read yet.
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+
+    fn pending(&self) -> &[u8] {
+        &self.raw[self.start..]
+    }
+
+    /// Reads another block of the input after the pending bytes.
+    fn read_raw(&mut self) -> Result<(), YamlError> {
+        self.raw.drain(..self.start);
+        self.start = 0;
+        let len = self.raw.len();
+        self.raw.resize(len + RAW_BLOCK_LEN, 0);
+        let res = self.input.read_bytes(&mut self.raw[len..]);
+        let read = *res.as_ref().unwrap_or(&0);
+        self.raw.truncate(len + read);
+        self.eof = read == 0;
+        res.map(|_| ())
+    }
+
+    fn error(&self, at: usize, info: &'static str) -> YamlError {
+        YamlError::InvalidEncoding {
+            offset: self.offset + at,
+            info,
+        }
+    }
+
+    /// Converts complete characters of the pending bytes into `out`.
+    ///
+    /// # Return
+    /// Returns the number of bytes consumed from the pending bytes and written to `out`.
+    fn decode(&self, encoding: Encoding, out: &mut [u8]) -> Result<(usize, usize), YamlError> {
+        let raw = self.pending();
+        if encoding == Encoding::Utf8 {
+            let len = raw.len().min(out.len());
+            let valid = match str::from_utf8(&raw[..len]) {
+                Ok(_) => len,
+                Err(err) if err.error_len().is_some() => {
+                    return Err(self.error(err.valid_up_to(), "invalid UTF-8"));
+                }
+                Err(err) => err.valid_up_to(),
+            };
+            out[..valid].copy_from_slice(&raw[..valid]);
+            return Ok((valid, valid));
+        }
+
+        let unit_len = encoding.unit_len();
+        let unit = |pos: usize| -> u32 {
+            let bytes = &raw[pos..pos + unit_len];
+            match encoding {
+                Encoding::Utf16Le => u32::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                Encoding::Utf16Be => u32::from(u16::from_be_bytes([bytes[0], bytes[1]])),
+                Encoding::Utf32Le => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                _ => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            }
+        };
+
+        let (mut read, mut written) = (0, 0);
+        while read + unit_len <= raw.len() && written + 4 <= out.len() {
+            let mut code = unit(read);
+            let mut len = unit_len;
+            if unit_len == 2 && (0xD800..0xDC00).contains(&code) {
+                if read + 4 > raw.len() {
+                    // The low surrogate is in the next block.
+                    break;
+                }
+                let low = unit(read + 2);
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error(read, "unpaired UTF-16 surrogate"));
+                }
+                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                len = 4;
+            }
+            let Some(chr) = char::from_u32(code) else {
+                return Err(self.error(read, "invalid code point"));
+            };
+            written += chr.encode_utf8(&mut out[written..]).len();
+            read += len;
+        }
+        Ok((read, written))
+    }
+
+    /// Decodes into `buf`, which must fit at least one character, reading more input as needed.
+    fn read_decoded(&mut self, buf: &mut [u8]) -> Result<usize, YamlError> {
+        loop {
+            let encoding = match self.encoding {
+                Some(encoding) => encoding,
+                // The longest pattern to detect is 4 bytes.
+                None if self.pending().len() >= 4 || self.eof => {
+                    let (encoding, bom_len) = Encoding::detect(self.pending());
+                    self.start += bom_len;
+                    self.offset += bom_len;
+                    self.encoding = Some(encoding);
+                    encoding
+                }
+                None => {
+                    self.read_raw()?;
+                    continue;
+                }
+            };
+
+            let (read, written) = self.decode(encoding, buf)?;
+            self.start += read;
+            self.offset += read;
+            if written > 0 {
+                return Ok(written);
+            }
+            if self.eof {
+                return match self.pending() {
+                    [] => Ok(0),
+                    _ => Err(self.error(0, "truncated character at the end of the input")),
+                };
+            }
+            self.read_raw()?;
+        }
+    }
+
+    /// Moves bytes of the spilled character into `buf`.
+    fn read_spill(&mut self, buf: &mut [u8]) -> usize {
+        let len = (self.spill_end - self.spill_start).min(buf.len());
+        buf[..len].copy_from_slice(&self.spill[self.spill_start..self.spill_start + len]);
+        self.spill_start += len;
+        len
+    }
+}
+
+impl<T: ByteRead> ByteRead for DecodingRead<T> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize, YamlError> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.spill_start == self.spill_end && buf.len() < MAX_CHAR_LEN {
+            // Decode into the spill buffer, so that characters longer than `buf` still get out.
+            let mut spill = [0; MAX_CHAR_LEN];
+            self.spill_end = self.read_decoded(&mut spill)?;
+            self.spill_start = 0;
+            self.spill = spill;
+        }
+        if self.spill_start < self.spill_end {
+            return Ok(self.read_spill(buf));
+        }
+        self.read_decoded(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MAX_CHAR_LEN;
+    use crate::parsing::{BufferedBytesSource, ByteRead, DecodingRead, Encoding, Parser};
+    use crate::prelude::{Yaml, YamlError, YamlLoader};
+    use alloc::vec::Vec;
+
+    const INPUT: &str = "key: välue\nemoji: [😀, ✓]\nlong: ";
+
+    fn encode(input: &str, encoding: Encoding, bom: bool) -> Vec<u8> {
+        let input = if bom {
+            alloc::format!("\u{FEFF}{input}")
+        } else {
+            input.into()
+        };
+        match encoding {
+            Encoding::Utf8 => input.into_bytes(),
+            Encoding::Utf16Le => input.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            Encoding::Utf16Be => input.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            Encoding::Utf32Le => input
+                .chars()
+                .flat_map(|c| u32::from(c).to_le_bytes())
+                .collect(),
+            Encoding::Utf32Be => input
+                .chars()
+                .flat_map(|c| u32::from(c).to_be_bytes())
+                .collect(),
+        }
+    }
+
+    fn load(input: Vec<u8>) -> Result<Vec<Yaml<'static>>, YamlError> {
+        let source = BufferedBytesSource::with_block_len(DecodingRead::new(input.into_iter()), 33);
+        YamlLoader::load_from_parser(&mut Parser::new(source))
+    }
+
+    #[test]
+    fn test_decode() {
+        // Long enough to span several blocks of the source and of the decoder.
+        let input = alloc::format!("{INPUT}{}\n", "ü".repeat(5000));
+        let expected = Yaml::load_from(&input).unwrap();
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Utf32Le,
+            Encoding::Utf32Be,
+        ] {
+            for bom in [false, true] {
+                let bytes = encode(&input, encoding, bom);
+                assert_eq!(Encoding::detect(&bytes).0, encoding);
+                assert_eq!(load(bytes).unwrap(), expected, "{encoding:?}, BOM: {bom}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_small_reads() {
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Utf32Le,
+            Encoding::Utf32Be,
+        ] {
+            let bytes = encode(INPUT, encoding, true);
+            for len in 1..=MAX_CHAR_LEN {
+                let mut read = DecodingRead::new(bytes.iter().copied());
+                let mut buf = [0; MAX_CHAR_LEN];
+                let mut out = Vec::new();
+                loop {
+                    match read.read_bytes(&mut buf[..len]).unwrap() {
+                        0 => break,
+                        n => out.extend_from_slice(&buf[..n]),
+                    }
+                }
+                assert_eq!(out, INPUT.as_bytes(), "{encoding:?}, {len} byte reads");
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes = encode("a: b", Encoding::Utf16Le, true);
+        bytes.extend_from_slice(&[0x00, 0xD8, b'c', 0x00]);
+        assert_eq!(
+            load(bytes),
+            Err(YamlError::InvalidEncoding {
+                offset: 10,
+                info: "unpaired UTF-16 surrogate"
+            })
+        );
+
+        let mut bytes = encode("a: b", Encoding::Utf32Be, false);
+        bytes.extend_from_slice(&[0x00, 0x11, 0x00, 0x00]);
+        assert!(matches!(
+            load(bytes),
+            Err(YamlError::InvalidEncoding { offset: 16, .. })
+        ));
+
+        let mut bytes = encode("a: b", Encoding::Utf16Be, false);
+        bytes.push(b'c');
+        assert!(matches!(
+            load(bytes),
+            Err(YamlError::InvalidEncoding { offset: 8, .. })
+        ));
+
+        assert!(matches!(
+            load(b"a: \xC3(".to_vec()),
+            Err(YamlError::InvalidEncoding { offset: 3, .. })
+        ));
+    }
+}
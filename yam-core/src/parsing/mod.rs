@@ -2,21 +2,29 @@
 //!
 mod buffered_source;
 mod char_utils;
+#[cfg(feature = "encoding")]
+mod encoding;
 mod parser;
 pub mod parser_iter;
+mod push_parser;
 mod scanner;
 mod source;
 
 use crate::prelude::{ScalarType, YamlError};
 use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
+pub use buffered_source::{BufferedBytesSource, ByteRead};
 use core::fmt;
 use core::fmt::{Display, Formatter};
+#[cfg(feature = "encoding")]
+pub use encoding::{DecodingRead, Encoding};
 pub use parser::EventReceiver;
 pub use parser::SpannedEventReceiver;
 pub use parser::{Event, Parser, ScalarValue};
 pub use parser_iter::ParserIter;
+pub use push_parser::{PushEvent, PushParser};
 pub use source::Source;
 pub use source::StrSource;
 
@@ -67,6 +75,7 @@ pub enum TokenType<'input> {
     Scalar {
         scalar_type: ScalarType,
         value: Cow<'input, str>,
+        block: Option<BlockIndicators>,
     },
 }
 
@@ -104,7 +113,7 @@ impl<'input> TokenType<'input> {
 }
 
 /// Chomp indicator of target block scalar
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum ChompIndicator {
     /// `-` final line break and any trailing empty lines are excluded from the scalar’s content
     Strip,
@@ -114,6 +123,31 @@ pub enum ChompIndicator {
     Keep,
 }
 
+/// Header indicators of a block scalar (`|` or `>`), e.g. `|2-` or `>+`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct BlockIndicators {
+    /// Chomping indicator, [`ChompIndicator::Clip`] when none was written.
+    pub chomp: ChompIndicator,
+    /// Explicit indentation indicator (`1`-`9`), if one was written.
+    pub indent: Option<u8>,
+}
+
+///
+/// Directives in effect for a YAML document.
+///
+/// * `version` - The version given by the `%YAML` directive, e.g. `Some((1, 1))` for `%YAML 1.1`.
+///   `None` when the document has no `%YAML` directive.
+/// * `tags` - Active `%TAG` directives, mapping each handle (e.g. `!e!`) to its prefix.
+///   When [`Parser::keep_tags`] is set, these include the directives of previous documents.
+///
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Directives {
+    /// `(major, minor)` version from the `%YAML` directive, if any.
+    pub version: Option<(u8, u8)>,
+    /// Active `%TAG` directives as `handle -> prefix`.
+    pub tags: BTreeMap<String, String>,
+}
+
 ///
 /// Represents the type of directives that can be encountered.
 ///
@@ -235,6 +269,12 @@ impl Tag {
     pub fn is_yaml_core_schema(&self) -> bool {
         self.handle == "tag:yaml.org,2002:"
     }
+
+    /// Returns whether the tag is the YAML tag with the given suffix, e.g. `!!set` for `"set"`.
+    #[must_use]
+    pub fn is_yaml_tag(&self, suffix: &str) -> bool {
+        self.is_yaml_core_schema() && self.suffix == suffix
+    }
 }
 
 impl Display for Tag {
@@ -6,8 +6,8 @@
 
 use crate::parsing::scanner::{Scanner, Token};
 pub(crate) use crate::parsing::source::{Source, StrSource};
-use crate::parsing::{Tag, TokenType};
-use crate::prelude::{Marker, ScalarType, Span, YamlError};
+use crate::parsing::{BlockIndicators, Directives, Tag, TokenType};
+use crate::prelude::{CollectionStyle, Marker, ScalarType, Span, YamlError};
 use alloc::{
     borrow::Cow,
     collections::BTreeMap,
@@ -15,6 +15,7 @@ use alloc::{
     vec::Vec,
 };
 use core::fmt::{Debug, Display, Formatter};
+use core::mem;
 
 ///
 /// Represents a scalar value parsed from input, which includes its type, value, associated metadata,
@@ -40,9 +41,13 @@ pub struct ScalarValue<'input> {
     /// A unique identifier for the anchor associated with this scalar value, if any.
     // ///   Used in cases where the scalar value is part of anchored references in a YAML-like format.
     pub anchor_id: usize,
+    /// The name of the anchor associated with this scalar value, if any. E.g. `x` for `&x value`.
+    pub anchor_name: Option<Cow<'input, str>>,
     /// An optional tag providing additional metadata for the value. This specifies or overrides the interpretation of the scalar data,
     ///   and can also be either owned or borrowed.
     pub tag: Option<Cow<'input, Tag>>,
+    /// Chomping and indentation indicators, present only for block scalars (`|` and `>`).
+    pub block_indicators: Option<BlockIndicators>,
 }
 
 impl Display for ScalarValue<'_> {
@@ -52,6 +57,19 @@ impl Display for ScalarValue<'_> {
 }
 
 impl<'input> ScalarValue<'input> {
+    /// Converts the scalar into one that owns its value, anchor name and tag.
+    #[must_use]
+    pub fn into_owned(self) -> ScalarValue<'static> {
+        ScalarValue {
+            value: Cow::Owned(self.value.into_owned()),
+            scalar_type: self.scalar_type,
+            anchor_id: self.anchor_id,
+            anchor_name: self.anchor_name.map(|name| Cow::Owned(name.into_owned())),
+            tag: self.tag.map(|tag| Cow::Owned(tag.into_owned())),
+            block_indicators: self.block_indicators,
+        }
+    }
+
     /// Create an empty scalar.
     pub(crate) fn empty_scalar() -> Self {
         // a null scalar
@@ -59,20 +77,25 @@ impl<'input> ScalarValue<'input> {
             value: "".into(),
             scalar_type: ScalarType::Plain,
             anchor_id: 0,
+            anchor_name: None,
             tag: None,
+            block_indicators: None,
         }
     }
 
     /// Create an empty scalar with the given anchor.
     pub(crate) fn empty_scalar_with_anchor(
         anchor_id: usize,
+        anchor_name: Option<Cow<'input, str>>,
         tag: Option<Cow<'input, Tag>>,
     ) -> Self {
         ScalarValue {
             value: Cow::default(),
             scalar_type: ScalarType::Plain,
             anchor_id,
+            anchor_name,
             tag,
+            block_indicators: None,
         }
     }
 
@@ -145,13 +168,18 @@ pub enum Event<'input> {
     ///
     /// When the boolean is `false`, it is an implicit document start
     /// (without `---`).
-    DocumentStart(bool),
+    ///
+    /// The [`Directives`] hold the `%YAML` version and the `%TAG` directives active for the
+    /// document.
+    DocumentStart(bool, Directives),
     /// The YAML end document directive (`...`).
     DocumentEnd,
     /// A YAML Alias.
     Alias(
         /// The anchor ID the alias refers to.
         usize,
+        /// The name of the anchor the alias refers to.
+        Cow<'input, str>,
     ),
     /// Comment in code
     Comment(Cow<'input, str>),
@@ -162,8 +190,12 @@ pub enum Event<'input> {
     SequenceStart(
         /// The anchor ID of the start of the sequence.
         usize,
+        /// The anchor name of the sequence, if any.
+        Option<Cow<'input, str>>,
         /// An optional tag
         Option<Cow<'input, Tag>>,
+        /// Whether the sequence is written in block or flow style (`[a, b]`).
+        CollectionStyle,
     ),
     /// The end of a YAML sequence (array).
     SequenceEnd,
@@ -171,8 +203,12 @@ pub enum Event<'input> {
     MappingStart(
         /// The anchor ID of the start of the mapping.
         usize,
+        /// The anchor name of the mapping, if any.
+        Option<Cow<'input, str>>,
         /// An optional tag
         Option<Cow<'input, Tag>>,
+        /// Whether the mapping is written in block or flow style (`{a: b}`).
+        CollectionStyle,
     ),
     /// The end of a YAML mapping (object, hash).
     MappingEnd,
@@ -192,10 +228,10 @@ impl<'input> Event<'input> {
     ///
     /// # Example
     /// ```
-    /// use yam_core::parsing::{Event,ScalarValue};
+    /// use yam_core::parsing::{Directives, Event, ScalarValue};
     ///
     /// let scalar_event = Event::Scalar(ScalarValue::default());
-    /// let other_event = Event::DocumentStart(true);
+    /// let other_event = Event::DocumentStart(true, Directives::default());
     ///
     /// assert!(scalar_event.is_scalar());
     /// assert!(!other_event.is_scalar());
@@ -212,17 +248,57 @@ impl<'input> Event<'input> {
     ///
     /// # Example
     /// ```
-    /// use yam_core::parsing::{Event,ScalarValue};
+    /// use yam_core::parsing::{Directives, Event, ScalarValue};
     ///
     /// let scalar_event = Event::Scalar(ScalarValue::default());
-    /// let other_event = Event::DocumentStart(true);
+    /// let other_event = Event::DocumentStart(true, Directives::default());
     ///
     /// assert!(!scalar_event.is_doc_start());
     /// assert!(other_event.is_doc_start());
     /// ```
     #[must_use]
     pub fn is_doc_start(&self) -> bool {
-        matches!(self, Event::DocumentStart(_))
+        matches!(self, Event::DocumentStart(..))
+    }
+
+    /// Converts the event into one that doesn't borrow from the input.
+    ///
+    /// # Example
+    /// ```
+    /// use yam_core::parsing::{Event, Parser};
+    ///
+    /// let events: Vec<Event<'static>> = {
+    ///     let input = String::from("a");
+    ///     Parser::new_from_str(&input)
+    ///         .map(|ev| ev.unwrap().0.into_owned())
+    ///         .collect()
+    /// };
+    /// assert!(events[2].is_scalar());
+    /// ```
+    #[must_use]
+    pub fn into_owned(self) -> Event<'static> {
+        let owned = |s: Cow<'input, str>| Cow::Owned(s.into_owned());
+        let owned_tag = |t: Cow<'input, Tag>| Cow::Owned(t.into_owned());
+        match self {
+            Event::Nothing => Event::Nothing,
+            Event::StreamStart => Event::StreamStart,
+            Event::StreamEnd => Event::StreamEnd,
+            Event::DocumentStart(explicit, directives) => {
+                Event::DocumentStart(explicit, directives)
+            }
+            Event::DocumentEnd => Event::DocumentEnd,
+            Event::Alias(id, name) => Event::Alias(id, owned(name)),
+            Event::Comment(comment) => Event::Comment(owned(comment)),
+            Event::Scalar(scalar) => Event::Scalar(scalar.into_owned()),
+            Event::SequenceStart(id, anchor, tag, style) => {
+                Event::SequenceStart(id, anchor.map(owned), tag.map(owned_tag), style)
+            }
+            Event::SequenceEnd => Event::SequenceEnd,
+            Event::MappingStart(id, anchor, tag, style) => {
+                Event::MappingStart(id, anchor.map(owned), tag.map(owned_tag), style)
+            }
+            Event::MappingEnd => Event::MappingEnd,
+        }
     }
 
     #[must_use]
@@ -239,20 +315,21 @@ impl<'input> Event<'input> {
             Event::Nothing => "Nothing",
             Event::StreamStart => "StreamStart",
             Event::StreamEnd => "StreamEnd",
-            Event::DocumentStart(_) => "DocumentStart",
+            Event::DocumentStart(..) => "DocumentStart",
             Event::DocumentEnd => "DocumentEnd",
-            Event::Alias(_) => "Alias",
+            Event::Alias(..) => "Alias",
             Event::Comment(_) => "Comment",
             Event::Scalar(_) => "Scalar",
-            Event::SequenceStart(_, _) => "SequenceStart",
+            Event::SequenceStart(..) => "SequenceStart",
             Event::SequenceEnd => "SequenceEnd",
-            Event::MappingStart(_, _) => "MappingStart",
+            Event::MappingStart(..) => "MappingStart",
             Event::MappingEnd => "MappingEnd",
         }
     }
 }
 
 /// A YAML parser.
+#[derive(Clone)]
 pub struct Parser<'input, T: Source> {
     /// The underlying scanner from which we pull tokens.
     scanner: Scanner<'input, T>,
@@ -284,6 +361,23 @@ pub struct Parser<'input, T: Source> {
     stream_end_emitted: bool,
     /// Make tags global across all documents.
     keep_tags: bool,
+    /// Emit aliases to unknown anchors instead of returning an error.
+    allow_undefined_aliases: bool,
+    /// Changes made to `anchors` and `tags` since [`Self::checkpoint`], oldest first.
+    ///
+    /// `None` when no checkpoint is kept.
+    undo: Option<Vec<Undo<'input>>>,
+}
+
+/// A change to the anchors or tags of a [`Parser`], recorded so it can be undone.
+#[derive(Clone)]
+enum Undo<'input> {
+    /// An anchor was defined, replacing the ID it had before, if any.
+    Anchor(Cow<'input, str>, Option<usize>),
+    /// The anchors were cleared.
+    Anchors(BTreeMap<Cow<'input, str>, usize>),
+    /// The tag directives were changed.
+    Tags(BTreeMap<String, String>),
 }
 
 ///
@@ -421,6 +515,8 @@ impl<'input, T: Source> Parser<'input, T> {
             tags: BTreeMap::new(),
             stream_end_emitted: false,
             keep_tags: false,
+            allow_undefined_aliases: false,
+            undo: None,
         }
     }
 
@@ -452,6 +548,29 @@ impl<'input, T: Source> Parser<'input, T> {
         self
     }
 
+    /// Tag handles in effect before the first document, as `handle -> prefix`.
+    ///
+    /// They behave as if a previous document declared them with `%TAG` directives, so this is
+    /// mostly useful together with [`Parser::keep_tags`], e.g. to continue parsing a stream from
+    /// the middle. Without `keep_tags` they only apply to the first document.
+    #[must_use]
+    pub fn tag_directives(mut self, tags: BTreeMap<String, String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Whether to accept aliases that refer to an anchor not defined earlier in the document.
+    ///
+    /// By default, an alias like `*unknown` results in a [`YamlError::UndefinedAlias`]. With
+    /// `allow_undefined_aliases` set to `true` the parser instead emits [`Event::Alias`] with
+    /// anchor id `0`, which is never assigned to an anchor. Loaders can then decide what to do
+    /// with it, see [`crate::prelude::YamlLoader::allow_undefined_aliases`].
+    #[must_use]
+    pub fn allow_undefined_aliases(mut self, value: bool) -> Self {
+        self.allow_undefined_aliases = value;
+        self
+    }
+
     /// Try to load the next event and return it, but do not consuming it from `self`.
     ///
     /// Any subsequent call to [`Parser::peek`] will return the same value, until a call to
@@ -508,6 +627,54 @@ impl<'input, T: Source> Parser<'input, T> {
         self.anchors.iter().find(|x| *x.1 == anchor_id).map(|x| x.0)
     }
 
+    /// The source the parser reads from.
+    pub(crate) fn source_mut(&mut self) -> &mut T {
+        self.scanner.source_mut()
+    }
+
+    /// Copies the state of the parser to go back to with [`Self::restore`].
+    ///
+    /// The copy leaves out the anchors and tags, which grow with the input. The changes made to
+    /// them from now on are recorded instead, until [`Self::restore`] or
+    /// [`Self::drop_checkpoint`].
+    pub(crate) fn checkpoint(&mut self) -> Self
+    where
+        T: Clone,
+    {
+        let anchors = mem::take(&mut self.anchors);
+        let tags = mem::take(&mut self.tags);
+        self.undo = None;
+        let checkpoint = self.clone();
+        self.anchors = anchors;
+        self.tags = tags;
+        self.undo = Some(Vec::new());
+        checkpoint
+    }
+
+    /// Goes back to `checkpoint`, taken with [`Self::checkpoint`] from this parser.
+    pub(crate) fn restore(&mut self, mut checkpoint: Self) {
+        for change in self.undo.take().unwrap_or_default().into_iter().rev() {
+            match change {
+                Undo::Anchor(name, Some(id)) => {
+                    self.anchors.insert(name, id);
+                }
+                Undo::Anchor(name, None) => {
+                    self.anchors.remove(&name);
+                }
+                Undo::Anchors(anchors) => self.anchors = anchors,
+                Undo::Tags(tags) => self.tags = tags,
+            }
+        }
+        checkpoint.anchors = mem::take(&mut self.anchors);
+        checkpoint.tags = mem::take(&mut self.tags);
+        *self = checkpoint;
+    }
+
+    /// Stops recording the changes for a checkpoint that won't be restored.
+    pub(crate) fn drop_checkpoint(&mut self) {
+        self.undo = None;
+    }
+
     /// Implementation function for [`Self::next_event`] without the `Option`.
     ///
     /// [`Self::next_event`] should conform to the expectations of an [`Iterator`] and return an
@@ -520,9 +687,14 @@ impl<'input, T: Source> Parser<'input, T> {
     where
         'input: 'a,
     {
-        match self.current.take() {
+        let res = match self.current.take() {
             None => self.parse(),
             Some(v) => Ok(v),
+        };
+        // Events after an early end of the input (a read error or invalid UTF-8) can't be trusted.
+        match self.scanner.take_source_error() {
+            Some(err) => Err(err),
+            None => res,
         }
     }
 
@@ -648,8 +820,6 @@ impl<'input, T: Source> Parser<'input, T> {
                 recv.on_event(ev, span);
                 return Ok(());
             }
-            // clear anchors before a new document
-            self.anchors.clear();
             self.load_document(ev, span, recv)?;
             if !multi {
                 break;
@@ -664,7 +834,7 @@ impl<'input, T: Source> Parser<'input, T> {
         span: Span,
         recv: &mut R,
     ) -> Result<(), YamlError> {
-        if !matches!(first_ev, Event::DocumentStart(_)) {
+        if !matches!(first_ev, Event::DocumentStart(..)) {
             return Err(YamlError::new_str(
                 span.start,
                 "did not find expected <document-start>",
@@ -845,10 +1015,10 @@ impl<'input, T: Source> Parser<'input, T> {
                 self.explicit_document_start()
             }
             Token { span, .. } if implicit => {
-                self.parser_process_directives()?;
+                let directives = self.parser_process_directives()?;
                 self.push_state(State::DocumentEnd);
                 self.state = State::BlockNode;
-                Ok((Event::DocumentStart(false), span))
+                Ok((Event::DocumentStart(false, directives), span))
             }
             _ => {
                 // explicit document
@@ -873,27 +1043,27 @@ impl<'input, T: Source> Parser<'input, T> {
         }
     }
 
-    fn parser_process_directives(&mut self) -> Result<(), YamlError> {
-        let mut version_directive_received = false;
+    fn parser_process_directives(&mut self) -> Result<Directives, YamlError> {
+        let mut version = None;
+        let mut tags = BTreeMap::new();
         loop {
-            let mut tags = BTreeMap::new();
             match self.peek_token()? {
                 Token {
                     span,
-                    token_type: TokenType::VersionDirective { .. },
+                    token_type: TokenType::VersionDirective { major, minor },
                 } => {
                     // XXX parsing with warning according to spec
                     //if major != 1 || minor > 2 {
                     //    return Err(YamlResult::new_str(tok.0,
                     //        "found incompatible YAML document"));
                     //}
-                    if version_directive_received {
+                    if version.is_some() {
                         return Err(YamlError::new_str(
                             span.start,
                             "duplicate version directive",
                         ));
                     }
-                    version_directive_received = true;
+                    version = Some((*major, *minor));
                 }
                 Token {
                     span,
@@ -909,17 +1079,23 @@ impl<'input, T: Source> Parser<'input, T> {
                 }
                 _ => break,
             }
-            self.tags = tags;
             self.skip();
         }
-        Ok(())
+        if let Some(undo) = &mut self.undo {
+            undo.push(Undo::Tags(self.tags.clone()));
+        }
+        self.tags.extend(tags);
+        Ok(Directives {
+            version,
+            tags: self.tags.clone(),
+        })
     }
 
     fn explicit_document_start<'a>(&mut self) -> ParseResult<'a>
     where
         'input: 'a,
     {
-        self.parser_process_directives()?;
+        let directives = self.parser_process_directives()?;
         match *self.peek_token()? {
             Token {
                 span,
@@ -928,7 +1104,7 @@ impl<'input, T: Source> Parser<'input, T> {
                 self.push_state(State::DocumentEnd);
                 self.state = State::DocumentContent;
                 self.skip();
-                Ok((Event::DocumentStart(true), span))
+                Ok((Event::DocumentStart(true, directives), span))
             }
             Token { span, .. } => Err(YamlError::new_str(
                 span.start,
@@ -977,7 +1153,15 @@ impl<'input, T: Source> Parser<'input, T> {
         };
 
         if !self.keep_tags {
-            self.tags.clear();
+            let tags = mem::take(&mut self.tags);
+            if let Some(undo) = &mut self.undo {
+                undo.push(Undo::Tags(tags));
+            }
+        }
+        // anchors are only valid within the document that defined them
+        let anchors = mem::take(&mut self.anchors);
+        if let Some(undo) = &mut self.undo {
+            undo.push(Undo::Anchors(anchors));
         }
         if explicit_end {
             self.state = State::ImplicitDocumentStart;
@@ -1006,7 +1190,12 @@ impl<'input, T: Source> Parser<'input, T> {
         // }
         let new_id = self.anchor_id_count;
         self.anchor_id_count += 1;
-        self.anchors.insert(name, new_id);
+        if let Some(undo) = &mut self.undo {
+            let old = self.anchors.insert(name.clone(), new_id);
+            undo.push(Undo::Anchor(name, old));
+        } else {
+            self.anchors.insert(name, new_id);
+        }
         new_id
     }
 
@@ -1016,6 +1205,7 @@ impl<'input, T: Source> Parser<'input, T> {
         'input: 'a,
     {
         let mut anchor_id = 0;
+        let mut anchor_name = None;
         let mut tag = None;
         match *self.peek_token()? {
             Token {
@@ -1029,11 +1219,12 @@ impl<'input, T: Source> Parser<'input, T> {
                 } = self.fetch_token()
                 {
                     return match self.anchors.get(&*name) {
-                        None => Err(YamlError::new_str(
-                            span.start,
-                            "while parsing node, found unknown anchor",
-                        )),
-                        Some(id) => Ok((Event::Alias(*id), span)),
+                        None if self.allow_undefined_aliases => Ok((Event::Alias(0, name), span)),
+                        None => Err(YamlError::UndefinedAlias {
+                            mark: span.start,
+                            name: name.into_owned(),
+                        }),
+                        Some(id) => Ok((Event::Alias(*id, name), span)),
                     };
                 }
                 unreachable!()
@@ -1047,6 +1238,7 @@ impl<'input, T: Source> Parser<'input, T> {
                     token_type: TokenType::Anchor(name),
                 } = self.fetch_token()
                 {
+                    anchor_name = Some(name.clone());
                     anchor_id = self.register_anchor(name, &span);
                     if let TokenType::Tag { .. } = self.peek_token()?.token_type {
                         if let TokenType::Tag { handle, suffix } = self.fetch_token().token_type {
@@ -1071,6 +1263,7 @@ impl<'input, T: Source> Parser<'input, T> {
                             token_type: TokenType::Anchor(name),
                         } = self.fetch_token()
                         {
+                            anchor_name = Some(name.clone());
                             anchor_id = self.register_anchor(name, &span);
                         } else {
                             unreachable!()
@@ -1088,7 +1281,10 @@ impl<'input, T: Source> Parser<'input, T> {
                 token_type: TokenType::BlockEntry,
             } if indentless_sequence => {
                 self.state = State::IndentlessSequenceEntry;
-                Ok((Event::SequenceStart(anchor_id, tag), span))
+                Ok((
+                    Event::SequenceStart(anchor_id, anchor_name, tag, CollectionStyle::Block),
+                    span,
+                ))
             }
             #[cfg(feature = "comment")]
             Token {
@@ -1102,7 +1298,12 @@ impl<'input, T: Source> Parser<'input, T> {
                 self.pop_state();
                 if let Token {
                     span,
-                    token_type: TokenType::Scalar { scalar_type, value },
+                    token_type:
+                        TokenType::Scalar {
+                            scalar_type,
+                            value,
+                            block,
+                        },
                 } = self.fetch_token()
                 {
                     Ok((
@@ -1110,7 +1311,9 @@ impl<'input, T: Source> Parser<'input, T> {
                             value,
                             scalar_type,
                             anchor_id,
+                            anchor_name,
                             tag,
+                            block_indicators: block,
                         }),
                         span,
                     ))
@@ -1123,34 +1326,50 @@ impl<'input, T: Source> Parser<'input, T> {
                 token_type: TokenType::FlowSequenceStart,
             } => {
                 self.state = State::FlowSequenceFirstEntry;
-                Ok((Event::SequenceStart(anchor_id, tag), span))
+                Ok((
+                    Event::SequenceStart(anchor_id, anchor_name, tag, CollectionStyle::Flow),
+                    span,
+                ))
             }
             Token {
                 span,
                 token_type: TokenType::FlowMappingStart,
             } => {
                 self.state = State::FlowMappingFirstKey;
-                Ok((Event::MappingStart(anchor_id, tag), span))
+                Ok((
+                    Event::MappingStart(anchor_id, anchor_name, tag, CollectionStyle::Flow),
+                    span,
+                ))
             }
             Token {
                 span,
                 token_type: TokenType::BlockSequenceStart,
             } if block => {
                 self.state = State::BlockSequenceFirstEntry;
-                Ok((Event::SequenceStart(anchor_id, tag), span))
+                Ok((
+                    Event::SequenceStart(anchor_id, anchor_name, tag, CollectionStyle::Block),
+                    span,
+                ))
             }
             Token {
                 span,
                 token_type: TokenType::BlockMappingStart,
             } if block => {
                 self.state = State::BlockMappingFirstKey;
-                Ok((Event::MappingStart(anchor_id, tag), span))
+                Ok((
+                    Event::MappingStart(anchor_id, anchor_name, tag, CollectionStyle::Block),
+                    span,
+                ))
             }
             // ex 7.2, an empty scalar can follow a secondary tag
             Token { span, .. } if tag.is_some() || anchor_id > 0 => {
                 self.pop_state();
                 Ok((
-                    Event::Scalar(ScalarValue::empty_scalar_with_anchor(anchor_id, tag)),
+                    Event::Scalar(ScalarValue::empty_scalar_with_anchor(
+                        anchor_id,
+                        anchor_name,
+                        tag,
+                    )),
                     span,
                 ))
             }
@@ -1435,7 +1654,10 @@ impl<'input, T: Source> Parser<'input, T> {
             } => {
                 self.state = State::FlowSequenceEntryMappingKey;
                 self.skip();
-                Ok((Event::MappingStart(0, None), span))
+                Ok((
+                    Event::MappingStart(0, None, None, CollectionStyle::Flow),
+                    span,
+                ))
             }
             _ => {
                 self.push_state(State::FlowSequenceEntry);
@@ -130,7 +130,7 @@ impl<'de, R: Source> ParserIter<'de, R> {
         };
         match ev {
             // Ignored events
-            Event::Nothing | Event::Alias(_) | Event::Comment(_) => ControlFlow::Continue(()),
+            Event::Nothing | Event::Alias(..) | Event::Comment(_) => ControlFlow::Continue(()),
             // Unexpected events
             Event::StreamStart => ControlFlow::Break(Err(YamlError::UnExpectedEvent {
                 expected: "StreamStart",
@@ -140,7 +140,7 @@ impl<'de, R: Source> ParserIter<'de, R> {
                 expected: "StreamEnd",
                 found: ev.as_simple_str(),
             })),
-            Event::DocumentStart(_) => ControlFlow::Break(Err(YamlError::UnExpectedEvent {
+            Event::DocumentStart(..) => ControlFlow::Break(Err(YamlError::UnExpectedEvent {
                 expected: "DocumentStart",
                 found: ev.as_simple_str(),
             })),
@@ -151,11 +151,11 @@ impl<'de, R: Source> ParserIter<'de, R> {
             }
 
             Event::Scalar(a) => ControlFlow::Break(Ok(YamEvent::Scalar(a))),
-            Event::SequenceStart(alias, tag) => {
+            Event::SequenceStart(alias, _, tag, _) => {
                 ControlFlow::Break(Ok(YamEvent::SeqStart(alias, tag)))
             }
             Event::SequenceEnd => ControlFlow::Break(Ok(YamEvent::SeqEnd)),
-            Event::MappingStart(alias, tag) => {
+            Event::MappingStart(alias, _, tag, _) => {
                 ControlFlow::Break(Ok(YamEvent::MapStart(alias, tag)))
             }
             Event::MappingEnd => ControlFlow::Break(Ok(YamEvent::MapEnd)),
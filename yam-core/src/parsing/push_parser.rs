@@ -0,0 +1,459 @@
+use crate::parsing::char_utils::is_break;
+use crate::parsing::scanner::SkipTabs;
+use crate::parsing::source::shared_skip_ws_to_eol;
+use crate::parsing::{Event, Parser, Source};
+use crate::prelude::{Span, YamlError};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::mem;
+
+///
+/// Result of [`PushParser::next_event`].
+///
+/// - `Event`: The next event of the stream, with its span in the whole input.
+/// - `NeedMoreInput`: The input fed so far doesn't determine the next event. Call
+///   [`PushParser::feed`] or [`PushParser::finish`] before asking again.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PushEvent {
+    Event(Event<'static>, Span),
+    NeedMoreInput,
+}
+
+///
+/// A parser for input that arrives in chunks, e.g. from a network connection.
+///
+/// Input is fed with [`PushParser::feed`] and terminated with [`PushParser::finish`]. Chunks may
+/// end anywhere, even in the middle of a token or of a UTF-8 character. Until the input is
+/// finished, [`PushParser::next_event`] only returns the events that can't change whatever comes
+/// next, and [`PushEvent::NeedMoreInput`] once those run out.
+///
+/// Events are returned as owned [`Event`]s with spans relative to the whole input. A single
+/// [`Parser`] reads all the input, so anchors and `%TAG` directives work as they do for a
+/// complete input. The parser keeps the input it hasn't consumed yet only. When it runs out of
+/// input in the middle of an event, it goes back to where that event started and waits for more.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::{Event, PushEvent, PushParser};
+///
+/// let mut parser = PushParser::new();
+/// let mut scalars = Vec::new();
+/// for chunk in ["key: val", "ue\n---\nsecond", "\n"] {
+///     parser.feed(chunk.as_bytes());
+///     while let Some(PushEvent::Event(ev, _)) = parser.next_event().transpose().unwrap() {
+///         if let Event::Scalar(scalar) = ev {
+///             scalars.push(scalar.value.into_owned());
+///         }
+///     }
+/// }
+/// assert_eq!(scalars, ["key", "value"]);
+///
+/// parser.finish();
+/// while let Some(PushEvent::Event(ev, _)) = parser.next_event().transpose().unwrap() {
+///     if let Event::Scalar(scalar) = ev {
+///         scalars.push(scalar.value.into_owned());
+///     }
+/// }
+/// assert_eq!(scalars, ["key", "value", "second"]);
+/// ```
+pub struct PushParser {
+    parser: Parser<'static, PushSource>,
+    /// Whether [`Event::StreamEnd`] or an error was returned.
+    done: bool,
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushParser {
+    #[must_use]
+    pub fn new() -> Self {
+        PushParser {
+            parser: Parser::new(PushSource::default()),
+            done: false,
+        }
+    }
+
+    /// Appends `chunk` to the input.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        let src = self.parser.source_mut();
+        debug_assert!(
+            !src.finished || src.error.is_some(),
+            "feed called after finish"
+        );
+        src.feed(chunk);
+    }
+
+    /// Marks the end of the input. The remaining events, up to [`Event::StreamEnd`], can then be
+    /// returned.
+    pub fn finish(&mut self) {
+        self.parser.source_mut().finish();
+    }
+
+    /// Returns the next event, or [`PushEvent::NeedMoreInput`] if the input fed so far doesn't
+    /// determine it.
+    ///
+    /// Returns `None` after [`Event::StreamEnd`] or an error was returned.
+    ///
+    /// # Errors
+    /// Returns `YamlError` when the input is invalid YAML or invalid UTF-8.
+    pub fn next_event(&mut self) -> Option<Result<PushEvent, YamlError>> {
+        if self.done {
+            return None;
+        }
+        // Once the input is finished, the parser can't run out of it early.
+        let checkpoint = if self.parser.source_mut().finished {
+            None
+        } else {
+            self.parser.source_mut().compact();
+            Some(self.checkpoint())
+        };
+        let res = self.parser.next_event();
+        if let Some(checkpoint) = checkpoint
+            && self.parser.source_mut().starved.get()
+        {
+            self.restore(checkpoint);
+            return Some(Ok(PushEvent::NeedMoreInput));
+        }
+        self.parser.drop_checkpoint();
+        match res {
+            Some(Ok((ev, span))) => {
+                self.done = ev == Event::StreamEnd;
+                Some(Ok(PushEvent::Event(ev, span)))
+            }
+            Some(Err(err)) => {
+                self.done = true;
+                Some(Err(err))
+            }
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+
+    /// Copies the state of the parser, without its input. See [`Parser::checkpoint`].
+    fn checkpoint(&mut self) -> Parser<'static, PushSource> {
+        let buf = mem::take(&mut self.parser.source_mut().buf);
+        let checkpoint = self.parser.checkpoint();
+        self.parser.source_mut().buf = buf;
+        checkpoint
+    }
+
+    /// Rewinds the parser to `checkpoint`. No input may have been fed since it was taken.
+    fn restore(&mut self, checkpoint: Parser<'static, PushSource>) {
+        let buf = mem::take(&mut self.parser.source_mut().buf);
+        self.parser.restore(checkpoint);
+        self.parser.source_mut().buf = buf;
+    }
+}
+
+/// The input fed to a [`PushParser`] that its scanner hasn't consumed yet.
+#[derive(Clone, Default)]
+struct PushSource {
+    /// Input fed and not yet dropped.
+    buf: Vec<u8>,
+    /// Position of the next unread byte in `buf`.
+    pos: usize,
+    /// Bytes of `buf` before this position are valid UTF-8. Only these can be peeked at.
+    valid: usize,
+    /// Whether the input was finished, or ended early by invalid UTF-8.
+    finished: bool,
+    error: Option<YamlError>,
+    /// Whether the scanner looked past the input fed so far since the last checkpoint.
+    starved: Cell<bool>,
+}
+
+impl PushSource {
+    fn feed(&mut self, chunk: &[u8]) {
+        if !self.finished {
+            self.buf.extend_from_slice(chunk);
+            self.validate();
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.validate();
+        }
+    }
+
+    /// Moves `valid` past the bytes that are valid UTF-8, leaving a character that may continue
+    /// in the next chunk.
+    fn validate(&mut self) {
+        match str::from_utf8(&self.buf[self.valid..]) {
+            Ok(_) => self.valid = self.buf.len(),
+            Err(err) if err.error_len().is_none() && !self.finished => {
+                self.valid += err.valid_up_to()
+            }
+            Err(err) => {
+                self.valid += err.valid_up_to();
+                self.finished = true;
+                self.error.get_or_insert(YamlError::Utf8(err));
+            }
+        }
+    }
+
+    /// Drops the consumed input once it makes up half of the buffer, and starts a new
+    /// checkpoint.
+    fn compact(&mut self) {
+        if self.pos > 0 && self.pos >= self.buf.len() / 2 {
+            self.buf.drain(..self.pos);
+            self.valid -= self.pos;
+            self.pos = 0;
+        }
+        self.starved.set(false);
+    }
+
+    fn available(&self) -> &[u8] {
+        &self.buf[self.pos..self.valid]
+    }
+
+    /// Records that the scanner wanted more input than was fed, unless the input is finished.
+    fn starve(&self) {
+        if !self.finished {
+            self.starved.set(true);
+        }
+    }
+}
+
+unsafe impl Source for PushSource {
+    unsafe fn peek_unsafe(&self, n: usize) -> u8 {
+        unsafe { *self.buf.get_unchecked(self.pos + n) }
+    }
+
+    fn peek_checked(&self, n: usize) -> Option<u8> {
+        let byte = self.available().get(n).copied();
+        if byte.is_none() {
+            self.starve();
+        }
+        byte
+    }
+
+    fn peek_char(&self) -> char {
+        let buf = self.available();
+        let len = match buf.first() {
+            Some(0xF0..) => 4,
+            Some(0xE0..) => 3,
+            Some(0xC0..) => 2,
+            Some(_) => 1,
+            None => {
+                self.starve();
+                return '\u{FFFD}';
+            }
+        };
+        // `valid` is on a character boundary, so the whole character is available.
+        str::from_utf8(&buf[..len])
+            .ok()
+            .and_then(|chr| chr.chars().next())
+            .unwrap_or('\u{FFFD}')
+    }
+
+    fn buf_is_empty(&self) -> bool {
+        let empty = self.pos == self.valid;
+        if empty {
+            self.starve();
+        }
+        empty
+    }
+
+    fn take_error(&mut self) -> Option<YamlError> {
+        self.error.take()
+    }
+
+    fn push_non_breakz_chr(&mut self, vec: &mut Vec<u8>) {
+        while let Some(chr) = self.peek_checked(0)
+            && !is_break(chr)
+        {
+            vec.push(chr);
+            self.skip(1);
+        }
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.pos = (self.pos + n).min(self.valid);
+    }
+
+    fn skip_ws_to_eol(
+        &mut self,
+        skip_tabs: bool,
+        has_yaml_ws: bool,
+    ) -> (u32, Result<SkipTabs, &'static str>) {
+        shared_skip_ws_to_eol(self, skip_tabs, 0, false, has_yaml_ws)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parsing::{Event, Parser, PushEvent, PushParser};
+    use crate::prelude::{Marker, Span, YamlError};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    /// Feeds `input` in chunks of `size` bytes and collects all events.
+    fn push_events(input: &str, size: usize) -> Result<Vec<(Event<'static>, Span)>, YamlError> {
+        let mut parser = PushParser::new();
+        let mut events = Vec::new();
+        let mut chunks = input.as_bytes().chunks(size);
+        loop {
+            match parser.next_event() {
+                Some(Ok(PushEvent::Event(ev, span))) => events.push((ev, span)),
+                Some(Ok(PushEvent::NeedMoreInput)) => match chunks.next() {
+                    Some(chunk) => parser.feed(chunk),
+                    None => parser.finish(),
+                },
+                Some(Err(err)) => return Err(err),
+                None => return Ok(events),
+            }
+        }
+    }
+
+    const INPUT: &str = "%YAML 1.2
+--- &a
+key: value
+  continued
+? 'multi
+  line'
+: |
+   block
+
+plain: [x, {y: *a}]
+? é
+...
+# comment
+--- !!str
+  ünïcödé
+---
+- &a 1
+- *a
+...
+%TAG !e! tag:example.com,2000:
+--- !e!point
+- !e!x 1
+";
+
+    #[test]
+    fn test_push_chunks() {
+        let expected: Vec<_> = Parser::new_from_str(INPUT)
+            .map(|ev| ev.map(|(ev, span)| (ev.into_owned(), span)))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for size in [1, 2, 3, 5, 7, 16, 64, INPUT.len()] {
+            let events = push_events(INPUT, size).unwrap();
+            assert_eq!(events, expected, "chunk size {size}");
+        }
+    }
+
+    #[test]
+    fn test_push_anchor_ids() {
+        let events = push_events("--- &a x\n--- &b y\n--- *b\n", 4);
+        assert!(matches!(events, Err(YamlError::UndefinedAlias { .. })));
+
+        let events = push_events("--- &a x\n--- &b y\n", 4).unwrap();
+        let ids: Vec<_> = events
+            .iter()
+            .filter_map(|(ev, _)| match ev {
+                Event::Scalar(scalar) => Some(scalar.anchor_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids, [1, 2]);
+
+        // Anchors redefined or cleared while waiting for input are restored on rewind.
+        let input = "a: &x 1\nb: &x [*x]\nc: *x\n---\nd: &x 3\ne: &y [*x]\nf: *y\n";
+        let expected: Vec<_> = Parser::new_from_str(input)
+            .map(|ev| ev.map(|(ev, span)| (ev.into_owned(), span)))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for size in 1..8 {
+            assert_eq!(
+                push_events(input, size).unwrap(),
+                expected,
+                "chunk size {size}"
+            );
+        }
+        // An anchor name cut by the end of a chunk isn't defined.
+        for size in 1..8 {
+            let events = push_events("a: &xy 1\nb: *x\n", size);
+            assert!(
+                matches!(events, Err(YamlError::UndefinedAlias { .. })),
+                "chunk size {size}: {events:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_push_need_more_input() {
+        /// Returns the scalars available until more input is needed.
+        fn scalars(parser: &mut PushParser) -> Vec<String> {
+            let mut scalars = Vec::new();
+            loop {
+                match parser.next_event() {
+                    Some(Ok(PushEvent::Event(Event::Scalar(scalar), _))) => {
+                        scalars.push(scalar.value.into_owned());
+                    }
+                    Some(Ok(PushEvent::Event(..))) => {}
+                    Some(Ok(PushEvent::NeedMoreInput)) => return scalars,
+                    res => panic!("{res:?}"),
+                }
+            }
+        }
+
+        let mut parser = PushParser::new();
+        assert_eq!(parser.next_event(), Some(Ok(PushEvent::NeedMoreInput)));
+        parser.feed(b"a: b");
+        // `b` may continue in the next chunk.
+        assert_eq!(scalars(&mut parser), ["a"]);
+        parser.feed(b"c\nd: e\n");
+        // `e` may continue on the next line.
+        assert_eq!(scalars(&mut parser), ["bc", "d"]);
+        parser.feed(b"f: |\n  g\n");
+        assert_eq!(scalars(&mut parser), ["e", "f"]);
+
+        parser.finish();
+        let mut events = Vec::new();
+        while let Some(Ok(PushEvent::Event(ev, _))) = parser.next_event() {
+            events.push(ev);
+        }
+        assert!(matches!(&events[0], Event::Scalar(scalar) if scalar.value == "g\n"));
+        assert_eq!(events.last(), Some(&Event::StreamEnd));
+        assert_eq!(parser.next_event(), None);
+    }
+
+    #[test]
+    fn test_push_drops_consumed_input() {
+        let mut parser = PushParser::new();
+        let mut docs = 0;
+        for _ in 0..1000 {
+            parser.feed(b"--- &a {key: [value, *a]}\n");
+            while let Some(Ok(PushEvent::Event(ev, _))) = parser.next_event() {
+                docs += usize::from(ev == Event::DocumentEnd);
+            }
+            assert!(parser.parser.source_mut().buf.len() < 64);
+        }
+        assert_eq!(docs, 999);
+    }
+
+    #[test]
+    fn test_push_errors() {
+        match push_events("a: 1\n---\nb: [1, 2\nc: d\n", 3) {
+            Err(YamlError::ScannerErr { mark, .. }) => assert!(mark.line >= 3, "{mark:?}"),
+            res => panic!("{res:?}"),
+        }
+
+        let mut parser = PushParser::new();
+        parser.feed(&"ü".as_bytes()[..1]);
+        assert_eq!(parser.next_event(), Some(Ok(PushEvent::NeedMoreInput)));
+        parser.finish();
+        assert!(matches!(parser.next_event(), Some(Err(YamlError::Utf8(_)))));
+        assert_eq!(parser.next_event(), None);
+
+        let spans = push_events("--- a\n--- b\n", 2).unwrap();
+        let b = spans.iter().rfind(|(ev, _)| ev.is_scalar()).unwrap();
+        assert_eq!(b.1.start, Marker::new(10, 5, 2));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::parsing::char_utils::{
-    as_hex, is_alpha, is_anchor_char, is_blank, is_blank_or_break, is_blank_or_breakz, is_break,
-    is_breakz, is_flow, is_tag_char, is_uri_char,
+    as_hex, count_chars, is_alpha, is_anchor_char, is_blank, is_blank_or_break, is_blank_or_breakz,
+    is_break, is_breakz, is_flow, is_tag_char, is_uri_char, is_utf8_continuation,
 };
 use crate::parsing::source::Source;
-use crate::parsing::{ChompIndicator, ScanResult, TokenType, YamlResult};
+use crate::parsing::{BlockIndicators, ChompIndicator, ScanResult, TokenType, YamlResult};
 use crate::prelude::ScalarType::Plain;
 use crate::prelude::{Marker, ScalarType, Span, YamlError};
 use alloc::borrow::Cow;
@@ -72,7 +72,7 @@ struct Indent {
     needs_block_end: bool,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum ImplicitMappingState {
     /// It is possible there is an implicit mapping.
     ///
@@ -86,6 +86,7 @@ enum ImplicitMappingState {
 }
 
 #[allow(clippy::struct_excessive_bools)] // TODO maybe too much bools?
+#[derive(Clone)]
 pub struct Scanner<'input, S> {
     src: S,
     pub(crate) mark: Marker,
@@ -154,6 +155,16 @@ impl<'input, S: Source> Scanner<'input, S> {
         }
     }
 
+    /// The source the scanner reads from.
+    pub(crate) fn source_mut(&mut self) -> &mut S {
+        &mut self.src
+    }
+
+    /// Takes the error that ended the input of the source early, if any.
+    pub(crate) fn take_source_error(&mut self) -> Option<YamlError> {
+        self.src.take_error()
+    }
+
     pub(crate) fn next_token(&mut self) -> YamlResult<Token<'input>> {
         if self.stream_end_produced {
             return Ok(Token {
@@ -329,6 +340,12 @@ impl<'input, S: Source> Scanner<'input, S> {
             token_type: TokenType::StreamStart,
         });
         self.simple_keys.push(SimpleKey::new(Marker::default()));
+
+        // A leading byte order mark isn't part of the content and doesn't take up a column.
+        if self.src.peekz(0) == 0xEF && self.src.peekz(1) == 0xBB && self.src.peekz(2) == 0xBF {
+            self.src.skip(3);
+            self.mark.pos += 3;
+        }
     }
 
     fn fetch_main_loop(&mut self) -> ScanResult {
@@ -593,11 +610,7 @@ impl<'input, S: Source> Scanner<'input, S> {
                         self.tokens.push_back(token);
                     }
                     #[cfg(not(feature = "comment"))]
-                    {
-                        let comment_length = self.src.skip_while_non_breakz();
-                        self.mark.pos += comment_length;
-                        self.mark.col += comment_length as u32;
-                    }
+                    self.skip_to_eol();
                 }
                 _ => break,
             }
@@ -810,9 +823,15 @@ impl<'input, S: Source> Scanner<'input, S> {
     ) -> Result<SkipTabs, YamlError> {
         let (n_bytes, result) = self.src.skip_ws_to_eol(skip_tabs.is_ignore_tabs(), prev_ws);
 
+        // Only blanks were skipped, so bytes and columns match.
         self.mark.col += n_bytes;
         self.mark.pos += n_bytes as usize;
-        result.map_err(|message| YamlError::new_str(self.mark, message))
+        let result = result.map_err(|message| YamlError::new_str(self.mark, message))?;
+        #[cfg(not(feature = "comment"))]
+        if self.src.peekz(0) == b'#' {
+            self.skip_to_eol();
+        }
+        Ok(result)
     }
 
     #[inline]
@@ -838,21 +857,37 @@ impl<'input, S: Source> Scanner<'input, S> {
         }
     }
 
+    /// Skips one byte. Columns count characters, so the continuation bytes of a multibyte
+    /// character don't advance the column.
     fn skip_blank(&mut self) {
+        self.mark.col += u32::from(!is_utf8_continuation(self.src.peekz(0)));
         self.src.skip(1);
 
         self.mark.pos += 1;
-        self.mark.col += 1;
     }
 
     fn skip_non_blank(&mut self) {
-        self.src.skip(1);
+        self.skip_blank();
+        self.leading_whitespace = false;
+    }
 
-        self.mark.pos += 1;
-        self.mark.col += 1;
+    /// Moves the mark past `fetched`, the non-blank bytes the source just fetched.
+    fn skip_fetched(&mut self, fetched: &[u8]) {
+        if fetched.is_empty() {
+            return;
+        }
+        self.mark.pos += fetched.len();
+        self.mark.col += count_chars(fetched);
         self.leading_whitespace = false;
     }
 
+    /// Skips the rest of the line, e.g. a comment.
+    fn skip_to_eol(&mut self) {
+        while !is_breakz(self.src.peekz(0)) {
+            self.skip_blank();
+        }
+    }
+
     fn is_within_block(&self) -> bool {
         !self.indents.is_empty()
     }
@@ -889,11 +924,7 @@ impl<'input, S: Source> Scanner<'input, S> {
                         self.simple_key_allowed = true;
                     }
                 }
-                b'#' => {
-                    let comment_length = self.src.skip_while_non_breakz();
-                    self.mark.pos += comment_length;
-                    self.mark.col += comment_length as u32;
-                }
+                b'#' => self.skip_to_eol(),
                 _ => break,
             }
         }
@@ -912,9 +943,7 @@ impl<'input, S: Source> Scanner<'input, S> {
             // XXX This should be a warning instead of an error
             _ => {
                 // skip current line
-                let line_len = self.src.skip_while_non_breakz();
-                self.mark.pos += line_len;
-                self.mark.col += line_len as u32;
+                self.skip_to_eol();
                 // XXX return an empty TagDirective token
                 Token {
                     span: Span::new(start_mark, self.mark),
@@ -1004,23 +1033,21 @@ impl<'input, S: Source> Scanner<'input, S> {
                 self.skip_non_blank();
                 string.reserve(self.src.buf_max_len());
 
-                // Add content non-blank characters to the scalar.
-                let mut end = false;
-                while !end {
-                    // Fill the buffer once and process all characters in the buffer until the next
-                    // fetch. Note that `next_can_be_plain_scalar` needs 2 lookahead characters,
-                    // hence the `for` loop looping `self.input.bufmaxlen() - 1` times.
-                    // ? self.src.lookahead(self.src.bufmaxlen());
-                    for _ in 0..self.src.buf_max_len() - 1 {
-                        if is_blank_or_breakz(self.src.peekz(0))
-                            || !self.src.next_can_be_plain_scalar(self.flow_level > 0)
-                        {
-                            end = true;
-                            break;
-                        }
-                        string.push(self.src.peek_checked(0).unwrap_or(b' '));
-                        self.skip_non_blank();
+                // Add content non-blank characters to the scalar. The source fetches the bytes
+                // that can't end the scalar in bulk, and only the others are checked one by one.
+                loop {
+                    let start = string.len();
+                    self.src
+                        .fetch_plain_scalar_chars(self.flow_level > 0, &mut string);
+                    self.skip_fetched(&string[start..]);
+
+                    if is_blank_or_breakz(self.src.peekz(0))
+                        || !self.src.next_can_be_plain_scalar(self.flow_level > 0)
+                    {
+                        break;
                     }
+                    string.push(self.src.peek_checked(0).unwrap_or(b' '));
+                    self.skip_non_blank();
                 }
                 end_mark = self.mark;
             }
@@ -1092,6 +1119,7 @@ impl<'input, S: Source> Scanner<'input, S> {
                 token_type: TokenType::Scalar {
                     scalar_type: Plain,
                     value: unsafe { Cow::Owned(String::from_utf8_unchecked(string)) },
+                    block: None,
                 },
             })
         }
@@ -1238,6 +1266,7 @@ impl<'input, S: Source> Scanner<'input, S> {
             token_type: TokenType::Scalar {
                 scalar_type: style,
                 value: unsafe { Cow::Owned(String::from_utf8_unchecked(string)) },
+                block: None,
             },
         })
     }
@@ -1331,6 +1360,7 @@ impl<'input, S: Source> Scanner<'input, S> {
             ));
         }
 
+        let indent_indicator = (increment > 0).then_some(increment as u8);
         if increment > 0 {
             indent = if self.indent >= 1 {
                 self.indent + increment as u32
@@ -1370,6 +1400,10 @@ impl<'input, S: Source> Scanner<'input, S> {
                 token_type: TokenType::Scalar {
                     scalar_type,
                     value: unsafe { Cow::Owned(String::from_utf8_unchecked(contents)) },
+                    block: Some(BlockIndicators {
+                        chomp: chomping,
+                        indent: indent_indicator,
+                    }),
                 },
             });
             return Ok(());
@@ -1382,7 +1416,6 @@ impl<'input, S: Source> Scanner<'input, S> {
             ));
         }
 
-        let mut line_buffer = Vec::with_capacity(100);
         let start_mark = self.mark;
         while self.mark.col == indent && self.src.peek_checked(0).is_some() {
             if indent == 1 {
@@ -1409,7 +1442,7 @@ impl<'input, S: Source> Scanner<'input, S> {
 
             leading_blank = is_blank(self.src.peekz(0));
 
-            self.scan_block_scalar_content_line(&mut string, &mut line_buffer);
+            self.scan_block_scalar_content_line(&mut string);
 
             // break on EOF
 
@@ -1443,45 +1476,22 @@ impl<'input, S: Source> Scanner<'input, S> {
             token_type: TokenType::Scalar {
                 scalar_type,
                 value: Cow::Owned(unsafe { String::from_utf8_unchecked(string) }),
+                block: Some(BlockIndicators {
+                    chomp: chomping,
+                    indent: indent_indicator,
+                }),
             },
         });
         Ok(())
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    fn scan_block_scalar_content_line(&mut self, string: &mut Vec<u8>, line_buffer: &mut Vec<u8>) {
-        // Start by evaluating characters in the buffer.
-        while !self.src.buf_is_empty() && !is_break(self.src.peekz(0)) {
-            string.push(self.src.peek_checked(0).unwrap_or(b' '));
-            // We may technically skip non-blank characters. However, the only distinction is
-            // to determine what is leading whitespace and what is not. Here, we read the
-            // contents of the line until either eof or a linebreak. We know we will not read
-            // `self.leading_whitespace` until the end of the line, where it will be reset.
-            // This allows us to call a slightly less expensive function.
-            self.skip_blank();
-        }
-
-        // All characters that were in the buffer were consumed. We need to check if more
-        // follow.
-        if self.src.buf_is_empty() {
-            // We will read all consecutive non-breakz characters. We push them into a
-            // temporary buffer. The main difference with going through `self.buffer` is that
-            // characters are appended here as their real size (1B for ascii, or up to 4 bytes for
-            // UTF-8). We can then use the internal `line_buffer` `Vec` to push data into `string`
-            // (using `String::push_str`).
-            self.src.push_non_breakz_chr(line_buffer);
-
-            // We need to manually update our position; we haven't called a `skip` function.
-            let n_chars = line_buffer.len();
-            self.mark.col += n_chars as u32;
-            self.mark.pos += n_chars;
-
-            // We can now append our bytes to our `string`.
-            string.reserve(line_buffer.len());
-            string.extend_from_slice(line_buffer);
-            // This clears the _contents_ without touching the _capacity_.
-            line_buffer.clear();
-        }
+    fn scan_block_scalar_content_line(&mut self, string: &mut Vec<u8>) {
+        // The source fetches the rest of the line in bulk. We need to manually update our
+        // position; we haven't called a `skip` function.
+        let start = string.len();
+        self.src.push_non_breakz_chr(string);
+        self.mark.col += count_chars(&string[start..]);
+        self.mark.pos += string.len() - start;
     }
 
     fn scan_anchor(&mut self, alias: bool) -> Result<Token<'input>, YamlError> {
@@ -1744,6 +1754,11 @@ impl<'input, S: Source> Scanner<'input, S> {
         start_mark: &Marker,
     ) -> Result<(), YamlError> {
         while !is_blank_or_breakz(self.src.peekz(0)) {
+            let start = string.len();
+            if self.src.fetch_quoted_scalar_chars(single, string) > 0 {
+                self.skip_fetched(&string[start..]);
+                continue;
+            }
             match self.src.peekz(0) {
                 // Check for an escaped single quote.
                 b'\'' if self.src.peekz(1) == b'\'' && single => {
@@ -2210,7 +2225,7 @@ impl<'input, S: Source> Scanner<'input, S> {
         let mut string = Vec::with_capacity(100);
         self.src.skip_and_accumulate_to_eol(&mut string);
         self.mark.pos += string.len();
-        self.mark.col += string.len() as u32;
+        self.mark.col += count_chars(&string);
 
         Token {
             span: Span::new(start, self.mark),
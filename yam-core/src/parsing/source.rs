@@ -2,8 +2,32 @@ use crate::parsing::char_utils::{
     is_alpha, is_blank, is_blank_or_breakz, is_break, is_breakz, is_flow,
 };
 use crate::parsing::scanner::SkipTabs;
+use crate::prelude::YamlError;
+use crate::util::NibbleSet;
 use alloc::vec::Vec;
 
+const BLANK: NibbleSet = NibbleSet::new(b" \t");
+const BREAK: NibbleSet = NibbleSet::new(b"\r\n");
+const BREAKZ: NibbleSet = NibbleSet::new(b"\0\r\n");
+/// Bytes that may end a plain scalar in block context: blanks, breaks and `:`.
+const PLAIN_END: NibbleSet = NibbleSet::new(b"\0\t\n\r :");
+/// Bytes that may end a plain scalar in flow context: [`PLAIN_END`] and flow indicators.
+const FLOW_PLAIN_END: NibbleSet = NibbleSet::new(b"\0\t\n\r :,[]{}");
+const SINGLE_QUOTED_END: NibbleSet = NibbleSet::new(b"\0\t\n\r '");
+const DOUBLE_QUOTED_END: NibbleSet = NibbleSet::new(b"\0\t\n\r \"\\");
+
+fn plain_end(in_flow: bool) -> &'static NibbleSet {
+    if in_flow { &FLOW_PLAIN_END } else { &PLAIN_END }
+}
+
+fn quoted_end(single: bool) -> &'static NibbleSet {
+    if single {
+        &SINGLE_QUOTED_END
+    } else {
+        &DOUBLE_QUOTED_END
+    }
+}
+
 ///
 /// A trait that defines a source of input data, with methods for peeking, skipping,
 /// and inspecting bytes and characters.
@@ -27,15 +51,18 @@ use alloc::vec::Vec;
 /// ## Buffer control
 /// - `buf_max_len() -> usize`: Returns the maximum recommended buffer length. The `Default` is `128`.
 /// - `buf_is_empty() -> bool`: Checks if the buffer is empty.
+/// - `take_error() -> Option<YamlError>`: Takes the error that ended the input early, if any.
 ///
 /// ## Parsing Helpers
 /// - `fetch_while_is_alpha(out: &mut Vec<u8>) -> usize`: Fetches and appends all consecutive alphanumeric characters into `out`, returning the count.
 /// - `push_non_breakz_chr(out: &mut Vec<u8>) -> usize`: Fetches and appends all consecutive alphanumeric characters into `out`, returning the count.
+/// - `fetch_plain_scalar_chars(in_flow: bool, out: &mut Vec<u8>) -> usize`: Fetches and appends the bytes up to the next one that may end a plain scalar, returning the count.
+/// - `fetch_quoted_scalar_chars(single: bool, out: &mut Vec<u8>) -> usize`: Fetches and appends the bytes up to the next blank, break, quote or escape of a quoted scalar, returning the count.
 ///
 /// ## Skipping
 /// - `skip(n: usize)`: Skips `n` bytes in the source.
 /// - `skip_while_blank() -> usize`: Skips all consecutive blank characters.
-/// - `skip_ws_to_eol(skip_tabs: bool, prev_ws: bool) -> (u32, Result<SkipTabs, &'static str>)`: Skips whitespace characters until the end of the line, optionally skipping tabs.
+/// - `skip_ws_to_eol(skip_tabs: bool, prev_ws: bool) -> (u32, Result<SkipTabs, &'static str>)`: Skips whitespace characters until the end of the line or a comment, optionally skipping tabs.
 /// - `skip_while_non_breakz() -> usize`: skips all non-break characters
 /// - `skip_and_accumulate_to_eol(buf: &mut Vec<u8>)`
 ///
@@ -88,6 +115,12 @@ pub unsafe trait Source {
 
     fn buf_is_empty(&self) -> bool;
 
+    /// Takes the error that ended the input early, e.g. a failed read. The parser returns it in
+    /// place of its next event.
+    fn take_error(&mut self) -> Option<YamlError> {
+        None
+    }
+
     fn fetch_while_is_alpha(&mut self, out: &mut Vec<u8>) -> usize {
         let mut n_chars = 0;
         while is_alpha(self.peekz(0)) {
@@ -100,6 +133,18 @@ pub unsafe trait Source {
 
     fn push_non_breakz_chr(&mut self, vec: &mut Vec<u8>);
 
+    /// Fetches the bytes of a plain scalar up to the next blank, break, `:` or, `in_flow`, flow
+    /// indicator. The scanner decides whether that byte ends the scalar.
+    fn fetch_plain_scalar_chars(&mut self, in_flow: bool, out: &mut Vec<u8>) -> usize {
+        fetch_until(self, plain_end(in_flow), out)
+    }
+
+    /// Fetches the bytes of a quoted scalar up to the next blank, break, quote or, unless
+    /// `single`, escape.
+    fn fetch_quoted_scalar_chars(&mut self, single: bool, out: &mut Vec<u8>) -> usize {
+        fetch_until(self, quoted_end(single), out)
+    }
+
     fn skip(&mut self, n: usize);
 
     fn skip_while_blank(&mut self) -> usize {
@@ -158,6 +203,18 @@ pub unsafe trait Source {
     }
 }
 
+fn fetch_until<T: Source + ?Sized>(x: &mut T, end: &NibbleSet, out: &mut Vec<u8>) -> usize {
+    let mut count = 0;
+    while let Some(chr) = x.peek_checked(0)
+        && !end.contains(chr)
+    {
+        out.push(chr);
+        x.skip(1);
+        count += 1;
+    }
+    count
+}
+
 #[inline]
 pub(crate) fn shared_skip_ws_to_eol<T: Source>(
     x: &mut T,
@@ -183,17 +240,7 @@ pub(crate) fn shared_skip_ws_to_eol<T: Source>(
                     Err("comments must be separated from other tokens by whitespace"),
                 );
             }
-            #[cfg(feature = "comment")]
-            b'#' => break,
-            #[cfg(not(feature = "comment"))]
-            b'#' => {
-                // Skip `#`
-                x.skip(1);
-                while !is_breakz(x.peekz(0)) {
-                    x.skip(1);
-                    bytes_consumed += 1;
-                }
-            }
+            // The scanner reads or skips the comment itself.
             _ => break,
         }
         bytes_consumed += 1;
@@ -260,6 +307,17 @@ impl StrSource<'_> {
             pos: 0,
         }
     }
+
+    fn rest(&self) -> &[u8] {
+        &self.input[self.pos..]
+    }
+
+    fn fetch_until(&mut self, end: &NibbleSet, out: &mut Vec<u8>) -> usize {
+        let len = end.position(self.rest());
+        out.extend_from_slice(&self.rest()[..len]);
+        self.skip(len);
+        len
+    }
 }
 
 unsafe impl Source for StrSource<'_> {
@@ -284,19 +342,35 @@ unsafe impl Source for StrSource<'_> {
     }
 
     fn push_non_breakz_chr(&mut self, vec: &mut Vec<u8>) {
-        let len = self.input[self.pos..]
-            .iter()
-            .position(|&c| is_break(c))
-            .unwrap_or(0);
-        let slice = &self.input[self.pos..self.pos + len];
+        let len = BREAK.position(self.rest());
+        vec.extend_from_slice(&self.rest()[..len]);
         self.skip(len);
-        vec.extend_from_slice(slice);
+    }
+
+    fn fetch_plain_scalar_chars(&mut self, in_flow: bool, out: &mut Vec<u8>) -> usize {
+        self.fetch_until(plain_end(in_flow), out)
+    }
+
+    fn fetch_quoted_scalar_chars(&mut self, single: bool, out: &mut Vec<u8>) -> usize {
+        self.fetch_until(quoted_end(single), out)
     }
 
     fn skip(&mut self, n: usize) {
         self.pos += n;
     }
 
+    fn skip_while_blank(&mut self) -> usize {
+        let len = BLANK.position_not(self.rest());
+        self.skip(len);
+        len
+    }
+
+    fn skip_while_non_breakz(&mut self) -> usize {
+        let len = BREAKZ.position(self.rest());
+        self.skip(len);
+        len
+    }
+
     fn skip_ws_to_eol(
         &mut self,
         skip_tabs: bool,
@@ -308,10 +382,11 @@ unsafe impl Source for StrSource<'_> {
 
 #[cfg(test)]
 mod test {
-    use crate::parsing::Source;
     use crate::parsing::buffered_source::BufferedBytesSource;
     use crate::parsing::scanner::SkipTabs;
-    use crate::prelude::StrSource;
+    use crate::parsing::{Event, Parser, Source};
+    use crate::prelude::{Marker, StrSource};
+    use alloc::vec::Vec;
 
     const TEST_STR: &str = "                                      \
                                     \n                     \
@@ -341,4 +416,14 @@ mod test {
             })
         );
     }
+
+    #[test]
+    fn test_leading_bom() {
+        let events: Vec<_> = Parser::new_from_str("\u{FEFF}a: b").collect();
+        let Ok((Event::Scalar(key), span)) = &events[3] else {
+            panic!("{events:?}");
+        };
+        assert_eq!(key.value, "a");
+        assert_eq!(span.start, Marker::new(3, 1, 1));
+    }
 }
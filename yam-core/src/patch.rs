@@ -0,0 +1,575 @@
+use crate::diff::{identities, same};
+use crate::prelude::path::{key_matches, map_value, pointer_segments, split_pointer, untagged_mut};
+use crate::prelude::{MappingLike, SequenceLike, YamlData, YamlDocAccess};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+
+///
+/// A change to apply to a [`YamlDocAccess`] tree.
+///
+/// - [`Patch::Json`] is an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON Patch, a list
+///   of operations addressed by JSON pointers. Use [`Patch::json_from`] to read one from a loaded
+///   document.
+/// - [`Patch::Merge`] is an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386) merge patch:
+///   mappings are merged recursively, `null` removes a key, and anything else replaces the
+///   target value.
+/// - [`Patch::Strategic`] is a Kubernetes-style strategic merge: like a merge patch, except
+///   that sequences of mappings are merged element by element, matching elements by the value of
+///   `key`. An element or mapping value with `$patch: delete` removes the match, and a mapping
+///   with `$patch: replace` replaces the target instead of merging into it.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::patch::Patch;
+/// use yam_core::prelude::{Yaml, YamlDocAccess};
+///
+/// let mut doc = Yaml::load_single("spec: {replicas: 1, image: nginx:1.25}").unwrap();
+///
+/// let ops = Yaml::load_single(r#"[
+///   {op: replace, path: /spec/replicas, value: 3},
+///   {op: test, path: /spec/image, value: "nginx:1.24"},
+/// ]"#).unwrap();
+/// let err = Patch::json_from(&ops).unwrap().apply(&mut doc).unwrap_err();
+/// assert_eq!(err.op, 1);
+/// assert_eq!(doc["spec"]["replicas"].as_i64(), Some(1));
+///
+/// let merge = Yaml::load_single("spec: {image: nginx:1.27, replicas: null}").unwrap();
+/// Patch::Merge(merge).apply(&mut doc).unwrap();
+/// assert_eq!(doc, Yaml::load_single("spec: {image: nginx:1.27}").unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Patch<N> {
+    /// An RFC 6902 JSON Patch, applied all or nothing.
+    Json(Vec<PatchOp<N>>),
+    /// An RFC 7386 merge patch.
+    Merge(N),
+    /// A strategic merge patch, matching sequence elements by `key`.
+    Strategic {
+        /// The patch document.
+        patch: N,
+        /// Key that identifies elements of a sequence, usually `name`.
+        key: String,
+    },
+}
+
+/// One operation of an RFC 6902 JSON Patch. Paths are JSON pointers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp<N> {
+    /// Inserts `value` into a sequence, or sets it in a mapping. `-` appends to a sequence.
+    Add { path: String, value: N },
+    /// Removes the value at `path`, which must exist.
+    Remove { path: String },
+    /// Replaces the value at `path`, which must exist.
+    Replace { path: String, value: N },
+    /// Removes the value at `from` and adds it at `path`.
+    Move { from: String, path: String },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Fails the patch unless the value at `path` equals `value`.
+    Test { path: String, value: N },
+}
+
+///
+/// Error returned when a [`Patch`] can't be read or applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatchError {
+    /// Index of the operation that failed.
+    pub op: usize,
+    /// What went wrong.
+    pub info: &'static str,
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Patch operation {} failed: {}", self.op, self.info)
+    }
+}
+
+impl<'input, N> Patch<N>
+where
+    N: YamlDocAccess<'input> + From<YamlData<'input, N>>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    /// Creates a strategic merge patch that matches sequence elements by `name`.
+    pub fn strategic(patch: N) -> Self {
+        Patch::Strategic {
+            patch,
+            key: String::from("name"),
+        }
+    }
+
+    ///
+    /// Reads a JSON Patch from a sequence of operation mappings such as
+    /// `[{op: add, path: /a/b, value: 1}]`.
+    ///
+    /// # Errors
+    /// Returns a [`PatchError`] for the first operation that isn't a mapping, has an unknown
+    /// `op`, or lacks the `path`, `from` or `value` it needs.
+    pub fn json_from(ops: &N) -> Result<Self, PatchError> {
+        let Ok(ops) = ops.as_sequence() else {
+            return Err(PatchError {
+                op: 0,
+                info: "expected a sequence of operations",
+            });
+        };
+        ops.elems()
+            .enumerate()
+            .map(|(op, node)| PatchOp::from_node(node).map_err(|info| PatchError { op, info }))
+            .collect::<Result<_, _>>()
+            .map(Patch::Json)
+    }
+
+    ///
+    /// Applies the patch to `target`.
+    ///
+    /// # Errors
+    /// Returns a [`PatchError`] if a JSON Patch operation fails, in which case `target` is left
+    /// unchanged. Merge patches always apply.
+    pub fn apply(&self, target: &mut N) -> Result<(), PatchError> {
+        match self {
+            Patch::Json(ops) => {
+                let mut patched = target.clone();
+                for (op, patch_op) in ops.iter().enumerate() {
+                    patch_op
+                        .apply(&mut patched)
+                        .map_err(|info| PatchError { op, info })?;
+                }
+                *target = patched;
+            }
+            Patch::Merge(patch) => merge(target, patch, None),
+            Patch::Strategic { patch, key } => merge(target, patch, Some(key)),
+        }
+        Ok(())
+    }
+}
+
+impl<'input, N> PatchOp<N>
+where
+    N: YamlDocAccess<'input> + From<YamlData<'input, N>>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    fn from_node(node: &N) -> Result<Self, &'static str> {
+        if !node.is_mapping() {
+            return Err("expected an operation mapping");
+        }
+        let field = |name: &str| map_value(node, |k| k.as_str() == Some(name));
+        let string = |name: &str, info| {
+            field(name)
+                .and_then(N::as_str)
+                .map(String::from)
+                .ok_or(info)
+        };
+        let value = || field("value").cloned().ok_or("missing `value`");
+        let op = field("op").and_then(N::as_str).ok_or("missing `op`")?;
+        let path = string("path", "missing `path`")?;
+        let from = || string("from", "missing `from`");
+        Ok(match op {
+            "add" => PatchOp::Add {
+                path,
+                value: value()?,
+            },
+            "remove" => PatchOp::Remove { path },
+            "replace" => PatchOp::Replace {
+                path,
+                value: value()?,
+            },
+            "move" => PatchOp::Move {
+                from: from()?,
+                path,
+            },
+            "copy" => PatchOp::Copy {
+                from: from()?,
+                path,
+            },
+            "test" => PatchOp::Test {
+                path,
+                value: value()?,
+            },
+            _ => return Err("unknown `op`"),
+        })
+    }
+
+    /// Applies the operation to `target`, which may be left half-changed on error.
+    fn apply(&self, target: &mut N) -> Result<(), &'static str> {
+        match self {
+            PatchOp::Add { path, value } => add(target, path, value.clone()),
+            PatchOp::Remove { path } => remove(target, path).map(drop),
+            PatchOp::Replace { path, value } => {
+                *lookup_mut(target, path)? = value.clone();
+                Ok(())
+            }
+            PatchOp::Move { from, path } => {
+                if from == path {
+                    lookup(target, from).map(drop)
+                } else if path
+                    .strip_prefix(from.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+                {
+                    Err("can't move a value into itself")
+                } else {
+                    let value = remove(target, from)?;
+                    add(target, path, value)
+                }
+            }
+            PatchOp::Copy { from, path } => {
+                let value = lookup(target, from)?.clone();
+                add(target, path, value)
+            }
+            PatchOp::Test { path, value } => {
+                if same(lookup(target, path)?, value) {
+                    Ok(())
+                } else {
+                    Err("test failed")
+                }
+            }
+        }
+    }
+}
+
+fn lookup<'n, 'input, N>(target: &'n N, path: &str) -> Result<&'n N, &'static str>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    if pointer_segments(path).is_none() {
+        return Err("invalid pointer");
+    }
+    target.pointer(path).ok_or("path not found")
+}
+
+fn lookup_mut<'n, 'input, N>(target: &'n mut N, path: &str) -> Result<&'n mut N, &'static str>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    if pointer_segments(path).is_none() {
+        return Err("invalid pointer");
+    }
+    target.pointer_mut(path).ok_or("path not found")
+}
+
+fn add<'input, N>(target: &mut N, path: &str, value: N) -> Result<(), &'static str>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    if path.is_empty() {
+        *target = value;
+        return Ok(());
+    }
+    let (parent, last) = split_pointer(path).ok_or("invalid pointer")?;
+    let parent = untagged_mut(lookup_mut(target, parent)?);
+    if let Ok(seq) = parent.as_sequence_mut() {
+        let index = match last.name() {
+            "-" => seq.elem_count(),
+            _ => last.position().ok_or("invalid sequence index")?,
+        };
+        if index > seq.elem_count() {
+            return Err("sequence index out of bounds");
+        }
+        seq.insert_elem(index, value);
+    } else if let Ok(map) = parent.as_mapping_mut() {
+        match map.find_value_mut(|k| last.matches(k)) {
+            Some(existing) => *existing = value,
+            None => map.push_mapping(N::key_from_str(last.name()), value),
+        }
+    } else {
+        return Err("parent is not a sequence or mapping");
+    }
+    Ok(())
+}
+
+fn remove<'input, N>(target: &mut N, path: &str) -> Result<N, &'static str>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    if path.is_empty() {
+        return Err("can't remove the root");
+    }
+    let (parent, last) = split_pointer(path).ok_or("invalid pointer")?;
+    let parent = untagged_mut(lookup_mut(target, parent)?);
+    let removed = if let Ok(seq) = parent.as_sequence_mut() {
+        last.position().and_then(|index| seq.remove_elem(index))
+    } else if let Ok(map) = parent.as_mapping_mut() {
+        let index = map.keys().position(|k| last.matches(k));
+        index.map(|index| map.remove_at(index).1)
+    } else {
+        None
+    };
+    removed.ok_or("path not found")
+}
+
+/// Merges `patch` into `target`; `key` selects strategic merge and names the identity key.
+fn merge<'input, N>(target: &mut N, patch: &N, key: Option<&str>)
+where
+    N: YamlDocAccess<'input> + From<YamlData<'input, N>>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    if let Some(key) = key
+        && let Ok(elems) = patch.as_sequence()
+        && identities(elems.vec(), key).is_some()
+    {
+        merge_sequence(target, elems.vec(), key);
+        return;
+    }
+    let Ok(entries) = patch.as_mapping().map(MappingLike::entries) else {
+        *target = patch.clone();
+        return;
+    };
+    if key.is_some() && directive(patch) == Some("replace") {
+        *target = N::null();
+    }
+    if !target.is_mapping() {
+        *target = YamlData::Mapping(Vec::new()).into();
+    }
+    let map = target.mapping_mut();
+    for entry in entries {
+        if key.is_some() && entry.key.as_str() == Some(DIRECTIVE) {
+            continue;
+        }
+        let delete =
+            entry.value.is_null() || key.is_some() && directive(&entry.value) == Some("delete");
+        if delete {
+            let index = map.keys().position(|k| same(k, &entry.key));
+            if let Some(index) = index {
+                map.remove_at(index);
+            }
+        } else if let Some(existing) = map.find_value_mut(|k| same(k, &entry.key)) {
+            merge(existing, &entry.value, key);
+        } else {
+            let mut value = N::null();
+            merge(&mut value, &entry.value, key);
+            map.push_mapping(entry.key.clone(), value);
+        }
+    }
+}
+
+/// Merges a sequence of mappings into `target`, matching elements by the value of `key`.
+fn merge_sequence<'input, N>(target: &mut N, elems: &[N], key: &str)
+where
+    N: YamlDocAccess<'input> + From<YamlData<'input, N>>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+{
+    if !target.is_sequence() {
+        *target = YamlData::Sequence(Vec::new()).into();
+    }
+    let seq = target.sequence_mut();
+    for elem in elems {
+        let id = map_value(elem, |k| key_matches(k, key));
+        let index = seq.elems().position(|existing| {
+            map_value(existing, |k| key_matches(k, key))
+                .zip(id)
+                .is_some_and(|(a, b)| same(a, b))
+        });
+        match (index, directive(elem)) {
+            (Some(index), Some("delete")) => {
+                seq.remove_elem(index);
+            }
+            (None, Some("delete")) => {}
+            (Some(index), _) => merge(seq.vec_mut().get_mut(index).unwrap(), elem, Some(key)),
+            (None, _) => {
+                let mut value = N::null();
+                merge(&mut value, elem, Some(key));
+                seq.push_elem(value);
+            }
+        }
+    }
+}
+
+const DIRECTIVE: &str = "$patch";
+
+/// The `$patch` directive of a strategic merge mapping, if it has one.
+fn directive<'n, 'input, N>(node: &'n N) -> Option<&'n str>
+where
+    N: YamlDocAccess<'input>,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    map_value(node, |k| k.as_str() == Some(DIRECTIVE)).and_then(N::as_str)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::patch::{Patch, PatchError, PatchOp};
+    use crate::prelude::{Yaml, YamlDocAccess, YamlOwned};
+    use alloc::string::String;
+    use alloc::vec;
+
+    fn json(ops: &str) -> Patch<YamlOwned> {
+        Patch::json_from(&YamlOwned::load_single(ops).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let mut doc = YamlOwned::load_single("{foo: [bar, baz], a/b: {c: 1}, 80: http}").unwrap();
+        json(
+            "[
+              {op: add, path: /foo/1, value: qux},
+              {op: add, path: /foo/-, value: end},
+              {op: remove, path: /foo/0},
+              {op: replace, path: /a~1b/c, value: 2},
+              {op: copy, from: /a~1b, path: /copy},
+              {op: move, from: /foo/2, path: /last},
+              {op: add, path: /80, value: www},
+              {op: test, path: /copy/c, value: 2},
+            ]",
+        )
+        .apply(&mut doc)
+        .unwrap();
+        let expected = "{foo: [qux, baz], a/b: {c: 2}, 80: www, copy: {c: 2}, last: end}";
+        assert_eq!(doc, YamlOwned::load_single(expected).unwrap());
+
+        let root = Patch::Json(vec![PatchOp::Add {
+            path: String::new(),
+            value: YamlOwned::from("root"),
+        }]);
+        let mut replaced = doc.clone();
+        root.apply(&mut replaced).unwrap();
+        assert_eq!(replaced.as_str(), Some("root"));
+    }
+
+    #[test]
+    fn test_json_patch_tagged() {
+        let mut doc = YamlOwned::load_single("spec: !custom {items: !list [a]}").unwrap();
+        json("[{op: add, path: /spec/items/-, value: b}, {op: add, path: /spec/n, value: 1}]")
+            .apply(&mut doc)
+            .unwrap();
+        let spec = &doc["spec"];
+        assert_eq!(spec.get_tag().unwrap().suffix, "custom");
+        assert_eq!(spec.pointer("/items/1").unwrap().as_str(), Some("b"));
+        assert_eq!(spec.get("n").unwrap().as_i64(), Some(1));
+    }
+
+    #[test]
+    fn test_json_patch_errors() {
+        let original = YamlOwned::load_single("{foo: [bar], a: {b: 1}}").unwrap();
+        let cases = [
+            (
+                "[{op: remove, path: /foo/0}, {op: remove, path: /foo/0}]",
+                1,
+                "path not found",
+            ),
+            (
+                "[{op: add, path: /foo/2, value: x}]",
+                0,
+                "sequence index out of bounds",
+            ),
+            (
+                "[{op: add, path: /foo/01, value: x}]",
+                0,
+                "invalid sequence index",
+            ),
+            ("[{op: add, path: /x/y, value: 1}]", 0, "path not found"),
+            ("[{op: replace, path: foo, value: 1}]", 0, "invalid pointer"),
+            (
+                "[{op: move, from: /a, path: /a/b/c}]",
+                0,
+                "can't move a value into itself",
+            ),
+            ("[{op: remove, path: ''}]", 0, "can't remove the root"),
+            (
+                "[{op: add, path: /a/b, value: 2}, {op: test, path: /a/b, value: 1}]",
+                1,
+                "test failed",
+            ),
+        ];
+        for (ops, op, info) in cases {
+            let mut doc = original.clone();
+            assert_eq!(
+                json(ops).apply(&mut doc),
+                Err(PatchError { op, info }),
+                "{ops}"
+            );
+            assert_eq!(doc, original);
+        }
+
+        let parse = |ops| Patch::json_from(&YamlOwned::load_single(ops).unwrap()).unwrap_err();
+        assert_eq!(parse("{op: add}").info, "expected a sequence of operations");
+        assert_eq!(parse("[{op: add, path: /a, value: 1}, {path: /a}]").op, 1);
+        assert_eq!(parse("[{op: add, path: /a}]").info, "missing `value`");
+        assert_eq!(parse("[{op: copy, path: /a}]").info, "missing `from`");
+        assert_eq!(parse("[{op: frob, path: /a}]").info, "unknown `op`");
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        // The example from RFC 7386, section 3.
+        let mut doc = Yaml::load_single(
+            "{title: Goodbye!, author: {givenName: John, familyName: Doe}, tags: [example, sample], content: This will be unchanged}",
+        )
+        .unwrap();
+        let patch = Yaml::load_single(
+            "{title: Hello!, phoneNumber: '+01-123-456-7890', author: {familyName: null}, tags: [example]}",
+        )
+        .unwrap();
+        Patch::Merge(patch).apply(&mut doc).unwrap();
+        let expected = Yaml::load_single(
+            "{title: Hello!, author: {givenName: John}, tags: [example], content: This will be unchanged, phoneNumber: '+01-123-456-7890'}",
+        )
+        .unwrap();
+        assert_eq!(doc, expected);
+
+        let mut scalar = Yaml::load_single("plain").unwrap();
+        let patch = Yaml::load_single("{a: {b: null, c: 1}}").unwrap();
+        Patch::Merge(patch).apply(&mut scalar).unwrap();
+        assert_eq!(scalar, Yaml::load_single("{a: {c: 1}}").unwrap());
+    }
+
+    #[test]
+    fn test_strategic_merge() {
+        let mut doc = YamlOwned::load_single(
+            "
+containers:
+  - name: web
+    image: nginx:1.25
+    env: [{name: A, value: '1'}, {name: B, value: '2'}]
+  - name: sidecar
+    image: envoy:1.30
+args: [--a, --b]
+labels: {app: web, tier: front}
+",
+        )
+        .unwrap();
+        let patch = YamlOwned::load_single(
+            "
+containers:
+  - name: web
+    image: nginx:1.27
+    env: [{name: B, $patch: delete}, {name: C, value: '3'}]
+  - name: log
+    image: fluentd
+  - name: sidecar
+    $patch: delete
+args: [--c]
+labels: {$patch: replace, app: api}
+",
+        )
+        .unwrap();
+        Patch::strategic(patch).apply(&mut doc).unwrap();
+        let expected = YamlOwned::load_single(
+            "
+containers:
+  - name: web
+    image: nginx:1.27
+    env: [{name: A, value: '1'}, {name: C, value: '3'}]
+  - name: log
+    image: fluentd
+args: [--c]
+labels: {app: api}
+",
+        )
+        .unwrap();
+        assert_eq!(doc, expected);
+    }
+}
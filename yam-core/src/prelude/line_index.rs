@@ -0,0 +1,189 @@
+use crate::prelude::{Marker, Span};
+use alloc::vec::Vec;
+
+///
+/// The position of a [`Marker`] in a line, counted in the units different tools expect.
+///
+/// # Fields
+/// - `line`: The one-indexed line number.
+/// - `utf8`: The one-indexed column, in bytes.
+/// - `utf16`: The one-indexed column, in UTF-16 code units, as used by the Language Server
+///   Protocol and JavaScript.
+/// - `chars`: The one-indexed column, in characters (Unicode scalar values), like
+///   [`Marker::col`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct LineColumns {
+    pub line: u32,
+    pub utf8: u32,
+    pub utf16: u32,
+    pub chars: u32,
+}
+
+///
+/// An index of the line starts of an input, converting byte positions to line and column numbers.
+///
+/// The parser only tracks columns in characters. Build a `LineIndex` of the input when columns
+/// are needed in other units, e.g. to report diagnostics to an editor.
+///
+/// Like the scanner, a byte order mark at the start of the input isn't counted as part of the
+/// first line, so columns on that line start after it.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::{Event, Parser};
+/// use yam_core::prelude::{LineColumns, LineIndex};
+///
+/// let input = "a: 1\nb: [😀, x]\n";
+/// let index = LineIndex::new(input);
+/// let (_, span) = Parser::new_from_str(input)
+///     .map(Result::unwrap)
+///     .find(|(ev, _)| matches!(ev, Event::Scalar(s) if s.value == "x"))
+///     .unwrap();
+///
+/// let start = index.columns(span.start).unwrap();
+/// assert_eq!(start, LineColumns { line: 2, utf8: 11, utf16: 9, chars: 8 });
+/// assert_eq!(start.chars, span.start.col);
+/// assert_eq!(index.marker(span.start.pos), Some(span.start));
+/// ```
+pub struct LineIndex<'input> {
+    input: &'input str,
+    /// Byte position of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'input> LineIndex<'input> {
+    /// Indexes the lines of `input`. Lines end with `\n` or `\r\n`, like in the scanner.
+    #[must_use]
+    pub fn new(input: &'input str) -> Self {
+        let line_starts = core::iter::once(0)
+            .chain(input.match_indices('\n').map(|(pos, _)| pos + 1))
+            .collect();
+        LineIndex { input, line_starts }
+    }
+
+    /// Returns the text of the one-indexed `line`, without its line break.
+    #[must_use]
+    pub fn line(&self, line: u32) -> Option<&'input str> {
+        let index = usize::try_from(line).ok()?.checked_sub(1)?;
+        let start = *self.line_starts.get(index)?;
+        let end = self
+            .line_starts
+            .get(index + 1)
+            .map_or(self.input.len(), |next| next - 1);
+        let text = skip_bom(index, &self.input[start..end]);
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Returns the line and columns of the byte position `pos`.
+    ///
+    /// # Return
+    /// Returns `None` if `pos` is past the end of the input or inside a character.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn position(&self, pos: usize) -> Option<LineColumns> {
+        if !self.input.is_char_boundary(pos) {
+            return None;
+        }
+        let index = self.line_starts.partition_point(|&start| start <= pos) - 1;
+        let before = skip_bom(index, &self.input[self.line_starts[index]..pos]);
+        Some(LineColumns {
+            line: index as u32 + 1,
+            utf8: before.len() as u32 + 1,
+            utf16: before.encode_utf16().count() as u32 + 1,
+            chars: before.chars().count() as u32 + 1,
+        })
+    }
+
+    /// Returns the line and columns of `mark`, using its byte position.
+    #[must_use]
+    pub fn columns(&self, mark: Marker) -> Option<LineColumns> {
+        self.position(mark.pos)
+    }
+
+    /// Returns the line and columns of the start and the end of `span`.
+    #[must_use]
+    pub fn span_columns(&self, span: Span) -> Option<(LineColumns, LineColumns)> {
+        Some((self.columns(span.start)?, self.columns(span.end)?))
+    }
+
+    /// Returns the [`Marker`] of the byte position `pos`, as the scanner would report it.
+    #[must_use]
+    pub fn marker(&self, pos: usize) -> Option<Marker> {
+        let columns = self.position(pos)?;
+        Some(Marker::new(pos, columns.chars, columns.line))
+    }
+}
+
+/// Strips the byte order mark from `text` if it starts the line at `index`.
+fn skip_bom(index: usize, text: &str) -> &str {
+    if index == 0 {
+        text.strip_prefix('\u{FEFF}').unwrap_or(text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parsing::Parser;
+    use crate::prelude::{LineColumns, LineIndex};
+
+    #[test]
+    fn test_line_index() {
+        let input = "# ünïcödé comment\r\nkey: \"𝄞 clef\" # ✓\nnext: 'é'\n";
+        let index = LineIndex::new(input);
+        assert_eq!(index.line(1), Some("# ünïcödé comment"));
+        assert_eq!(index.line(4), Some(""));
+        assert_eq!(index.line(5), None);
+        assert_eq!(index.position(input.len() + 1), None);
+        // Inside `ü`.
+        assert_eq!(index.position(3), None);
+
+        let clef = input.find('𝄞').unwrap();
+        assert_eq!(
+            index.position(clef + 4),
+            Some(LineColumns {
+                line: 2,
+                utf8: 11,
+                utf16: 9,
+                chars: 8,
+            })
+        );
+
+        // The scanner counts columns in characters.
+        for res in Parser::new_from_str(input) {
+            let Ok((_, span)) = res else { break };
+            for mark in [span.start, span.end] {
+                if mark.pos < input.len() {
+                    assert_eq!(index.marker(mark.pos), Some(mark), "{span:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_line_index_bom() {
+        let input = "\u{FEFF}a: é\nb: c";
+        let index = LineIndex::new(input);
+        assert_eq!(index.line(1), Some("a: é"));
+        assert_eq!(index.position(0).map(|c| c.utf8), Some(1));
+        assert_eq!(
+            index.position(input.find('é').unwrap()),
+            Some(LineColumns {
+                line: 1,
+                utf8: 4,
+                utf16: 4,
+                chars: 4,
+            })
+        );
+
+        for res in Parser::new_from_str(input) {
+            let (_, span) = res.unwrap();
+            for mark in [span.start, span.end] {
+                if mark.pos < input.len() {
+                    assert_eq!(index.marker(mark.pos), Some(mark), "{span:?}");
+                }
+            }
+        }
+    }
+}
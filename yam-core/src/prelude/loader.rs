@@ -1,14 +1,28 @@
 use crate::node::yaml_data::YamlData;
 use crate::parsing;
-use crate::parsing::Tag;
+use crate::parsing::{Directives, Tag};
 use crate::parsing::{Event, ScalarValue, SpannedEventReceiver};
 use crate::prelude::{
-    IsEmpty, Source, Span, StrSource, YamlDocAccess, YamlEntry, YamlError, YamlScalar,
+    IsEmpty, Marker, NodeType, Presentation, ScalarType, Schema, Source, Span, StrSource,
+    TagResolver, Timestamp, YamlDocAccess, YamlEntry, YamlError, YamlScalar,
 };
 use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
+use core::mem;
+
+/// A node under construction, with its anchor id, anchor name and tag.
+type StackEntry<'input, Node> = (
+    Node,
+    usize,
+    Option<Cow<'input, str>>,
+    Option<Cow<'input, Tag>>,
+);
 
 /// A struct responsible for loading and parsing YAML documents, while maintaining
 /// internal state for tracking document structure and node relationships.
@@ -36,9 +50,19 @@ where
     Node: YamlDocAccess<'input>,
 {
     docs: Vec<Node>,
-    doc_stack: Vec<(Node, usize, Option<Cow<'input, Tag>>)>,
+    directives: Vec<Directives>,
+    doc_stack: Vec<StackEntry<'input, Node>>,
     key_stack: Vec<Node>,
+    /// For each open mapping: whether a `<<` merge key awaits its value, and the merged values.
+    merge_stack: Vec<(bool, Vec<Node>)>,
     anchor_map: BTreeMap<usize, Node>,
+    allow_undefined_aliases: bool,
+    schema: Schema,
+    detect_schema: bool,
+    doc_schema: Schema,
+    resolver: Option<Box<dyn NodeResolver<'input, Node>>>,
+    standard_tags: bool,
+    error: Option<YamlError>,
 }
 
 impl<'i, Node> Default for YamlLoader<'i, Node>
@@ -48,19 +72,218 @@ where
     fn default() -> Self {
         Self {
             docs: Vec::new(),
+            directives: Vec::new(),
             doc_stack: Vec::new(),
             key_stack: Vec::new(),
+            merge_stack: Vec::new(),
             anchor_map: BTreeMap::new(),
+            allow_undefined_aliases: false,
+            schema: Schema::Core,
+            detect_schema: false,
+            doc_schema: Schema::Core,
+            resolver: None,
+            standard_tags: false,
+            error: None,
         }
     }
 }
 
+impl<'i, Node> YamlLoader<'i, Node>
+where
+    Node: YamlDocAccess<'i>,
+{
+    /// Whether to load aliases that can't be resolved as [`YamlData::BadValue`].
+    ///
+    /// An alias can't be resolved when it refers to an anchor the loader hasn't finished, like
+    /// `&a [*a]`, or when the parser was configured with
+    /// [`parsing::Parser::allow_undefined_aliases`]. By default, such an alias is reported as an
+    /// error by [`YamlLoader::into_documents`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{Yaml, YamlDocAccess, YamlLoader};
+    ///
+    /// let mut parser = Parser::new_from_str("[*unknown]").allow_undefined_aliases(true);
+    /// let mut loader = YamlLoader::<Yaml>::default().allow_undefined_aliases(true);
+    /// parser.load(&mut loader, true).unwrap();
+    ///
+    /// let docs = loader.into_documents().unwrap();
+    /// assert!(docs[0][0].is_bad_value());
+    /// ```
+    #[must_use]
+    pub fn allow_undefined_aliases(mut self, value: bool) -> Self {
+        self.allow_undefined_aliases = value;
+        self
+    }
+
+    /// Sets the [`Schema`] used to resolve plain scalars, [`Schema::Core`] by default.
+    ///
+    /// With [`Schema::Yaml11`], merge keys (`<<`) are applied as well: the entries of the merged
+    /// mapping(s) are added to the mapping, unless it already has an entry with the same key.
+    /// A merge key whose value isn't a mapping or a sequence of mappings, or that has an anchor,
+    /// is reported as a [`YamlError::ResolveErr`] by [`YamlLoader::into_documents`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{Schema, Yaml, YamlLoader};
+    ///
+    /// let mut parser = Parser::new_from_str("base: &b {x: 1}\nuse: {<<: *b, enabled: yes}");
+    /// let mut loader = YamlLoader::<Yaml>::default().schema(Schema::Yaml11);
+    /// parser.load(&mut loader, true).unwrap();
+    ///
+    /// let docs = loader.into_documents().unwrap();
+    /// assert_eq!(docs[0]["use"]["x"], Yaml::from(1));
+    /// assert_eq!(docs[0]["use"]["enabled"], Yaml::from(true));
+    /// ```
+    #[must_use]
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = schema;
+        self.doc_schema = schema;
+        self
+    }
+
+    /// Whether to select the schema of each document from its `%YAML` directive.
+    ///
+    /// When set, documents starting with `%YAML 1.1` are loaded with [`Schema::Yaml11`], and
+    /// other documents with the schema set by [`YamlLoader::schema`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{Yaml, YamlLoader};
+    ///
+    /// let mut parser = Parser::new_from_str("%YAML 1.1\n--- off\n...\n--- off");
+    /// let mut loader = YamlLoader::<Yaml>::default().detect_schema(true);
+    /// parser.load(&mut loader, true).unwrap();
+    ///
+    /// let docs = loader.into_documents().unwrap();
+    /// assert_eq!(docs[0], Yaml::from(false));
+    /// assert_eq!(docs[1], Yaml::from("off"));
+    /// ```
+    #[must_use]
+    pub fn detect_schema(mut self, value: bool) -> Self {
+        self.detect_schema = value;
+        self
+    }
+
+    /// Whether to support the `!!timestamp`, `!!set`, `!!omap` and `!!pairs` tags of the
+    /// [YAML type repository](https://yaml.org/type/).
+    ///
+    /// When set, such nodes keep their tag after checking that they match it:
+    /// - `!!timestamp` scalars must be a valid [`Timestamp`], read with
+    ///   [`YamlDocAccess::as_timestamp`].
+    /// - `!!set` must be a mapping whose values are all null, e.g. `!!set {a, b}`.
+    /// - `!!omap` and `!!pairs` must be sequences of single-key mappings, e.g.
+    ///   `!!omap [a: 1, b: 2]`. The keys of an `!!omap` must be unique.
+    ///
+    /// Nodes that don't match their tag are loaded as [`YamlData::BadValue`], and the first
+    /// mismatch is reported as a [`YamlError::ResolveErr`] by [`YamlLoader::into_documents`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{Yaml, YamlDocAccess, YamlLoader};
+    ///
+    /// let mut parser = Parser::new_from_str("at: !!timestamp 2001-12-14 21:59:43.10 -5");
+    /// let mut loader = YamlLoader::<Yaml>::default().standard_tags(true);
+    /// parser.load(&mut loader, true).unwrap();
+    ///
+    /// let docs = loader.into_documents().unwrap();
+    /// let at = docs[0]["at"].as_timestamp().unwrap();
+    /// assert_eq!((at.year, at.hour, at.offset_minutes), (2001, 21, -300));
+    ///
+    /// let mut parser = Parser::new_from_str("!!set {a: 1}");
+    /// let mut loader = YamlLoader::<Yaml>::default().standard_tags(true);
+    /// parser.load(&mut loader, true).unwrap();
+    /// assert!(loader.into_documents().is_err());
+    /// ```
+    #[must_use]
+    pub fn standard_tags(mut self, value: bool) -> Self {
+        self.standard_tags = value;
+        self
+    }
+
+    /// Returns the directives of each document loaded so far, in the same order as the documents.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yam_core::parsing::Parser;
+    /// use yam_core::prelude::{Yaml, YamlLoader};
+    ///
+    /// let mut parser = Parser::new_from_str("%YAML 1.1\n%TAG !e! tag:e.com:\n--- a\n...\n--- b");
+    /// let mut loader = YamlLoader::<Yaml>::default();
+    /// parser.load(&mut loader, true).unwrap();
+    ///
+    /// let directives = loader.directives();
+    /// assert_eq!(directives[0].version, Some((1, 1)));
+    /// assert_eq!(directives[0].tags["!e!"], "tag:e.com:");
+    /// assert_eq!(directives[1].version, None);
+    /// assert!(directives[1].tags.is_empty());
+    /// ```
+    #[must_use]
+    pub fn directives(&self) -> &[Directives] {
+        &self.directives
+    }
+}
+
+///
+/// Sequence storage of a node type, see [`YamlDocAccess::SequenceNode`](crate::prelude::YamlDocAccess).
+///
+/// Besides the methods [`YamlLoader`] needs to build sequences, it offers the element operations
+/// used to edit them through generic code.
 pub trait SequenceLike<T>: IsEmpty {
     fn new_empty() -> Self;
 
     fn push_elem(&mut self, elem: T);
 
     fn vec(&self) -> &Vec<T>;
+
+    fn vec_mut(&mut self) -> &mut Vec<T>;
+
+    /// Element at `index`, if there is one.
+    fn elem(&self, index: usize) -> Option<&T> {
+        self.vec().get(index)
+    }
+
+    /// Mutable reference to the element at `index`, if there is one.
+    fn elem_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.vec_mut().get_mut(index)
+    }
+
+    /// Number of elements.
+    fn elem_count(&self) -> usize {
+        self.vec().len()
+    }
+
+    /// Iterates over the elements in order.
+    fn elems(&self) -> core::slice::Iter<'_, T> {
+        self.vec().iter()
+    }
+
+    /// Iterates mutably over the elements in order.
+    fn elems_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.vec_mut().iter_mut()
+    }
+
+    /// Inserts `elem` at `index`, shifting later elements.
+    ///
+    /// # Panics
+    /// If `index` is greater than the number of elements.
+    fn insert_elem(&mut self, index: usize, elem: T) {
+        self.vec_mut().insert(index, elem);
+    }
+
+    /// Removes the element at `index`, shifting later elements, or returns `None` if there is none.
+    fn remove_elem(&mut self, index: usize) -> Option<T> {
+        (index < self.elem_count()).then(|| self.vec_mut().remove(index))
+    }
+
+    /// Removes the last element.
+    fn pop_elem(&mut self) -> Option<T> {
+        self.vec_mut().pop()
+    }
 }
 
 impl<T> SequenceLike<T> for Vec<T>
@@ -78,14 +301,156 @@ where
     fn vec(&self) -> &Vec<T> {
         self
     }
+
+    fn vec_mut(&mut self) -> &mut Vec<T> {
+        self
+    }
 }
 
+///
+/// Mapping storage of a node type, see [`YamlDocAccess::MappingNode`](crate::prelude::YamlDocAccess).
+///
+/// Entries keep their insertion order, and removing one shifts the entries after it. Keys are
+/// compared as whole nodes, so `Yaml::from("a")` and `Yaml::from(1)` are different keys; use
+/// [`YamlDocAccess::get`](crate::prelude::YamlDocAccess::get) to look values up by `&str` or
+/// `usize`. Keys can't be changed in place, only removed and inserted again.
+///
+/// Method names avoid those of `Vec`, so that they can be called on `Vec<YamlEntry>` directly.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::{MappingLike, Yaml, YamlDocAccess};
+///
+/// let mut doc = Yaml::load_single("a: 1\nb: 2").unwrap();
+/// let map = doc.mapping_mut();
+/// assert_eq!(map.insert_value(Yaml::from("a"), Yaml::from(3)), Some(Yaml::from(1)));
+/// map.insert_value(Yaml::from("c"), Yaml::from(4));
+/// assert_eq!(map.remove_key(&Yaml::from("b")), Some(Yaml::from(2)));
+///
+/// let keys: Vec<_> = map.keys().filter_map(Yaml::as_str).collect();
+/// assert_eq!(keys, ["a", "c"]);
+/// ```
 pub trait MappingLike<T> {
     fn new_map() -> Self;
 
     fn push_mapping(&mut self, key: T, value: T);
 
     fn entries(&self) -> &Vec<YamlEntry<'_, T>>;
+
+    /// Iterates over the keys and mutable values in order.
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a T, &'a mut T)>
+    where
+        T: 'a;
+
+    /// Removes the entry at `index`, shifting later entries, and returns its key and value.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    fn remove_at(&mut self, index: usize) -> (T, T);
+
+    /// Keeps only the entries for which `keep` returns `true`, in order.
+    fn retain_entries(&mut self, keep: impl FnMut(&T, &mut T) -> bool);
+
+    /// Removes all entries.
+    fn clear_entries(&mut self);
+
+    /// Iterates over the keys in order.
+    fn keys<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.entries().iter().map(|entry| &entry.key)
+    }
+
+    /// Iterates over the values in order.
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.entries().iter().map(|entry| &entry.value)
+    }
+
+    /// Value of the first entry whose key satisfies `pred`.
+    fn find_value(&self, mut pred: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.entries()
+            .iter()
+            .find(|entry| pred(&entry.key))
+            .map(|entry| &entry.value)
+    }
+
+    /// Mutable reference to the value of the first entry whose key satisfies `pred`.
+    fn find_value_mut(&mut self, mut pred: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        self.entries_mut()
+            .find(|(key, _)| pred(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Value stored under `key`.
+    fn get_value(&self, key: &T) -> Option<&T>
+    where
+        T: PartialEq,
+    {
+        self.find_value(|k| k == key)
+    }
+
+    /// Mutable reference to the value stored under `key`.
+    fn get_value_mut(&mut self, key: &T) -> Option<&mut T>
+    where
+        T: PartialEq,
+    {
+        self.find_value_mut(|k| k == key)
+    }
+
+    /// Whether there is an entry for `key`.
+    fn contains_key(&self, key: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.get_value(key).is_some()
+    }
+
+    /// Stores `value` under `key`, returning the value it replaced. New keys are appended.
+    fn insert_value(&mut self, key: T, value: T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        match self.get_value_mut(&key) {
+            Some(old) => Some(mem::replace(old, value)),
+            None => {
+                self.push_mapping(key, value);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry for `key`, returning its value.
+    fn remove_key(&mut self, key: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let index = self.entries().iter().position(|entry| entry.key == *key)?;
+        Some(self.remove_at(index).1)
+    }
+
+    /// Mutable reference to the value stored under `key`, appending `default()` first if
+    /// there is no entry for `key`.
+    fn entry_or_insert_with(&mut self, key: T, default: impl FnOnce() -> T) -> &mut T
+    where
+        T: PartialEq,
+    {
+        let index = match self.entries().iter().position(|entry| entry.key == key) {
+            Some(index) => index,
+            None => {
+                self.push_mapping(key, default());
+                self.entries().len() - 1
+            }
+        };
+        self.entries_mut()
+            .nth(index)
+            .map(|(_, value)| value)
+            .expect("entry was just found or inserted")
+    }
 }
 
 impl<T> MappingLike<T> for Vec<YamlEntry<'_, T>> {
@@ -104,6 +469,26 @@ impl<T> MappingLike<T> for Vec<YamlEntry<'_, T>> {
     fn entries(&self) -> &Vec<YamlEntry<'_, T>> {
         self
     }
+
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a T, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.iter_mut().map(|entry| (&entry.key, &mut entry.value))
+    }
+
+    fn remove_at(&mut self, index: usize) -> (T, T) {
+        let entry = self.remove(index);
+        (entry.key, entry.value)
+    }
+
+    fn retain_entries(&mut self, mut keep: impl FnMut(&T, &mut T) -> bool) {
+        self.retain_mut(|entry| keep(&entry.key, &mut entry.value));
+    }
+
+    fn clear_entries(&mut self) {
+        self.clear();
+    }
 }
 
 impl<'input, Node, SEQ, MAP> YamlLoader<'input, Node>
@@ -114,21 +499,49 @@ where
     SEQ: SequenceLike<Node> + IsEmpty + Clone,
     MAP: MappingLike<Node> + IsEmpty + Clone,
 {
+    /// Sets a [`TagResolver`] that is consulted for every scalar and tagged collection before
+    /// the schema.
+    ///
+    /// Values it rejects are loaded as [`YamlData::BadValue`], and the first rejection is
+    /// reported as a [`YamlError::ResolveErr`] by [`YamlLoader::into_documents`].
     #[must_use]
-    pub fn into_documents(self) -> Vec<Node> {
-        self.docs
+    pub fn resolver<R: TagResolver + 'static>(mut self, resolver: R) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Returns the documents loaded so far.
+    ///
+    /// # Errors
+    /// Returns the first error encountered while loading, e.g. a [`YamlError::UndefinedAlias`].
+    pub fn into_documents(self) -> Result<Vec<Node>, YamlError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.docs),
+        }
+    }
+
+    /// Keeps `err` if it is the first error encountered.
+    fn record_error(&mut self, err: YamlError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
     }
 
     pub(crate) fn insert_new_node(
         &mut self,
         mut node: Node,
         anchor_id: usize,
+        anchor_name: Option<Cow<'input, str>>,
         tag: Option<Cow<'input, Tag>>,
     ) {
+        if let Some(name) = anchor_name {
+            node = node.with_anchor(name);
+        }
         if anchor_id > 0 {
             self.anchor_map.insert(anchor_id, node.clone());
         }
-        if let Some((parent_node, _, _)) = self.doc_stack.last_mut() {
+        if let Some((parent_node, ..)) = self.doc_stack.last_mut() {
             if let Some(tag) = tag
                 && node.is_collection()
                 && !tag.is_yaml_core_schema()
@@ -140,8 +553,12 @@ where
                 parent_node.sequence_mut().push_elem(node);
             } else if parent_node.is_mapping() {
                 let curr_key = self.key_stack.last_mut().unwrap();
+                let (merge_pending, merges) = self.merge_stack.last_mut().unwrap();
 
-                if curr_key.is_bad_value() {
+                if *merge_pending {
+                    *merge_pending = false;
+                    merges.push(node);
+                } else if curr_key.is_bad_value() {
                     *curr_key = node;
                 } else {
                     parent_node
@@ -150,7 +567,56 @@ where
                 }
             }
         } else {
-            self.doc_stack.push((node, anchor_id, tag));
+            self.doc_stack.push((node, anchor_id, None, tag));
+        }
+    }
+
+    /// Whether a plain `<<` scalar is about to be inserted as a key, making it a merge key.
+    fn is_merge_key(&self, value: &str, scalar_type: ScalarType, tag: Option<&Cow<Tag>>) -> bool {
+        self.doc_schema == Schema::Yaml11
+            && value == "<<"
+            && scalar_type == ScalarType::Plain
+            && tag.is_none()
+            && self
+                .doc_stack
+                .last()
+                .is_some_and(|(node, ..)| node.is_mapping())
+            && self
+                .key_stack
+                .last()
+                .is_some_and(YamlDocAccess::is_bad_value)
+    }
+
+    /// Adds the entries of the values merged with `<<` to `node`, skipping keys `node` already has.
+    /// Entries of earlier merged mappings take precedence over later ones.
+    fn apply_merges(&mut self, node: &mut Node, merges: Vec<Node>, mark: Marker) {
+        for merge in merges {
+            let sources: Vec<&Node> = if merge.is_mapping() {
+                vec![&merge]
+            } else if merge.is_sequence() && merge.sequence().vec().iter().all(Node::is_mapping) {
+                merge.sequence().vec().iter().collect()
+            } else {
+                self.record_error(YamlError::ResolveErr {
+                    mark,
+                    info: String::from(
+                        "merge key value must be a mapping or a sequence of mappings",
+                    ),
+                });
+                continue;
+            };
+            for source in sources {
+                for entry in source.mapping().entries() {
+                    let exists = node
+                        .mapping()
+                        .entries()
+                        .iter()
+                        .any(|e| same_scalar_key(&e.key, &entry.key));
+                    if !exists {
+                        node.mapping_mut()
+                            .push_mapping(entry.key.clone(), entry.value.clone());
+                    }
+                }
+            }
         }
     }
 
@@ -190,7 +656,7 @@ where
     ) -> Result<Vec<Node>, YamlError> {
         let mut loader = YamlLoader::default();
         parser.load(&mut loader, true)?;
-        Ok(loader.into_documents())
+        loader.into_documents()
     }
 
     ///
@@ -237,7 +703,7 @@ where
         let mut event_listener = YamlLoader::default();
         let mut parser = parsing::Parser::new(StrSource::new(input.as_ref()));
         parser.load(&mut event_listener, true)?;
-        Ok(event_listener.docs)
+        event_listener.into_documents()
     }
 
     ///
@@ -287,9 +753,9 @@ where
         let mut parser = parsing::Parser::new(StrSource::new(input.as_ref()));
         parser.load(&mut event_listener, false)?;
         event_listener
-            .docs
-            .first()
-            .cloned()
+            .into_documents()?
+            .into_iter()
+            .next()
             .ok_or(YamlError::NoDocument)
     }
 
@@ -337,9 +803,9 @@ where
         let mut event_listener = YamlLoader::default();
         parser.load(&mut event_listener, false)?;
         event_listener
-            .docs
-            .first()
-            .cloned()
+            .into_documents()?
+            .into_iter()
+            .next()
             .ok_or(YamlError::NoDocument)
     }
 
@@ -388,9 +854,9 @@ where
         let mut parser = parsing::Parser::new(input);
         parser.load(&mut event_listener, false)?;
         event_listener
-            .docs
-            .first()
-            .cloned()
+            .into_documents()?
+            .into_iter()
+            .next()
             .ok_or(YamlError::NoDocument)
     }
 }
@@ -406,11 +872,15 @@ where
     fn on_event(&mut self, ev: Event<'input>, span: Span) {
         let mark = span.start;
         match ev {
-            Event::DocumentStart(_)
-            | Event::Nothing
-            | Event::StreamStart
-            | Event::StreamEnd
-            | Event::Comment(_) => {
+            Event::DocumentStart(_, directives) => {
+                self.doc_schema = directives
+                    .version
+                    .filter(|_| self.detect_schema)
+                    .and_then(Schema::from_version)
+                    .unwrap_or(self.schema);
+                self.directives.push(directives);
+            }
+            Event::Nothing | Event::StreamStart | Event::StreamEnd | Event::Comment(_) => {
                 // do nothing
             }
             Event::DocumentEnd => {
@@ -421,60 +891,589 @@ where
                     _ => unreachable!(),
                 }
             }
-            Event::SequenceStart(aid, tag) => {
+            Event::SequenceStart(aid, anchor_name, tag, style) => {
                 let node: Node = YamlData::Sequence(Vec::new()).into();
+                let node = node.with_presentation(Presentation::collection(style));
 
-                self.doc_stack.push((node.with_start(mark), aid, tag));
+                self.doc_stack
+                    .push((node.with_start(mark), aid, anchor_name, tag));
             }
-            Event::MappingStart(aid, tag) => {
+            Event::MappingStart(aid, anchor_name, tag, style) => {
                 let node: Node = YamlData::Mapping(Vec::new()).into();
-                self.doc_stack.push((node.with_start(mark), aid, tag));
+                let node = node.with_presentation(Presentation::collection(style));
+                self.doc_stack
+                    .push((node.with_start(mark), aid, anchor_name, tag));
                 self.key_stack.push(YamlData::BadValue.into());
+                self.merge_stack.push((false, Vec::new()));
             }
             Event::MappingEnd | Event::SequenceEnd => {
+                let (mut node, anchor_id, anchor_name, tag) = self.doc_stack.pop().unwrap();
                 if ev == Event::MappingEnd {
                     self.key_stack.pop().unwrap();
+                    let (_, merges) = self.merge_stack.pop().unwrap();
+                    if !merges.is_empty() {
+                        self.apply_merges(&mut node, merges, mark);
+                    }
                 }
-
-                let (mut node, anchor_id, tag) = self.doc_stack.pop().unwrap();
                 node = node.with_end(mark);
-                if let Some(tag) = tag
-                    && !tag.is_yaml_core_schema()
-                {
-                    node = node.into_tagged(tag);
+                if let Some(tag) = tag {
+                    let resolved = match &self.resolver {
+                        Some(resolver) => resolver.collection(&node, &tag),
+                        None => Ok(None),
+                    };
+                    node = match resolved {
+                        Ok(Some(resolved)) => resolved,
+                        Ok(None) if !tag.is_yaml_core_schema() => node.into_tagged(tag),
+                        Ok(None)
+                            if self.standard_tags
+                                && matches!(tag.suffix.as_str(), "set" | "omap" | "pairs") =>
+                        {
+                            match check_standard_collection(&node, &tag) {
+                                Ok(()) => node.into_tagged(tag),
+                                Err(info) => {
+                                    self.record_error(YamlError::ResolveErr { mark, info });
+                                    YamlData::BadValue.into()
+                                }
+                            }
+                        }
+                        Ok(None) => node,
+                        Err(info) => {
+                            self.record_error(YamlError::ResolveErr { mark, info });
+                            YamlData::BadValue.into()
+                        }
+                    };
                 }
-                self.insert_new_node(node, anchor_id, None);
+                self.insert_new_node(node, anchor_id, anchor_name, None);
             }
             Event::Scalar(ScalarValue {
                 value,
                 scalar_type,
                 anchor_id,
+                anchor_name,
                 tag,
+                block_indicators,
             }) => {
-                let node =
-                    YamlData::value_from_cow_and_metadata(value, scalar_type, tag.as_ref()).into();
-                self.insert_new_node(node, anchor_id, tag);
+                if self.is_merge_key(&value, scalar_type, tag.as_ref()) {
+                    if anchor_name.is_some() {
+                        let info = String::from("a merge key can't have an anchor");
+                        self.record_error(YamlError::ResolveErr { mark, info });
+                    }
+                    self.merge_stack.last_mut().unwrap().0 = true;
+                    return;
+                }
+                let resolved = match &self.resolver {
+                    Some(resolver) => resolver.scalar(&value, scalar_type, tag.as_deref()),
+                    None => Ok(None),
+                };
+                // Once validated, `!!timestamp` scalars keep their text and tag.
+                let timestamp_tag = tag
+                    .as_ref()
+                    .filter(|tag| self.standard_tags && tag.is_yaml_tag("timestamp"));
+                let node: Node = match (resolved, timestamp_tag) {
+                    (Ok(None), Some(timestamp)) if Timestamp::parse(&value).is_some() => {
+                        let node: Node = YamlScalar::String(value).into();
+                        node.into_tagged(timestamp.clone())
+                    }
+                    (Ok(None), Some(_)) => {
+                        let info = format!("invalid !!timestamp `{value}`");
+                        self.record_error(YamlError::ResolveErr { mark, info });
+                        YamlData::BadValue.into()
+                    }
+                    (Ok(Some(scalar)), _) => scalar.into(),
+                    (Ok(None), None) => YamlData::value_from_cow_and_metadata(
+                        value,
+                        scalar_type,
+                        tag.as_ref(),
+                        self.doc_schema,
+                    )
+                    .into(),
+                    (Err(info), _) => {
+                        self.record_error(YamlError::ResolveErr { mark, info });
+                        YamlData::BadValue.into()
+                    }
+                };
+                let node = node
+                    .with_span(span)
+                    .with_presentation(Presentation::scalar(scalar_type, block_indicators));
+                self.insert_new_node(node, anchor_id, anchor_name, tag);
             }
-            Event::Alias(id) => {
+            Event::Alias(id, name) => {
                 let n = match self.anchor_map.get(&id) {
-                    Some(v) => v.clone(),
-                    None => YamlData::BadValue.into(),
+                    Some(v) => v.clone().with_alias(name),
+                    None => {
+                        if !self.allow_undefined_aliases && self.error.is_none() {
+                            self.error = Some(YamlError::UndefinedAlias {
+                                mark,
+                                name: name.into_owned(),
+                            });
+                        }
+                        YamlData::BadValue.into()
+                    }
                 };
-                self.insert_new_node(n.with_span(span), 0, None);
+                self.insert_new_node(n.with_span(span), 0, None, None);
             }
         }
     }
 }
 
+/// A [`TagResolver`] with its collection hook bound to the node type of a [`YamlLoader`].
+trait NodeResolver<'input, Node> {
+    fn scalar(
+        &self,
+        value: &Cow<'input, str>,
+        scalar_type: ScalarType,
+        tag: Option<&Tag>,
+    ) -> Result<Option<YamlScalar<'input>>, String>;
+
+    fn collection(&self, node: &Node, tag: &Tag) -> Result<Option<Node>, String>;
+}
+
+impl<'input, Node, R> NodeResolver<'input, Node> for R
+where
+    R: TagResolver,
+    Node: YamlDocAccess<'input, OutNode = Node>
+        + From<YamlData<'input, Node>>
+        + From<YamlScalar<'input>>,
+    Node::MappingNode: MappingLike<Node>,
+    Node::SequenceNode: SequenceLike<Node>,
+{
+    fn scalar(
+        &self,
+        value: &Cow<'input, str>,
+        scalar_type: ScalarType,
+        tag: Option<&Tag>,
+    ) -> Result<Option<YamlScalar<'input>>, String> {
+        self.resolve(value, scalar_type, tag)
+    }
+
+    fn collection(&self, node: &Node, tag: &Tag) -> Result<Option<Node>, String> {
+        self.resolve_collection(node, tag)
+    }
+}
+
+/// Checks that a `!!set`, `!!omap` or `!!pairs` collection matches its tag.
+fn check_standard_collection<'a, Node>(node: &Node, tag: &Tag) -> Result<(), String>
+where
+    Node: YamlDocAccess<'a, OutNode = Node>,
+    Node::MappingNode: MappingLike<Node>,
+    Node::SequenceNode: SequenceLike<Node>,
+{
+    let tag = tag.suffix.as_str();
+    if tag == "set" {
+        // `{a, b}` leaves the values empty, which the default schema loads as empty strings.
+        let is_empty = |v: &Node| v.is_null() || v.as_str() == Some("");
+        return if node.is_mapping() && node.mapping().entries().iter().all(|e| is_empty(&e.value)) {
+            Ok(())
+        } else {
+            Err("!!set must be a mapping with null values".to_string())
+        };
+    }
+    let pairs = node.is_sequence().then(|| node.sequence().vec());
+    let Some(pairs) = pairs.filter(|p| {
+        p.iter()
+            .all(|pair| pair.is_mapping() && pair.mapping().entries().len() == 1)
+    }) else {
+        return Err(format!("!!{tag} must be a sequence of single-key mappings"));
+    };
+    if tag == "omap" {
+        let keys: Vec<&Node> = pairs
+            .iter()
+            .map(|p| &p.mapping().entries()[0].key)
+            .collect();
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].iter().any(|k| same_scalar_key(*k, *key)) {
+                return Err("!!omap keys must be unique".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether two keys are equal scalars. Used to find the keys a mapping already has when merging.
+fn same_scalar_key<'a, Node: YamlDocAccess<'a>>(a: &Node, b: &Node) -> bool {
+    match (a.get_type(), b.get_type()) {
+        (NodeType::Null, NodeType::Null) => true,
+        (NodeType::String, NodeType::String) => a.as_str() == b.as_str(),
+        (NodeType::Bool, NodeType::Bool) => a.as_bool() == b.as_bool(),
+        (NodeType::Integer, NodeType::Integer) => a.as_i64() == b.as_i64(),
+        (NodeType::Floating, NodeType::Floating) => a.as_f64() == b.as_f64(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::prelude::{Yaml, YamlLoader};
-    use alloc::borrow::ToOwned;
+    use crate::parsing::{BlockIndicators, ChompIndicator, Parser};
+    use crate::prelude::{
+        CollectionStyle, MappingLike, Marker, ScalarType, Schema, SequenceLike, SpannedYaml, Tag,
+        TagResolver, Yaml, YamlData, YamlDocAccess, YamlError, YamlLoader, YamlScalar,
+    };
+    use alloc::borrow::{Cow, ToOwned};
+    use alloc::string::String;
+    use alloc::vec;
     use alloc::vec::Vec;
 
     #[test]
     fn test_simple() {
         let yaml_str = "{a : b, c: d}".to_owned();
         let doc: Vec<Yaml> = YamlLoader::load_from(yaml_str).unwrap();
+        assert_eq!(doc.len(), 1);
+    }
+
+    #[test]
+    fn test_undefined_alias() {
+        let err = Yaml::load_from("a: &x 1\nb: *y").unwrap_err();
+        assert_eq!(
+            err,
+            YamlError::UndefinedAlias {
+                mark: Marker::new(11, 4, 2),
+                name: "y".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_alias_scoped_to_document() {
+        let err = Yaml::load_from("--- &x 1\n--- *x").unwrap_err();
+        assert!(matches!(err, YamlError::UndefinedAlias { name, .. } if name == "x"));
+    }
+
+    #[test]
+    fn test_recursive_alias() {
+        let err = Yaml::load_from("&a [*a]").unwrap_err();
+        assert!(matches!(err, YamlError::UndefinedAlias { name, .. } if name == "a"));
+
+        let mut parser = Parser::new_from_str("&a [*a]");
+        let mut loader = YamlLoader::<Yaml>::default().allow_undefined_aliases(true);
+        parser.load(&mut loader, true).unwrap();
+        let docs = loader.into_documents().unwrap();
+        assert!(docs[0][0].is_bad_value());
+    }
+
+    #[test]
+    fn test_lenient_undefined_alias() {
+        let mut parser = Parser::new_from_str("[1, *y]").allow_undefined_aliases(true);
+        let err = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap_err();
+        assert!(matches!(err, YamlError::UndefinedAlias { name, .. } if name == "y"));
+
+        let mut parser = Parser::new_from_str("[1, *y]").allow_undefined_aliases(true);
+        let mut loader = YamlLoader::<Yaml>::default().allow_undefined_aliases(true);
+        parser.load(&mut loader, true).unwrap();
+        let docs = loader.into_documents().unwrap();
+        assert_eq!(docs[0][0], Yaml::from(1));
+        assert!(docs[0][1].is_bad_value());
+    }
+
+    #[test]
+    fn test_spanned_anchor() {
+        let mut parser = Parser::new_from_str("a: &x [1]\nb: &y 2\nc: *x");
+        let doc = YamlLoader::<SpannedYaml>::load_from_parser(&mut parser)
+            .unwrap()
+            .remove(0);
+        let entries = doc.mapping();
+        assert_eq!(entries[0].value.get_anchor(), Some("x"));
+        assert_eq!(entries[1].value.get_anchor(), Some("y"));
+        assert_eq!(entries[1].value.span.start, Marker::new(16, 7, 2));
+        assert_eq!(entries[2].value.get_anchor(), None);
+        assert_eq!(entries[2].value.get_alias(), Some("x"));
+        assert_eq!(entries[2].value.sequence().len(), 1);
+        assert_eq!(entries[0].value.get_alias(), None);
+        assert_eq!(entries[0].key.get_anchor(), None);
+    }
+
+    #[test]
+    fn test_yaml_anchor() {
+        let doc = Yaml::load_single("a: &x [1]\nb: &y !t 2\nc: *x\nd: &z !t [3]").unwrap();
+        assert_eq!(doc["a"].get_anchor(), Some("x"));
+        assert_eq!(doc["a"].get_alias(), None);
+        assert_eq!(doc["b"].get_anchor(), Some("y"));
+        assert_eq!(doc["c"].get_anchor(), None);
+        assert_eq!(doc["c"].get_alias(), Some("x"));
+        assert_eq!(doc["c"], doc["a"]);
+        assert_eq!(doc["a"], Yaml::from(vec![Yaml::from(1)]));
+        assert_eq!(doc["d"].get_anchor(), Some("z"));
+    }
+
+    #[test]
+    fn test_spanned_presentation() {
+        let input = "a: |2-\n   x\nb: >+\n  y\nc: [\"z\"]\n";
+        let doc = YamlLoader::<SpannedYaml>::load_from(input)
+            .unwrap()
+            .remove(0);
+        let entries = doc.mapping();
+        let literal = entries[0].value.presentation;
+        assert_eq!(literal.scalar_type, ScalarType::Literal);
+        assert_eq!(
+            literal.block_indicators,
+            Some(BlockIndicators {
+                chomp: ChompIndicator::Strip,
+                indent: Some(2),
+            })
+        );
+        assert_eq!(entries[0].value.as_str(), Some(" x"));
+
+        let folded = entries[1].value.presentation;
+        assert_eq!(folded.scalar_type, ScalarType::Folded);
+        assert_eq!(folded.block_indicators.unwrap().chomp, ChompIndicator::Keep);
+        assert_eq!(folded.block_indicators.unwrap().indent, None);
+
+        assert_eq!(doc.presentation.collection_style, CollectionStyle::Block);
+        assert_eq!(
+            entries[2].value.presentation.collection_style,
+            CollectionStyle::Flow
+        );
+        let quoted = entries[2].value.sequence()[0].presentation;
+        assert_eq!(quoted.scalar_type, ScalarType::DoubleQuote);
+        assert_eq!(quoted.block_indicators, None);
+    }
+
+    #[test]
+    fn test_directives() {
+        let input = "%TAG !a! tag:a:\n%TAG !b! tag:b:\n--- !a!x 1\n...\n--- !b!y 2";
+        let mut parser = Parser::new_from_str(input).keep_tags(true);
+        let mut loader = YamlLoader::<Yaml>::default();
+        parser.load(&mut loader, true).unwrap();
+        let directives = loader.directives();
+        assert_eq!(directives.len(), 2);
+        assert_eq!(directives[0].tags.len(), 2);
+        assert_eq!(directives[0], directives[1]);
+
+        let err = Yaml::load_from("%TAG !a! tag:a:\n%TAG !a! tag:b:\n--- 1").unwrap_err();
+        assert!(matches!(err, YamlError::ScannerErr { .. }));
+    }
+
+    #[test]
+    fn test_merge_keys() {
+        let input = "
+base: &base {a: 1, b: 2}
+other: &other {b: 3, c: 4}
+single: {<<: *base, b: 5}
+list:
+  <<: [*base, *other]
+  d: 6
+quoted: {'<<': *base}
+";
+        let mut parser = Parser::new_from_str(input);
+        let mut loader = YamlLoader::<Yaml>::default().schema(Schema::Yaml11);
+        parser.load(&mut loader, true).unwrap();
+        let doc = loader.into_documents().unwrap().remove(0);
+
+        let single = &doc["single"];
+        assert_eq!(single.mapping().len(), 2);
+        assert_eq!(single["a"], Yaml::from(1));
+        assert_eq!(single["b"], Yaml::from(5));
+
+        let list = &doc["list"];
+        assert_eq!(list.mapping().len(), 4);
+        assert_eq!(list["b"], Yaml::from(2));
+        assert_eq!(list["c"], Yaml::from(4));
+        assert_eq!(list["d"], Yaml::from(6));
+
+        assert!(doc["quoted"]["<<"].is_mapping());
+
+        let core = Yaml::load_from("{<<: {a: 1}}").unwrap().remove(0);
+        assert!(core["<<"].is_mapping());
+    }
+
+    #[test]
+    fn test_invalid_merge() {
+        let mut parser = Parser::new_from_str("{<<: [1]}");
+        let mut loader = YamlLoader::<Yaml>::default().schema(Schema::Yaml11);
+        parser.load(&mut loader, true).unwrap();
+        assert!(matches!(
+            loader.into_documents(),
+            Err(YamlError::ResolveErr { info, .. }) if info.starts_with("merge key value")
+        ));
+
+        let mut parser = Parser::new_from_str("base: &b {x: 1}\nuse: {&m <<: *b}\nagain: *m");
+        let mut loader = YamlLoader::<Yaml>::default().schema(Schema::Yaml11);
+        parser.load(&mut loader, true).unwrap();
+        assert!(matches!(
+            loader.into_documents(),
+            Err(YamlError::ResolveErr { info, mark })
+                if info == "a merge key can't have an anchor" && mark.line == 2
+        ));
+    }
+
+    struct Env;
+
+    impl TagResolver for Env {
+        fn resolve<'a>(
+            &self,
+            value: &Cow<'a, str>,
+            scalar_type: ScalarType,
+            tag: Option<&Tag>,
+        ) -> Result<Option<YamlScalar<'a>>, String> {
+            match (tag, value.as_ref()) {
+                (Some(tag), "HOME") if tag.suffix == "env" => {
+                    Ok(Some(YamlScalar::String("/root".into())))
+                }
+                (Some(tag), _) if tag.suffix == "env" => Err("unknown variable".into()),
+                (None, "off") if scalar_type == ScalarType::Plain => {
+                    Ok(Some(YamlScalar::Bool(false)))
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tag_resolver() {
+        let mut parser = Parser::new_from_str(
+            "home: !env HOME
+flag: off
+quoted: 'off'
+n: 3",
+        );
+        let mut loader = YamlLoader::<Yaml>::default().resolver(Env);
+        parser.load(&mut loader, true).unwrap();
+        let doc = loader.into_documents().unwrap().remove(0);
+        assert_eq!(doc["home"], Yaml::from("/root"));
+        assert_eq!(doc["flag"], Yaml::from(false));
+        assert_eq!(doc["quoted"], Yaml::from("off"));
+        assert_eq!(doc["n"], Yaml::from(3));
+
+        let mut parser = Parser::new_from_str("a: !env PATH");
+        let mut loader = YamlLoader::<Yaml>::default().resolver(Env);
+        parser.load(&mut loader, true).unwrap();
+        assert!(matches!(
+            loader.into_documents(),
+            Err(YamlError::ResolveErr { info, .. }) if info == "unknown variable"
+        ));
+    }
+
+    /// Loads `!point {x, y}` mappings as `[x, y]` sequences.
+    struct Point;
+
+    impl TagResolver for Point {
+        fn resolve<'a>(
+            &self,
+            _value: &Cow<'a, str>,
+            _scalar_type: ScalarType,
+            _tag: Option<&Tag>,
+        ) -> Result<Option<YamlScalar<'a>>, String> {
+            Ok(None)
+        }
+
+        fn resolve_collection<'a, N>(&self, node: &N, tag: &Tag) -> Result<Option<N>, String>
+        where
+            N: YamlDocAccess<'a, OutNode = N> + From<YamlData<'a, N>> + From<YamlScalar<'a>>,
+            N::MappingNode: MappingLike<N>,
+            N::SequenceNode: SequenceLike<N>,
+        {
+            if tag.suffix != "point" {
+                return Ok(None);
+            }
+            if !node.is_mapping() {
+                return Err("!point must be a mapping".into());
+            }
+            let coord = |name| node.mapping().find_value(|k| k.as_str() == Some(name));
+            match (coord("x"), coord("y")) {
+                (Some(x), Some(y)) => {
+                    Ok(Some(YamlData::Sequence(vec![x.clone(), y.clone()]).into()))
+                }
+                _ => Err("!point needs x and y".into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_collection_resolver() {
+        let input = "a: !point {x: 1, y: 2}\nb: !other {x: 1}\nc: !point\n  y: 4\n  x: 3";
+        let mut parser = Parser::new_from_str(input);
+        let mut loader = YamlLoader::<Yaml>::default().resolver(Point);
+        parser.load(&mut loader, true).unwrap();
+        let doc = loader.into_documents().unwrap().remove(0);
+        assert_eq!(doc["a"], Yaml::from(vec![Yaml::from(1), Yaml::from(2)]));
+        assert_eq!(doc["b"].get_tag().unwrap().suffix, "other");
+        assert_eq!(doc["c"], Yaml::from(vec![Yaml::from(3), Yaml::from(4)]));
+
+        let mut parser = Parser::new_from_str("a: !point {x: 1}");
+        let mut loader = YamlLoader::<SpannedYaml>::default().resolver(Point);
+        parser.load(&mut loader, true).unwrap();
+        assert!(matches!(
+            loader.into_documents(),
+            Err(YamlError::ResolveErr { info, mark }) if info == "!point needs x and y" && mark.col == 16
+        ));
+    }
+
+    fn load_standard(input: &str) -> Result<Vec<Yaml<'_>>, YamlError> {
+        let mut parser = Parser::new_from_str(input);
+        let mut loader = YamlLoader::<Yaml>::default().standard_tags(true);
+        parser.load(&mut loader, true).unwrap();
+        loader.into_documents()
+    }
+
+    #[test]
+    fn test_standard_tags() {
+        let input = "
+at: !!timestamp 2001-12-15T02:59:43.1Z
+set: !!set {a, b}
+omap: !!omap [a: 1, b: 2]
+pairs: !!pairs [a: 1, a: 2]
+";
+        let doc = load_standard(input).unwrap().remove(0);
+        let at = doc["at"].as_timestamp().unwrap();
+        assert_eq!((at.year, at.month, at.day, at.second), (2001, 12, 15, 43));
+        for key in ["set", "omap", "pairs"] {
+            assert_eq!(doc[key].get_tag().unwrap().suffix, key);
+        }
+
+        let plain = Yaml::load_from("!!set {a: 1}").unwrap().remove(0);
+        assert!(plain.is_mapping());
+        assert!(Yaml::load_from("a: !!timestamp 2001-12-15").unwrap()[0]["a"].is_bad_value());
+    }
+
+    #[test]
+    fn test_invalid_standard_tags() {
+        for (input, info) in [
+            ("!!timestamp 2001-02-30", "invalid !!timestamp `2001-02-30`"),
+            ("!!set {a: 1}", "!!set must be a mapping with null values"),
+            ("!!set [a]", "!!set must be a mapping with null values"),
+            (
+                "!!omap [a: 1, b]",
+                "!!omap must be a sequence of single-key mappings",
+            ),
+            (
+                "!!pairs [{a: 1, b: 2}]",
+                "!!pairs must be a sequence of single-key mappings",
+            ),
+            ("!!omap [a: 1, a: 2]", "!!omap keys must be unique"),
+        ] {
+            match load_standard(input) {
+                Err(YamlError::ResolveErr { info: err, .. }) => assert_eq!(err, info),
+                res => panic!("{input}: {res:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_collection_api() {
+        let mut doc = Yaml::load_single("{a: 1, b: [x, y], c: 3}").unwrap();
+        let map = doc.mapping_mut();
+        let key = |k: &'static str| Yaml::from(k);
+        assert!(map.contains_key(&key("b")));
+        assert_eq!(map.get_value(&key("c")), Some(&Yaml::from(3)));
+        assert_eq!(map.insert_value(key("d"), Yaml::from(4)), None);
+        assert_eq!(map.remove_key(&key("a")), Some(Yaml::from(1)));
+        assert_eq!(map.remove_key(&key("a")), None);
+        *map.entry_or_insert_with(key("e"), Yaml::null) = Yaml::from(5);
+        *map.entry_or_insert_with(key("c"), Yaml::null) = Yaml::from(6);
+        map.retain_entries(|k, _| k != &key("d"));
+        let keys: Vec<_> = map.keys().filter_map(Yaml::as_str).collect();
+        assert_eq!(keys, ["b", "c", "e"]);
+        let values: Vec<_> = map.values().filter_map(Yaml::as_i64).collect();
+        assert_eq!(values, [6, 5]);
+
+        let seq = map.get_value_mut(&key("b")).unwrap().sequence_mut();
+        seq.insert_elem(1, Yaml::from("z"));
+        assert_eq!(seq.remove_elem(0), Some(Yaml::from("x")));
+        assert_eq!(seq.remove_elem(5), None);
+        assert_eq!(seq.elem(1), Some(&Yaml::from("y")));
+        assert_eq!(seq.pop_elem(), Some(Yaml::from("y")));
+        assert_eq!(seq.elem_count(), 1);
+
+        map.clear_entries();
+        assert!(doc.mapping().is_empty());
     }
 }
@@ -1,12 +1,19 @@
 //! Use `yam_core::prelude::*` to import common components and traits.
+pub use crate::node::OrdFloat;
+pub use crate::node::Presentation;
+pub use crate::node::Schema;
+pub use crate::node::TagResolver;
+pub use crate::node::Timestamp;
 pub use crate::node::YamlScalar;
 pub use crate::node::spanned_yaml::SpannedYaml;
-pub use crate::node::yaml::Yaml;
+pub use crate::node::yaml::{NodeName, Yaml};
 pub use crate::node::yaml_data::YamlData;
 pub use crate::node::yaml_owned::YamlOwned;
+pub use crate::node::{ArenaData, ArenaLoader, ArenaNode, ArenaRef, NodeId, YamlArena};
 pub use crate::parsing::Source;
 pub use crate::parsing::StrSource;
 pub use crate::parsing::Tag;
+pub use crate::query::{QueryError, YamlPath};
 use alloc::borrow::Cow;
 use alloc::collections::{BTreeMap, LinkedList};
 use alloc::string::{String, ToString};
@@ -15,11 +22,15 @@ use core::fmt;
 use core::fmt::{Display, Formatter};
 use core::marker::PhantomData;
 use core::str::Utf8Error;
+pub use line_index::{LineColumns, LineIndex};
 pub use loader::MappingLike;
 pub use loader::SequenceLike;
 pub use loader::YamlLoader;
+pub use path::YamlIndex;
 
+mod line_index;
 mod loader;
+pub(crate) mod path;
 
 /// Represents a marker within an input string for tracking position.
 ///
@@ -31,8 +42,9 @@ mod loader;
 pub struct Marker {
     /// The zero-based index in bytes of the input string.
     pub pos: usize,
-    ///  The one-indexed column number corresponding to the marker's position.
-    ///  This is useful for reporting the horizontal location of the marker.
+    ///  The one-indexed column number corresponding to the marker's position, counted in
+    ///  characters (Unicode scalar values). See [`LineIndex`] for columns in bytes or UTF-16 code
+    ///  units.
     pub col: u32,
     /// The one-indexed line number corresponding to the marker's position.
     /// This is often used when tracking the vertical location of the marker.
@@ -97,9 +109,11 @@ pub enum YamlError {
     Io(String),
     /// Didn't expect and end of file at that position.
     UnexpectedEof,
-    /// Input decoding error. If `encoding` feature is disabled, contains `None`,
-    /// otherwise contains the UTF-8 decoding error
+    /// Input decoding error, converted from a [`Utf8Error`].
     NonDecodable(Option<Utf8Error>),
+    /// The input isn't valid in its encoding, e.g. an unpaired UTF-16 surrogate. `offset` is the
+    /// position of the invalid bytes in the input, before it was converted to UTF-8.
+    InvalidEncoding { offset: usize, info: &'static str },
     ///
     /// Represents an error encountered during scanning or parsing operations.
     ///
@@ -116,6 +130,12 @@ pub enum YamlError {
     ///   A description or message detailing the nature of the error. This provides a human-readable
     ///   explanation of what caused the error, aiding in understanding and resolving the issue.
     ScannerErr { mark: Marker, info: String },
+    /// An alias (`*name`) refers to an anchor that wasn't defined earlier in the same document.
+    UndefinedAlias { mark: Marker, name: String },
+    /// A [`TagResolver`] rejected the scalar at `mark`, e.g. `!duration abc`, a node doesn't
+    /// match its standard tag, e.g. `!!set {a: 1}`, or a YAML 1.1 merge key is invalid, e.g.
+    /// `<<: [1]`.
+    ResolveErr { mark: Marker, info: String },
     /// Expected a document but found none.
     NoDocument,
     UnExpectedEvent {
@@ -133,9 +153,18 @@ impl Display for YamlError {
             YamlError::NonDecodable(utf8_error) => {
                 write!(f, "Non-decodable input: {utf8_error:?}")
             }
+            YamlError::InvalidEncoding { offset, info } => {
+                write!(f, "Invalid encoding at byte {offset}: {info}")
+            }
             YamlError::ScannerErr { mark, info } => {
                 write!(f, "Scanner error at marker {mark:?}: {info}")
             }
+            YamlError::UndefinedAlias { mark, name } => {
+                write!(f, "Undefined alias '*{name}' at marker {mark:?}")
+            }
+            YamlError::ResolveErr { mark, info } => {
+                write!(f, "Failed to resolve node at marker {mark:?}: {info}")
+            }
             YamlError::NoDocument => write!(f, "No document found"),
             YamlError::UnExpectedEvent { expected, found } => {
                 write!(f, "Expected event '{expected}' but found '{found}' instead")
@@ -394,7 +423,7 @@ pub trait YamlDocAccess<'input>: Clone {
     /// # Example
     /// ```
     /// use yam_core::prelude::{Yaml, YamlData, YamlDocAccess};
-    /// let alias : Yaml = Yaml(YamlData::Alias(3));
+    /// let alias : Yaml = Yaml(YamlData::Alias(3), None);
     ///
     /// assert!(alias.is_alias());
     ///```
@@ -465,7 +494,7 @@ pub trait YamlDocAccess<'input>: Clone {
     ///
     /// let example = Yaml(YamlData::Sequence(vec![
     ///     Yaml::from(1), Yaml::from(2)
-    /// ]));
+    /// ]), None);
     /// assert!(example.is_sequence());
     /// ```
     ///
@@ -610,6 +639,17 @@ pub trait YamlDocAccess<'input>: Clone {
     ///
     fn as_str_mut(&mut self) -> Option<&mut str>;
 
+    /// Returns the [`Timestamp`] of a `!!timestamp` node.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Timestamp)` if the node is a `!!timestamp` loaded with
+    ///   [`YamlLoader::standard_tags`]
+    /// - `None` otherwise, and always for node types that don't support standard tags.
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        None
+    }
+
     /// Provides mutable access to the sequence within the implementing type.
     ///
     /// This method allows for getting a mutable reference to a `Vec` associated with
@@ -647,7 +687,7 @@ pub trait YamlDocAccess<'input>: Clone {
     /// ```rust
     /// use yam_core::prelude::{Yaml, YamlData, YamlDocAccess};
     ///
-    /// let mut instance = Yaml(YamlData::Sequence(vec![Yaml::from(true)]));
+    /// let mut instance = Yaml(YamlData::Sequence(vec![Yaml::from(true)]), None);
     /// let sequence = instance.sequence();
     /// ```
     fn sequence(&self) -> &Self::SequenceNode;
@@ -672,7 +712,7 @@ pub trait YamlDocAccess<'input>: Clone {
     ///
     /// let entry1 = YamlEntry::new("key".into(), "value".into());
     /// let entry2 = YamlEntry::new("another_key".into(), "value2".into());
-    /// let mut instance = Yaml(YamlData::Mapping(vec![entry1]));
+    /// let mut instance = Yaml(YamlData::Mapping(vec![entry1]), None);
     /// let sequence = instance.mapping_mut();
     /// sequence.push(entry2);
     /// ```
@@ -697,7 +737,7 @@ pub trait YamlDocAccess<'input>: Clone {
     /// use yam_core::prelude::{Yaml, YamlEntry, YamlDocAccess, YamlData};
     ///
     /// let entry1 = YamlEntry::new("key".into(), "value".into());
-    /// let mut instance = Yaml(YamlData::Mapping(vec![entry1]));
+    /// let mut instance = Yaml(YamlData::Mapping(vec![entry1]), None);
     /// let sequence = instance.mapping();
     /// ```
     fn mapping(&self) -> &Self::MappingNode;
@@ -713,6 +753,23 @@ pub trait YamlDocAccess<'input>: Clone {
     ///
     fn get_tag(&self) -> Option<Tag>;
 
+    /// Returns the node wrapped by a tag, or `None` if this isn't a tagged node.
+    fn tagged_node(&self) -> Option<&Self> {
+        None
+    }
+
+    /// Mutable version of [`YamlDocAccess::tagged_node`].
+    fn tagged_node_mut(&mut self) -> Option<&mut Self> {
+        None
+    }
+
+    /// Returns where this node was found in the source, for node types that record it.
+    ///
+    /// Only [`SpannedYaml`] keeps spans; every other node type returns `None`.
+    fn source_span(&self) -> Option<Span> {
+        None
+    }
+
     /// Retrieves the type of the current node.
     ///
     /// This method returns the `NodeType` of the node on which it is called.
@@ -916,7 +973,7 @@ pub trait YamlDocAccess<'input>: Clone {
     /// let mut value = Yaml::from(true);
     /// let previous_value = value.take();
     ///
-    /// assert_eq!(previous_value, Yaml(YamlData::Scalar(YamlScalar::Bool(true))));
+    /// assert_eq!(previous_value, Yaml(YamlData::Scalar(YamlScalar::Bool(true)), None));
     /// assert_eq!(value, Yaml::bad_value());
     /// ```
     #[must_use]
@@ -989,6 +1046,165 @@ pub trait YamlDocAccess<'input>: Clone {
     fn with_span(self, _span: Span) -> Self {
         self
     }
+
+    ///
+    /// Records the name of the anchor (`&name`) that was attached to this node.
+    ///
+    /// Node types that don't keep anchor names (e.g. `YamlOwned`) return `self` unchanged.
+    ///
+    #[must_use]
+    fn with_anchor(self, _anchor: Cow<'input, str>) -> Self {
+        self
+    }
+
+    ///
+    /// Returns the name of the anchor attached to this node, if the node type keeps it.
+    ///
+    fn get_anchor(&self) -> Option<&str> {
+        None
+    }
+
+    ///
+    /// Records that this node is a copy made in place of the alias `*alias`. The copy doesn't
+    /// keep the anchor name of the node it was copied from.
+    ///
+    /// Node types that don't keep anchor names (e.g. `YamlOwned`) return `self` unchanged.
+    ///
+    #[must_use]
+    fn with_alias(self, _alias: Cow<'input, str>) -> Self {
+        self
+    }
+
+    ///
+    /// Returns the name of the alias this node was copied in place of, if the node type keeps it.
+    ///
+    fn get_alias(&self) -> Option<&str> {
+        None
+    }
+
+    ///
+    /// Records how this node was written in the source, see [`Presentation`].
+    ///
+    /// Node types that don't keep presentation (e.g. `Yaml`) return `self` unchanged.
+    ///
+    #[must_use]
+    fn with_presentation(self, _presentation: Presentation) -> Self {
+        self
+    }
+
+    ///
+    /// Returns how this node was written in the source, if the node type keeps it.
+    ///
+    fn get_presentation(&self) -> Option<&Presentation> {
+        None
+    }
+
+    ///
+    /// Looks up a sequence element or mapping value, see [`YamlIndex`].
+    ///
+    /// Unlike indexing with `[]`, this returns `None` instead of panicking when the node isn't a
+    /// collection or the key/position isn't present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use yam_core::prelude::{Yaml, YamlDocAccess};
+    ///
+    /// let doc = Yaml::load_single("a: [x, y]\n1: one").unwrap();
+    /// assert_eq!(doc.get("a").and_then(|a| a.get(1)).and_then(Yaml::as_str), Some("y"));
+    /// assert_eq!(doc.get(1).and_then(Yaml::as_str), Some("one"));
+    /// assert!(doc.get("b").is_none());
+    /// ```
+    fn get<'a, I: YamlIndex>(&'a self, index: I) -> Option<&'a Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        index.index_into(self)
+    }
+
+    ///
+    /// Mutable version of [`YamlDocAccess::get`].
+    ///
+    fn get_mut<'a, I: YamlIndex>(&'a mut self, index: I) -> Option<&'a mut Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        index.index_into_mut(self)
+    }
+
+    ///
+    /// Looks up a node by an [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901) JSON pointer.
+    ///
+    /// The empty pointer selects `self`, otherwise every `/`-separated segment steps into a
+    /// sequence by position or into a mapping by key, with `~1` and `~0` standing for `/` and `~`.
+    /// A segment matches a string key, or an integer key spelled the same way.
+    ///
+    /// Returns `None` if the pointer is malformed or doesn't lead to a node.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use yam_core::prelude::{Yaml, YamlDocAccess};
+    ///
+    /// let doc = Yaml::load_single("spec:\n  containers:\n  - image: nginx\n  a/b: 1").unwrap();
+    /// let image = doc.pointer("/spec/containers/0/image");
+    /// assert_eq!(image.and_then(Yaml::as_str), Some("nginx"));
+    /// assert_eq!(doc.pointer("/spec/a~1b").and_then(Yaml::as_i64), Some(1));
+    /// ```
+    fn pointer<'a>(&'a self, pointer: &str) -> Option<&'a Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        path::pointer_segments(pointer)?.try_fold(self, |node, seg| seg.step(node))
+    }
+
+    ///
+    /// Mutable version of [`YamlDocAccess::pointer`].
+    ///
+    fn pointer_mut<'a>(&'a mut self, pointer: &str) -> Option<&'a mut Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        path::pointer_segments(pointer)?.try_fold(self, |node, seg| seg.step_mut(node))
+    }
+
+    ///
+    /// Looks up a node by a dotted path like `spec.containers.0.image`.
+    ///
+    /// Segments are matched the same way as in [`YamlDocAccess::pointer`], but there is no
+    /// escaping, so keys containing `.` can only be reached with a pointer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use yam_core::prelude::{Yaml, YamlDocAccess};
+    ///
+    /// let doc = Yaml::load_single("spec:\n  containers:\n  - image: nginx").unwrap();
+    /// let image = doc.get_path("spec.containers.0.image");
+    /// assert_eq!(image.and_then(Yaml::as_str), Some("nginx"));
+    /// ```
+    fn get_path<'a>(&'a self, path: &str) -> Option<&'a Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        path::dotted_segments(path).try_fold(self, |node, seg| seg.step(node))
+    }
+
+    ///
+    /// Mutable version of [`YamlDocAccess::get_path`].
+    ///
+    fn get_path_mut<'a>(&'a mut self, path: &str) -> Option<&'a mut Self>
+    where
+        Self::SequenceNode: SequenceLike<Self> + 'a,
+        Self::MappingNode: MappingLike<Self> + 'a,
+    {
+        path::dotted_segments(path).try_fold(self, |node, seg| seg.step_mut(node))
+    }
 }
 
 ///
@@ -1007,7 +1223,7 @@ pub trait YamlDocAccess<'input>: Clone {
 ///  - `'input`: Lifetime parameter used by the `_marker` field to link the `YamlEntry` instance
 ///    with a specific lifetime context.
 ///  - `T`: Generic type representing the key and value in the YAML entry. It must implement `Clone`.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct YamlEntry<'input, T> {
     /// Represents the key of the YAML entry. It is of type `T`.
     pub key: T,
@@ -1036,6 +1252,20 @@ impl<T: Clone> YamlEntry<'_, T> {
     }
 }
 
+/// The style a sequence or mapping is written in.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CollectionStyle {
+    /// Indentation based, like:
+    /// ```yaml
+    ///   - a
+    ///   - b
+    /// ```
+    #[default]
+    Block,
+    /// Delimited by brackets or braces, like `[a, b]` or `{a: b}`.
+    Flow,
+}
+
 /// Represents the different types of scalar values in YAML with distinct formatting styles.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
 pub enum ScalarType {
@@ -0,0 +1,372 @@
+use crate::prelude::{MappingLike, SequenceLike, YamlDocAccess};
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+///
+/// A key or position that can be looked up with [`YamlDocAccess::get`] and
+/// [`YamlDocAccess::get_mut`].
+///
+/// - `usize` selects an element of a sequence, or the value of an integer key in a mapping.
+/// - `str`/`String` select the value of a string key in a mapping.
+///
+/// Tagged collections are looked into, so `!set {a: null}` can be indexed by `"a"` like the
+/// mapping it wraps. [`YamlDocAccess::pointer`] and [`YamlDocAccess::get_path`] do the same.
+///
+pub trait YamlIndex {
+    /// Looks `self` up in `node`, returning `None` if it isn't there.
+    fn index_into<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n;
+
+    /// Looks `self` up in `node` mutably, returning `None` if it isn't there.
+    fn index_into_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n;
+}
+
+impl YamlIndex for usize {
+    fn index_into<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        let node = untagged(node);
+        if node.is_sequence() {
+            return node.as_sequence().ok()?.vec().get(*self);
+        }
+        let key = i64::try_from(*self).ok()?;
+        map_value(node, |k| k.as_i64() == Some(key))
+    }
+
+    fn index_into_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        let node = untagged_mut(node);
+        if node.is_sequence() {
+            return node.as_sequence_mut().ok()?.vec_mut().get_mut(*self);
+        }
+        let key = i64::try_from(*self).ok()?;
+        map_value_mut(node, |k| k.as_i64() == Some(key))
+    }
+}
+
+impl YamlIndex for str {
+    fn index_into<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        map_value(untagged(node), |k| k.as_str() == Some(self))
+    }
+
+    fn index_into_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        map_value_mut(untagged_mut(node), |k| k.as_str() == Some(self))
+    }
+}
+
+impl YamlIndex for String {
+    fn index_into<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        self.as_str().index_into(node)
+    }
+
+    fn index_into_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        self.as_str().index_into_mut(node)
+    }
+}
+
+impl<T: YamlIndex + ?Sized> YamlIndex for &T {
+    fn index_into<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        (**self).index_into(node)
+    }
+
+    fn index_into_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        (**self).index_into_mut(node)
+    }
+}
+
+pub(crate) fn map_value<'n, 'input, N>(
+    node: &'n N,
+    mut pred: impl FnMut(&N) -> bool,
+) -> Option<&'n N>
+where
+    N: YamlDocAccess<'input>,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    node.as_mapping()
+        .ok()?
+        .entries()
+        .iter()
+        .find(|entry| pred(&entry.key))
+        .map(|entry| &entry.value)
+}
+
+fn map_value_mut<'n, 'input, N>(node: &'n mut N, pred: impl FnMut(&N) -> bool) -> Option<&'n mut N>
+where
+    N: YamlDocAccess<'input>,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    node.as_mapping_mut().ok()?.find_value_mut(pred)
+}
+
+/// One segment of a pointer or dotted path, already unescaped.
+///
+/// Segments index sequences by position, and mappings by a string key or,
+/// failing that, an integer key with the same spelling.
+pub(crate) struct Segment<'a>(Cow<'a, str>);
+
+impl Segment<'_> {
+    /// The segment as written, after unescaping.
+    pub(crate) fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Position written the way RFC 6901 allows: no sign and no leading zeros.
+    pub(crate) fn position(&self) -> Option<usize> {
+        let s = self.0.as_ref();
+        let canonical = s == "0" || (!s.starts_with('0') && !s.is_empty());
+        if canonical && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn matches<'input, N: YamlDocAccess<'input>>(&self, key: &N) -> bool {
+        key_matches(key, &self.0)
+    }
+
+    pub(crate) fn step<'n, 'input, N>(&self, node: &'n N) -> Option<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        let node = untagged(node);
+        if node.is_sequence() {
+            return node.as_sequence().ok()?.vec().get(self.position()?);
+        }
+        map_value(node, |k| self.matches(k))
+    }
+
+    pub(crate) fn step_mut<'n, 'input, N>(&self, node: &'n mut N) -> Option<&'n mut N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        let node = untagged_mut(node);
+        if node.is_sequence() {
+            let pos = self.position()?;
+            return node.as_sequence_mut().ok()?.vec_mut().get_mut(pos);
+        }
+        map_value_mut(node, |k| self.matches(k))
+    }
+}
+
+/// The node wrapped by any tags on `node`, so that paths reach into tagged collections.
+pub(crate) fn untagged<'n, 'input, N: YamlDocAccess<'input>>(node: &'n N) -> &'n N {
+    match node.tagged_node() {
+        Some(inner) => untagged(inner),
+        None => node,
+    }
+}
+
+pub(crate) fn untagged_mut<'n, 'input, N: YamlDocAccess<'input>>(node: &'n mut N) -> &'n mut N {
+    if node.tagged_node().is_some() {
+        untagged_mut(node.tagged_node_mut().unwrap())
+    } else {
+        node
+    }
+}
+
+/// Whether `key` is the string `name`, or an integer spelled as `name`.
+pub(crate) fn key_matches<'input, N: YamlDocAccess<'input>>(key: &N, name: &str) -> bool {
+    match key.as_str() {
+        Some(s) => s == name,
+        None => key.as_i64().is_some_and(|i| name.parse::<i64>() == Ok(i)),
+    }
+}
+
+///
+/// Splits an RFC 6901 JSON pointer into unescaped segments.
+///
+/// Returns `None` if the pointer is neither empty nor starts with `/`, or if
+/// it contains a `~` that isn't followed by `0` or `1`.
+///
+pub(crate) fn pointer_segments(pointer: &str) -> Option<impl Iterator<Item = Segment<'_>>> {
+    let rest = match pointer {
+        "" => None,
+        _ => Some(pointer.strip_prefix('/')?),
+    };
+    let valid = rest.is_none_or(|rest| {
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            if c == '~' && !matches!(chars.next(), Some('0' | '1')) {
+                return false;
+            }
+        }
+        true
+    });
+    valid.then(|| {
+        rest.into_iter()
+            .flat_map(|rest| rest.split('/'))
+            .map(|raw| {
+                Segment(if raw.contains('~') {
+                    Cow::Owned(raw.replace("~1", "/").replace("~0", "~"))
+                } else {
+                    Cow::Borrowed(raw)
+                })
+            })
+    })
+}
+
+///
+/// Splits a non-empty JSON pointer into the pointer to its parent and its last segment.
+///
+/// Returns `None` for the empty (root) pointer and for pointers [`pointer_segments`] rejects.
+///
+pub(crate) fn split_pointer(pointer: &str) -> Option<(&str, Segment<'_>)> {
+    let last = pointer_segments(pointer)?.last()?;
+    let parent = &pointer[..pointer.rfind('/')?];
+    Some((parent, last))
+}
+
+/// Splits a dotted path like `spec.containers.0.image` into segments.
+pub(crate) fn dotted_segments(path: &str) -> impl Iterator<Item = Segment<'_>> {
+    path.split('.').map(|raw| Segment(Cow::Borrowed(raw)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{SpannedYaml, Yaml, YamlDocAccess, YamlLoader, YamlOwned};
+    use alloc::string::String;
+
+    const DOC: &str = "
+spec:
+  containers:
+    - name: web
+      image: nginx:1.25
+  a/b: slash
+  m~n: tilde
+  '': empty
+ports:
+  80: http
+  '443': https
+";
+
+    #[test]
+    fn test_get() {
+        let mut doc = Yaml::load_single(DOC).unwrap();
+        let spec = doc.get("spec").unwrap();
+        assert_eq!(
+            spec.get(String::from("a/b")).unwrap().as_str(),
+            Some("slash")
+        );
+        assert!(spec.get("containers").unwrap().get(0).is_some());
+        assert!(spec.get("containers").unwrap().get(1).is_none());
+        assert!(spec.get(0).is_none());
+        assert_eq!(doc["ports"].get(80).unwrap().as_str(), Some("http"));
+        assert!(doc["ports"].get(443).is_none());
+        assert!(doc.get("missing").is_none());
+
+        *doc.get_mut("ports").unwrap().get_mut(80).unwrap() = Yaml::from("www");
+        assert_eq!(doc["ports"][80].as_str(), Some("www"));
+    }
+
+    #[test]
+    fn test_pointer() {
+        let mut doc = YamlOwned::load_single(DOC).unwrap();
+        assert_eq!(doc.pointer(""), Some(&doc));
+        let image = doc.pointer("/spec/containers/0/image").unwrap();
+        assert_eq!(image.as_str(), Some("nginx:1.25"));
+        assert_eq!(doc.pointer("/spec/a~1b").unwrap().as_str(), Some("slash"));
+        assert_eq!(doc.pointer("/spec/m~0n").unwrap().as_str(), Some("tilde"));
+        assert_eq!(doc.pointer("/spec/").unwrap().as_str(), Some("empty"));
+        assert_eq!(doc.pointer("/ports/80").unwrap().as_str(), Some("http"));
+        assert_eq!(doc.pointer("/ports/443").unwrap().as_str(), Some("https"));
+
+        assert!(doc.pointer("spec").is_none());
+        assert!(doc.pointer("/spec/containers/00").is_none());
+        assert!(doc.pointer("/spec/containers/-").is_none());
+        assert!(doc.pointer("/spec/m~2n").is_none());
+        assert!(doc.pointer("/spec/containers/0/image/x").is_none());
+
+        let image = doc.pointer_mut("/spec/containers/0/image").unwrap();
+        *image = YamlOwned::from("nginx:1.27");
+        assert_eq!(
+            doc["spec"]["containers"][0]["image"].as_str(),
+            Some("nginx:1.27")
+        );
+    }
+
+    #[test]
+    fn test_get_path() {
+        let mut doc = YamlLoader::<SpannedYaml>::load_from(DOC).unwrap().remove(0);
+        let name = doc.get_path("spec.containers.0.name").unwrap();
+        assert_eq!(name.as_str(), Some("web"));
+        assert_eq!(name.span.start.line, 4);
+        assert!(doc.get_path("spec.containers.0.missing").is_none());
+        assert!(doc.get_path("ports.80").is_some());
+
+        let name = doc.get_path_mut("spec.containers.0.name").unwrap();
+        name.as_str_mut().unwrap().make_ascii_uppercase();
+        assert_eq!(
+            doc.pointer("/spec/containers/0/name").unwrap().as_str(),
+            Some("WEB")
+        );
+    }
+
+    #[test]
+    fn test_tagged_collections() {
+        let input = "t: !custom {a: [x, !other [y]]}\ns: !custom [1, 2]\nv: !custom scalar";
+        let mut doc = Yaml::load_single(input).unwrap();
+        let a = doc.get("t").unwrap().get("a").unwrap();
+        assert_eq!(doc.pointer("/t/a"), Some(a));
+        assert_eq!(doc.get_path("t.a"), Some(a));
+        assert_eq!(a.get(1).unwrap().get(0).unwrap().as_str(), Some("y"));
+        assert_eq!(doc.pointer("/t/a/1/0").unwrap().as_str(), Some("y"));
+        assert_eq!(doc.get("s").unwrap().get(1).unwrap().as_i64(), Some(2));
+        assert!(doc.get("v").unwrap().get(0).is_none());
+        assert!(doc.pointer("/v/0").is_none());
+
+        *doc.get_mut("s").unwrap().get_mut(0).unwrap() = Yaml::from(10);
+        *doc.pointer_mut("/t/a/0").unwrap() = Yaml::from("z");
+        assert_eq!(doc.get_path("s.0").unwrap().as_i64(), Some(10));
+        assert_eq!(doc.get_path("t.a.0").unwrap().as_str(), Some("z"));
+        assert_eq!(doc["s"].get_tag().unwrap().suffix, "custom");
+    }
+}
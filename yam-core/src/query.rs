@@ -0,0 +1,824 @@
+use crate::prelude::path::{key_matches, map_value, untagged};
+use crate::prelude::{MappingLike, SequenceLike, YamlDocAccess};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+///
+/// A compiled JSONPath-like query, evaluated against any [`YamlDocAccess`] tree.
+///
+/// Supported syntax, following [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535):
+/// - `$` is the root node, `@` the current node inside a filter.
+/// - `.name`, `['name']` and `["name"]` select a mapping value. A name also matches an integer
+///   key spelled the same way, so `$.ports.80` works on `ports: {80: http}`.
+/// - `.*` and `[*]` select every element of a sequence or every value of a mapping.
+/// - `[1]`, `[-1]` and `[start:end:step]` select sequence elements by position.
+/// - `[a, b]` selects the union of several selectors.
+/// - `..` in front of any of the above applies it to the node and all of its descendants.
+/// - `[?expr]` or `[?(expr)]` keeps the children for which `expr` holds. Expressions compare
+///   literals (`'str'`, `"str"`, numbers, `true`, `false`, `null`) and single-node queries
+///   (`@.protocol`, `$.limits.max`) with `==`, `!=`, `<`, `<=`, `>`, `>=`, test whether a query
+///   selects anything (`@.name`), and combine with `&&`, `||`, `!` and parentheses.
+///
+/// Selectors look into tagged collections, like [`YamlDocAccess::pointer`] does, so `$.t.a`
+/// finds `1` in `t: !custom {a: 1}`.
+///
+/// Results are references into the tree, so when querying a
+/// [`SpannedYaml`](crate::prelude::SpannedYaml) every match carries its source span.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::{Marker, SpannedYaml, YamlDocAccess, YamlLoader, YamlPath};
+///
+/// let input = "ports:\n- port: 53\n  protocol: UDP\n- port: 80\n  protocol: TCP\n";
+/// let doc = &YamlLoader::<SpannedYaml>::load_from(input).unwrap()[0];
+///
+/// let path = YamlPath::compile("$..ports[?(@.protocol == 'UDP')].port").unwrap();
+/// let found = path.query(doc);
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].as_i64(), Some(53));
+/// assert_eq!(found[0].span.start, Marker::new(15, 9, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlPath {
+    segments: Vec<Segment>,
+}
+
+///
+/// Error returned when a [`YamlPath`] expression can't be compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryError {
+    /// Byte offset in the expression where the problem was found.
+    pub pos: usize,
+    /// What was expected at that position.
+    pub info: &'static str,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid query at byte {}: {}", self.pos, self.info)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Expr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CmpOp, Operand),
+    Exists(SubQuery),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Literal),
+    Query(SubQuery),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SubQuery {
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl YamlPath {
+    ///
+    /// Compiles a query expression, see [`YamlPath`] for the syntax.
+    ///
+    /// # Errors
+    /// Returns a [`QueryError`] pointing at the first character that doesn't fit the syntax.
+    pub fn compile(expr: &str) -> Result<Self, QueryError> {
+        let mut parser = QueryParser {
+            input: expr,
+            pos: 0,
+        };
+        parser.skip_ws();
+        if !parser.eat("$") {
+            return parser.err("expected `$` at the start of the query");
+        }
+        let segments = parser.segments()?;
+        parser.skip_ws();
+        if parser.pos < expr.len() {
+            return parser.err("unexpected character");
+        }
+        Ok(YamlPath { segments })
+    }
+
+    ///
+    /// Returns every node of `root` selected by the query, in document order.
+    ///
+    #[must_use]
+    pub fn query<'n, 'input, N>(&self, root: &'n N) -> Vec<&'n N>
+    where
+        N: YamlDocAccess<'input>,
+        N::SequenceNode: SequenceLike<N> + 'n,
+        N::MappingNode: MappingLike<N> + 'n,
+    {
+        Eval { root }.segments(&self.segments, root)
+    }
+}
+
+impl FromStr for YamlPath {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        YamlPath::compile(s)
+    }
+}
+
+struct QueryParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl QueryParser<'_> {
+    fn err<T>(&self, info: &'static str) -> Result<T, QueryError> {
+        Err(QueryError {
+            pos: self.pos,
+            info,
+        })
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let found = self.rest().starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, s: &str, info: &'static str) -> Result<(), QueryError> {
+        if self.eat(s) { Ok(()) } else { self.err(info) }
+    }
+
+    fn skip_ws(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|c| matches!(c, ' ' | '\t' | '\n' | '\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, QueryError> {
+        let mut segments = Vec::new();
+        loop {
+            if self.eat("..") {
+                let selectors = match self.peek() {
+                    Some('[') => self.bracket()?,
+                    Some('*') => {
+                        self.pos += 1;
+                        vec![Selector::Wildcard]
+                    }
+                    _ => vec![Selector::Name(self.name()?)],
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.eat(".") {
+                let selector = if self.eat("*") {
+                    Selector::Wildcard
+                } else {
+                    Selector::Name(self.name()?)
+                };
+                segments.push(Segment::Child(vec![selector]));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '-') || !c.is_ascii())
+        {
+            self.bump();
+        }
+        if start == self.pos {
+            return self.err("expected a name or `*`");
+        }
+        Ok(String::from(&self.input[start..self.pos]))
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Selector>, QueryError> {
+        self.expect("[", "expected `[`")?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_ws();
+            selectors.push(self.selector()?);
+            self.skip_ws();
+            if self.eat("]") {
+                return Ok(selectors);
+            }
+            self.expect(",", "expected `,` or `]`")?;
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, QueryError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                Ok(Selector::Filter(self.expr()?))
+            }
+            Some('-' | ':' | '0'..='9') => {
+                let start = self.opt_int()?;
+                self.skip_ws();
+                if !self.eat(":") {
+                    return start
+                        .map_or_else(|| self.err("expected an index"), |i| Ok(Selector::Index(i)));
+                }
+                self.skip_ws();
+                let end = self.opt_int()?;
+                self.skip_ws();
+                let step = if self.eat(":") {
+                    self.skip_ws();
+                    self.opt_int()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice { start, end, step })
+            }
+            _ => self.err("expected a selector"),
+        }
+    }
+
+    fn opt_int(&mut self) -> Result<Option<i64>, QueryError> {
+        let start = self.pos;
+        self.eat("-");
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        match &self.input[start..self.pos] {
+            "" => Ok(None),
+            digits => match digits.parse() {
+                Ok(i) => Ok(Some(i)),
+                Err(_) => {
+                    self.pos = start;
+                    self.err("expected an integer")
+                }
+            },
+        }
+    }
+
+    fn string(&mut self) -> Result<String, QueryError> {
+        let Some(quote) = self.bump() else {
+            return self.err("expected a string");
+        };
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return self.err("unterminated string"),
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex = self.rest().get(..4).unwrap_or_default();
+                            match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                                Some(c) if hex.len() == 4 => {
+                                    self.pos += 4;
+                                    c
+                                }
+                                _ => return self.err("invalid unicode escape"),
+                            }
+                        }
+                        Some(c @ ('\\' | '/' | '\'' | '"')) => c,
+                        _ => return self.err("invalid escape"),
+                    };
+                    out.push(c);
+                }
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.and()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("||") {
+                return Ok(left);
+            }
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("&&") {
+                return Ok(left);
+            }
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        self.skip_ws();
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.expr()?;
+            self.skip_ws();
+            self.expect(")", "expected `)`")?;
+            return Ok(expr);
+        }
+        let left = self.operand()?;
+        self.skip_ws();
+        match self.cmp_op() {
+            Some(op) => {
+                self.skip_ws();
+                Ok(Expr::Compare(left, op, self.operand()?))
+            }
+            None => match left {
+                Operand::Query(query) => Ok(Expr::Exists(query)),
+                Operand::Literal(_) => self.err("expected a comparison operator"),
+            },
+        }
+    }
+
+    fn cmp_op(&mut self) -> Option<CmpOp> {
+        let ops = [
+            ("==", CmpOp::Eq),
+            ("!=", CmpOp::Ne),
+            ("<=", CmpOp::Le),
+            (">=", CmpOp::Ge),
+            ("<", CmpOp::Lt),
+            (">", CmpOp::Gt),
+        ];
+        ops.into_iter().find(|(s, _)| self.eat(s)).map(|(_, op)| op)
+    }
+
+    fn operand(&mut self) -> Result<Operand, QueryError> {
+        let relative = match self.peek() {
+            Some('@') => true,
+            Some('$') => false,
+            Some('\'' | '"') => return Ok(Operand::Literal(Literal::Str(self.string()?))),
+            Some('-' | '0'..='9') => return self.number().map(Operand::Literal),
+            _ => {
+                let literals = [
+                    ("true", Literal::Bool(true)),
+                    ("false", Literal::Bool(false)),
+                    ("null", Literal::Null),
+                ];
+                return match literals.into_iter().find(|(s, _)| self.eat(s)) {
+                    Some((_, lit)) => Ok(Operand::Literal(lit)),
+                    None => self.err("expected `@`, `$` or a literal"),
+                };
+            }
+        };
+        self.pos += 1;
+        let segments = self.segments()?;
+        Ok(Operand::Query(SubQuery { relative, segments }))
+    }
+
+    fn number(&mut self) -> Result<Literal, QueryError> {
+        let start = self.pos;
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while p.peek().is_some_and(|c| c.is_ascii_digit()) {
+                p.pos += 1;
+            }
+            p.pos > from
+        };
+        self.eat("-");
+        let mut valid = digits(self);
+        let mut float = false;
+        if self.eat(".") {
+            float = true;
+            valid &= digits(self);
+        }
+        if self.eat("e") || self.eat("E") {
+            float = true;
+            let _ = self.eat("+") || self.eat("-");
+            valid &= digits(self);
+        }
+        let text = &self.input[start..self.pos];
+        let lit = if !valid {
+            None
+        } else if float {
+            text.parse().ok().map(Literal::Float)
+        } else {
+            text.parse().ok().map(Literal::Int)
+        };
+        lit.map_or_else(
+            || {
+                self.pos = start;
+                self.err("expected a number")
+            },
+            Ok,
+        )
+    }
+}
+
+/// A value that can be compared inside a filter.
+#[derive(Clone, Copy)]
+enum Value<'v> {
+    /// A query that didn't select exactly one node.
+    Nothing,
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(&'v str),
+    /// Collections and other nodes that only compare unequal.
+    Other,
+}
+
+impl Value<'_> {
+    fn of<'input, N: YamlDocAccess<'input>>(node: &N) -> Value<'_> {
+        if node.is_collection() {
+            Value::Other
+        } else if node.is_null() {
+            Value::Null
+        } else if let Some(b) = node.as_bool() {
+            Value::Bool(b)
+        } else if let Some(i) = node.as_i64() {
+            Value::Int(i)
+        } else if let Some(f) = node.as_f64() {
+            Value::Float(f)
+        } else if let Some(s) = node.as_str() {
+            Value::Str(s)
+        } else {
+            Value::Other
+        }
+    }
+
+    fn number(self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(i as f64),
+            Value::Float(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    fn eq(self, other: Value<'_>) -> bool {
+        match (self, other) {
+            (Value::Nothing, Value::Nothing) | (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (a, b) => a.number().zip(b.number()).is_some_and(|(a, b)| a == b),
+        }
+    }
+
+    fn lt(self, other: Value<'_>) -> bool {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a < b,
+            (Value::Str(a), Value::Str(b)) => a < b,
+            (a, b) => a.number().zip(b.number()).is_some_and(|(a, b)| a < b),
+        }
+    }
+
+    fn compare(self, op: CmpOp, other: Value<'_>) -> bool {
+        match op {
+            CmpOp::Eq => self.eq(other),
+            CmpOp::Ne => !self.eq(other),
+            CmpOp::Lt => self.lt(other),
+            CmpOp::Le => self.lt(other) || self.eq(other),
+            CmpOp::Gt => other.lt(self),
+            CmpOp::Ge => other.lt(self) || self.eq(other),
+        }
+    }
+}
+
+struct Eval<'n, N> {
+    root: &'n N,
+}
+
+impl<'n, 'input, N> Eval<'n, N>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    fn segments(&self, segments: &[Segment], start: &'n N) -> Vec<&'n N> {
+        segments.iter().fold(vec![start], |nodes, segment| {
+            let mut out = Vec::new();
+            for node in nodes {
+                match segment {
+                    Segment::Child(selectors) => self.select(selectors, node, &mut out),
+                    Segment::Descendant(selectors) => {
+                        let mut stack = vec![node];
+                        while let Some(desc) = stack.pop() {
+                            self.select(selectors, desc, &mut out);
+                            let children = Self::children(desc);
+                            stack.extend(children.into_iter().rev());
+                        }
+                    }
+                }
+            }
+            out
+        })
+    }
+
+    fn children(node: &'n N) -> Vec<&'n N> {
+        let node = untagged(node);
+        if let Ok(seq) = node.as_sequence() {
+            seq.vec().iter().collect()
+        } else if let Ok(map) = node.as_mapping() {
+            map.entries().iter().map(|entry| &entry.value).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn select(&self, selectors: &[Selector], node: &'n N, out: &mut Vec<&'n N>) {
+        let node = untagged(node);
+        for selector in selectors {
+            match selector {
+                Selector::Name(name) => out.extend(map_value(node, |k| key_matches(k, name))),
+                Selector::Wildcard => out.extend(Self::children(node)),
+                Selector::Index(i) => {
+                    if let Ok(seq) = node.as_sequence() {
+                        let seq = seq.vec();
+                        let len = i64::try_from(seq.len()).unwrap_or(i64::MAX);
+                        let i = if *i < 0 { len + i } else { *i };
+                        out.extend(usize::try_from(i).ok().and_then(|i| seq.get(i)));
+                    }
+                }
+                Selector::Slice { start, end, step } => {
+                    if let Ok(seq) = node.as_sequence() {
+                        slice(seq.vec(), *start, *end, step.unwrap_or(1), out);
+                    }
+                }
+                Selector::Filter(expr) => {
+                    let children = Self::children(node);
+                    out.extend(children.into_iter().filter(|child| self.test(expr, child)));
+                }
+            }
+        }
+    }
+
+    fn test(&self, expr: &Expr, current: &'n N) -> bool {
+        match expr {
+            Expr::Or(a, b) => self.test(a, current) || self.test(b, current),
+            Expr::And(a, b) => self.test(a, current) && self.test(b, current),
+            Expr::Not(a) => !self.test(a, current),
+            Expr::Exists(query) => !self.sub_query(query, current).is_empty(),
+            Expr::Compare(left, op, right) => {
+                let left_nodes;
+                let right_nodes;
+                let left = match left {
+                    Operand::Literal(lit) => literal_value(lit),
+                    Operand::Query(query) => {
+                        left_nodes = self.sub_query(query, current);
+                        single_value(&left_nodes)
+                    }
+                };
+                let right = match right {
+                    Operand::Literal(lit) => literal_value(lit),
+                    Operand::Query(query) => {
+                        right_nodes = self.sub_query(query, current);
+                        single_value(&right_nodes)
+                    }
+                };
+                left.compare(*op, right)
+            }
+        }
+    }
+
+    fn sub_query(&self, query: &SubQuery, current: &'n N) -> Vec<&'n N> {
+        let start = if query.relative { current } else { self.root };
+        self.segments(&query.segments, start)
+    }
+}
+
+fn single_value<'v, 'input, N: YamlDocAccess<'input>>(nodes: &[&'v N]) -> Value<'v> {
+    match nodes {
+        [node] => Value::of(*node),
+        _ => Value::Nothing,
+    }
+}
+
+fn literal_value(lit: &Literal) -> Value<'_> {
+    match lit {
+        Literal::Null => Value::Null,
+        Literal::Bool(b) => Value::Bool(*b),
+        Literal::Int(i) => Value::Int(*i),
+        Literal::Float(f) => Value::Float(*f),
+        Literal::Str(s) => Value::Str(s),
+    }
+}
+
+/// Pushes the elements selected by an RFC 9535 slice.
+fn slice<'n, T>(
+    seq: &'n [T],
+    start: Option<i64>,
+    end: Option<i64>,
+    step: i64,
+    out: &mut Vec<&'n T>,
+) {
+    let len = i64::try_from(seq.len()).unwrap_or(i64::MAX);
+    let bound = |i: i64| if i < 0 { (len + i).max(-1) } else { i.min(len) };
+    let mut push = |i: i64| out.extend(usize::try_from(i).ok().and_then(|i| seq.get(i)));
+    if step > 0 {
+        let mut i = start.map_or(0, bound).max(0);
+        let end = end.map_or(len, bound);
+        while i < end {
+            push(i);
+            let Some(next) = i.checked_add(step) else {
+                break;
+            };
+            i = next;
+        }
+    } else if step < 0 {
+        let mut i = start.map_or(len - 1, bound).min(len - 1);
+        let end = end.map_or(-1, bound);
+        while i > end {
+            push(i);
+            let Some(next) = i.checked_add(step) else {
+                break;
+            };
+            i = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{Marker, SpannedYaml, Yaml, YamlDocAccess, YamlLoader, YamlPath};
+    use crate::query::QueryError;
+    use alloc::vec::Vec;
+
+    const DOC: &str = "
+spec:
+  containers:
+    - name: web
+      image: nginx:1.25
+      ports:
+        - {port: 80, protocol: TCP}
+        - {port: 53, protocol: UDP}
+    - name: sidecar
+      image: envoy:1.30
+      ports:
+        - {port: 9901, protocol: TCP}
+  limits: {max: 100}
+";
+
+    fn strs<'a>(nodes: &[&'a Yaml<'a>]) -> Vec<&'a str> {
+        nodes.iter().filter_map(|n| n.as_str()).collect()
+    }
+
+    fn query<'a>(doc: &'a Yaml<'a>, expr: &str) -> Vec<&'a Yaml<'a>> {
+        YamlPath::compile(expr).unwrap().query(doc)
+    }
+
+    #[test]
+    fn test_query_selectors() {
+        let doc = Yaml::load_single(DOC).unwrap();
+        let images = query(&doc, "$.spec.containers[*].image");
+        assert_eq!(strs(&images), ["nginx:1.25", "envoy:1.30"]);
+        assert_eq!(strs(&query(&doc, "$..name")), ["web", "sidecar"]);
+        assert_eq!(
+            strs(&query(&doc, "$['spec'].containers[-1].name")),
+            ["sidecar"]
+        );
+        assert_eq!(
+            strs(&query(&doc, "$.spec.containers[1, 0].name")),
+            ["sidecar", "web"]
+        );
+        assert_eq!(
+            strs(&query(&doc, "$.spec.containers[::-1].name")),
+            ["sidecar", "web"]
+        );
+        assert_eq!(
+            strs(&query(&doc, "$.spec.containers[1:].name")),
+            ["sidecar"]
+        );
+        let seq = Yaml::load_single("[a, b, c]").unwrap();
+        assert_eq!(strs(&query(&seq, "$[1::9223372036854775807]")), ["b"]);
+        assert_eq!(strs(&query(&seq, "$[::-9223372036854775808]")), ["c"]);
+        assert_eq!(query(&doc, "$..port").len(), 3);
+        assert_eq!(query(&doc, "$").len(), 1);
+        assert!(query(&doc, "$.spec.missing[*]").is_empty());
+        assert!(query(&doc, "$.spec.containers.name").is_empty());
+
+        let tagged = Yaml::load_single("t: !custom {a: 1, b: !list [x, y]}").unwrap();
+        assert_eq!(query(&tagged, "$.t.a"), [tagged.pointer("/t/a").unwrap()]);
+        assert_eq!(query(&tagged, "$.t.*").len(), 2);
+        assert_eq!(strs(&query(&tagged, "$..b[0]")), ["x"]);
+        assert_eq!(strs(&query(&tagged, "$.t.b[-1:]")), ["y"]);
+        assert_eq!(query(&tagged, "$..*").len(), 5);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let doc = Yaml::load_single(DOC).unwrap();
+        let udp = query(&doc, "$..ports[?(@.protocol == 'UDP')].port");
+        assert_eq!(
+            udp.iter().map(|n| n.as_i64()).collect::<Vec<_>>(),
+            [Some(53)]
+        );
+        let names = query(&doc, r#"$.spec.containers[?@.ports[?@.port >= 9000]].name"#);
+        assert_eq!(strs(&names), ["sidecar"]);
+        let names = query(
+            &doc,
+            "$.spec.containers[?!(@.name == 'web') || @.image == \"x\"].name",
+        );
+        assert_eq!(strs(&names), ["sidecar"]);
+        let ports = query(
+            &doc,
+            "$..ports[?@.port < $.spec.limits.max && @.protocol != 'UDP'].port",
+        );
+        assert_eq!(
+            ports.iter().map(|n| n.as_i64()).collect::<Vec<_>>(),
+            [Some(80)]
+        );
+        assert_eq!(query(&doc, "$.spec.containers[?@.ports]").len(), 2);
+        assert!(query(&doc, "$.spec.containers[?@.missing == null]").is_empty());
+    }
+
+    #[test]
+    fn test_query_spans_and_errors() {
+        let docs = YamlLoader::<SpannedYaml>::load_from("a:\n  - b: 1\n  - b: 2\n").unwrap();
+        let found = YamlPath::compile("$.a[?@.b > 1].b")
+            .unwrap()
+            .query(&docs[0]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].span.start, Marker::new(19, 8, 3));
+
+        let err = |expr: &str| YamlPath::compile(expr).unwrap_err();
+        assert_eq!(err("a.b").pos, 0);
+        assert_eq!(
+            err("$.a[?@.b = 1]"),
+            QueryError {
+                pos: 9,
+                info: "expected `,` or `]`"
+            }
+        );
+        assert_eq!(err("$.a['b").info, "unterminated string");
+        assert_eq!(err("$.a[?1]").info, "expected a comparison operator");
+        assert_eq!(err("$.a.").info, "expected a name or `*`");
+    }
+}
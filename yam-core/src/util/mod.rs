@@ -1,6 +1,8 @@
+mod simd;
 mod u8x16;
 mod u8x32;
 
+pub(crate) use simd::NibbleSet;
 pub(crate) use u8x16::U8X16;
 pub(crate) use u8x32::U8X32;
 
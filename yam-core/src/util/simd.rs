@@ -0,0 +1,326 @@
+use crate::util::{BitOps, U8X16};
+
+///
+/// A set of ASCII bytes, matched a vector at a time with the nibble lookup described in
+/// `doc/nibble.md`.
+///
+/// Every distinct high nibble of the set gets its own bit. `high` maps a high nibble to its bit
+/// and `low` maps a low nibble to the bits of the high nibbles it is paired with, so
+/// `low[b & 0xF] & high[b >> 4]` is non-zero exactly for the bytes of the set. Sets may use at
+/// most 8 distinct high nibbles.
+///
+/// On `x86`/`x86_64` the lookup uses AVX2 when the CPU supports it, detected at runtime, and
+/// falls back to SSE2 comparisons, as SSE2 has no byte shuffle. On `aarch64` it uses NEON.
+/// Other targets use the portable [`U8X16`].
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct NibbleSet {
+    low: [u8; 16],
+    high: [u8; 16],
+    bytes: &'static [u8],
+}
+
+impl NibbleSet {
+    pub(crate) const fn new(bytes: &'static [u8]) -> Self {
+        let mut low = [0; 16];
+        let mut high = [0; 16];
+        let mut next_bit = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            assert!(byte < 0x80, "only ASCII bytes can be matched");
+            let hi = (byte >> 4) as usize;
+            if high[hi] == 0 {
+                assert!(next_bit < 8, "too many distinct high nibbles");
+                high[hi] = 1 << next_bit;
+                next_bit += 1;
+            }
+            low[(byte & 0xF) as usize] |= high[hi];
+            i += 1;
+        }
+        NibbleSet { low, high, bytes }
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, byte: u8) -> bool {
+        self.low[usize::from(byte & 0xF)] & self.high[usize::from(byte >> 4)] != 0
+    }
+
+    /// Returns the position of the first byte of `input` in the set, or `input.len()` if there
+    /// is none.
+    #[inline]
+    pub(crate) fn position(&self, input: &[u8]) -> usize {
+        imp::position(self, input, true)
+    }
+
+    /// Returns the position of the first byte of `input` not in the set, or `input.len()` if
+    /// there is none.
+    #[inline]
+    pub(crate) fn position_not(&self, input: &[u8]) -> usize {
+        imp::position(self, input, false)
+    }
+
+    /// Scans the rest of `input`, after the vectors processed by the target specific code.
+    fn position_scalar(&self, input: &[u8], offset: usize, members: bool) -> usize {
+        offset
+            + input[offset..]
+                .iter()
+                .position(|&byte| self.contains(byte) == members)
+                .unwrap_or(input.len() - offset)
+    }
+
+    // Only tests use it on targets with a vectorized implementation.
+    #[cfg_attr(
+        any(
+            all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                target_feature = "sse2"
+            ),
+            all(target_arch = "aarch64", target_feature = "neon")
+        ),
+        allow(dead_code)
+    )]
+    fn position_portable(&self, input: &[u8], members: bool) -> usize {
+        let low = U8X16::from_array(self.low);
+        let high = U8X16::from_array(self.high);
+        let mut offset = 0;
+        while let Some(chunk) = input[offset..].first_chunk::<16>() {
+            let v = U8X16::from_array(*chunk);
+            let class = low.swizzle(v & 0xF) & high.swizzle(v >> 4);
+            let outside = class.comp(0).to_bitmask();
+            let found = if members { !outside } else { outside } & 0xFFFF;
+            if found != 0 {
+                return offset + found.trailing_zeros() as usize;
+            }
+            offset += 16;
+        }
+        self.position_scalar(input, offset, members)
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod imp {
+    use super::NibbleSet;
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 2;
+    static HAS_AVX2: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    #[inline]
+    pub(super) fn position(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        if has_avx2() {
+            // SAFETY: The CPU supports AVX2.
+            unsafe { position_avx2(set, input, members) }
+        } else {
+            position_fallback(set, input, members)
+        }
+    }
+
+    #[inline]
+    fn has_avx2() -> bool {
+        match HAS_AVX2.load(Ordering::Relaxed) {
+            UNKNOWN => {
+                let detected = detect_avx2();
+                HAS_AVX2.store(u8::from(detected), Ordering::Relaxed);
+                detected
+            }
+            known => known == 1,
+        }
+    }
+
+    /// `is_x86_feature_detected!` needs `std`, so ask the CPU and the OS directly.
+    fn detect_avx2() -> bool {
+        #[cfg(target_arch = "x86")]
+        if !has_cpuid() {
+            return false;
+        }
+        // SAFETY: `cpuid` is available, and `xgetbv` is only used once the OS reports supporting
+        // it with the OSXSAVE bit.
+        unsafe {
+            if __cpuid(0).eax < 7 {
+                return false;
+            }
+            let features = __cpuid(1).ecx;
+            let osxsave_and_avx = (1 << 27) | (1 << 28);
+            if features & osxsave_and_avx != osxsave_and_avx {
+                return false;
+            }
+            // The OS must save the SSE and AVX registers on context switches.
+            if _xgetbv(0) & 0b110 != 0b110 {
+                return false;
+            }
+            __cpuid_count(7, 0).ebx & (1 << 5) != 0
+        }
+    }
+
+    #[cfg(target_feature = "sse2")]
+    fn position_fallback(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        // SAFETY: SSE2 is enabled at compile time.
+        unsafe { position_sse2(set, input, members) }
+    }
+
+    #[cfg(not(target_feature = "sse2"))]
+    fn position_fallback(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        set.position_portable(input, members)
+    }
+
+    #[target_feature(enable = "avx2")]
+    #[allow(clippy::cast_sign_loss)]
+    unsafe fn position_avx2(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        let mut offset = 0;
+        // SAFETY: Loads stay within `input` and the tables.
+        unsafe {
+            // `vpshufb` looks up each 128-bit lane separately, so both lanes get the tables.
+            let low = _mm256_broadcastsi128_si256(_mm_loadu_si128(set.low.as_ptr().cast()));
+            let high = _mm256_broadcastsi128_si256(_mm_loadu_si128(set.high.as_ptr().cast()));
+            let nibble = _mm256_set1_epi8(0xF);
+            while offset + 32 <= input.len() {
+                let v = _mm256_loadu_si256(input.as_ptr().add(offset).cast());
+                let lo = _mm256_shuffle_epi8(low, _mm256_and_si256(v, nibble));
+                let hi =
+                    _mm256_shuffle_epi8(high, _mm256_and_si256(_mm256_srli_epi16::<4>(v), nibble));
+                let class = _mm256_and_si256(lo, hi);
+                let empty = _mm256_cmpeq_epi8(class, _mm256_setzero_si256());
+                let outside = _mm256_movemask_epi8(empty) as u32;
+                let found = if members { !outside } else { outside };
+                if found != 0 {
+                    return offset + found.trailing_zeros() as usize;
+                }
+                offset += 32;
+            }
+        }
+        set.position_scalar(input, offset, members)
+    }
+
+    #[cfg(target_feature = "sse2")]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+    pub(super) unsafe fn position_sse2(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        let mut offset = 0;
+        // SAFETY: Loads stay within `input`.
+        unsafe {
+            while offset + 16 <= input.len() {
+                let v = _mm_loadu_si128(input.as_ptr().add(offset).cast());
+                let mut matched = _mm_setzero_si128();
+                for &byte in set.bytes {
+                    matched = _mm_or_si128(matched, _mm_cmpeq_epi8(v, _mm_set1_epi8(byte as i8)));
+                }
+                let inside = _mm_movemask_epi8(matched) as u32;
+                let found = if members { inside } else { !inside & 0xFFFF };
+                if found != 0 {
+                    return offset + found.trailing_zeros() as usize;
+                }
+                offset += 16;
+            }
+        }
+        set.position_scalar(input, offset, members)
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod imp {
+    use super::NibbleSet;
+    use core::arch::aarch64::*;
+
+    #[inline]
+    pub(super) fn position(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        // SAFETY: NEON is enabled at compile time.
+        unsafe { position_neon(set, input, members) }
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn position_neon(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        let mut offset = 0;
+        // SAFETY: Loads stay within `input` and the tables.
+        unsafe {
+            let low = vld1q_u8(set.low.as_ptr());
+            let high = vld1q_u8(set.high.as_ptr());
+            let nibble = vdupq_n_u8(0xF);
+            while offset + 16 <= input.len() {
+                let v = vld1q_u8(input.as_ptr().add(offset));
+                let lo = vqtbl1q_u8(low, vandq_u8(v, nibble));
+                let hi = vqtbl1q_u8(high, vshrq_n_u8::<4>(v));
+                let class = vandq_u8(lo, hi);
+                // NEON has no `movemask`: narrow each byte of the comparison to a nibble.
+                let inside = vtstq_u8(class, class);
+                let matched = if members { inside } else { vmvnq_u8(inside) };
+                let narrowed = vshrn_n_u16::<4>(vreinterpretq_u16_u8(matched));
+                let found = vget_lane_u64::<0>(vreinterpret_u64_u8(narrowed));
+                if found != 0 {
+                    return offset + (found.trailing_zeros() / 4) as usize;
+                }
+                offset += 16;
+            }
+        }
+        set.position_scalar(input, offset, members)
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+mod imp {
+    use super::NibbleSet;
+
+    #[inline]
+    pub(super) fn position(set: &NibbleSet, input: &[u8], members: bool) -> usize {
+        set.position_portable(input, members)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::NibbleSet;
+    use alloc::vec::Vec;
+
+    const SET: NibbleSet = NibbleSet::new(b"\0\t\n\r :,[]{}");
+
+    fn assert_position(input: &[u8], members: bool, expected: usize) {
+        let position = if members {
+            SET.position(input)
+        } else {
+            SET.position_not(input)
+        };
+        assert_eq!(position, expected);
+        assert_eq!(SET.position_portable(input, members), expected);
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ))]
+        assert_eq!(
+            unsafe { super::imp::position_sse2(&SET, input, members) },
+            expected
+        );
+    }
+
+    #[test]
+    fn test_nibble_set() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(SET.contains(byte), SET.bytes.contains(&byte), "{byte:#x}");
+        }
+
+        let mut input: Vec<u8> = "plain-scalar_ünïcödé!".repeat(8).into_bytes();
+        let mut blanks: Vec<u8> = b" \t".repeat(40);
+        assert_position(&input, true, input.len());
+        assert_position(&blanks, false, blanks.len());
+        // Every offset and every byte of the set, to cover vectors and the tail.
+        for pos in 0..input.len() {
+            for &byte in SET.bytes {
+                let old = core::mem::replace(&mut input[pos], byte);
+                assert_position(&input, true, pos);
+                input[pos] = old;
+            }
+        }
+        for pos in 0..blanks.len() {
+            for byte in [b'a', 0xC3, b'#'] {
+                let old = core::mem::replace(&mut blanks[pos], byte);
+                assert_position(&blanks, false, pos);
+                blanks[pos] = old;
+            }
+        }
+    }
+}
@@ -99,22 +99,6 @@ impl BitOps for U8X16 {
             | bitmask!(self, 13 => u32)
             | bitmask!(self, 14 => u32)
             | bitmask!(self, 15 => u32)
-            | bitmask!(self, 16 => u32)
-            | bitmask!(self, 17 => u32)
-            | bitmask!(self, 18 => u32)
-            | bitmask!(self, 19 => u32)
-            | bitmask!(self, 20 => u32)
-            | bitmask!(self, 21 => u32)
-            | bitmask!(self, 22 => u32)
-            | bitmask!(self, 23 => u32)
-            | bitmask!(self, 24 => u32)
-            | bitmask!(self, 25 => u32)
-            | bitmask!(self, 26 => u32)
-            | bitmask!(self, 27 => u32)
-            | bitmask!(self, 28 => u32)
-            | bitmask!(self, 29 => u32)
-            | bitmask!(self, 30 => u32)
-            | bitmask!(self, 31 => u32)
     }
 
     fn swizzle(self, other: Self) -> Self {
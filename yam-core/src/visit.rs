@@ -0,0 +1,703 @@
+use crate::diff::write_inline;
+use crate::prelude::{MappingLike, NodeType, SequenceLike, YamlDocAccess};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::{Display, Formatter, Write};
+
+/// One step from a node to one of its children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathSegment {
+    /// The value of a mapping entry, by its key. Keys that are neither strings nor integers are
+    /// written in flow style, e.g. `[a, b]`.
+    Key(String),
+    /// An element of a sequence, by position.
+    Index(usize),
+}
+
+///
+/// Location of a node below the root, as the keys and positions leading to it.
+///
+/// Displays as a JSON pointer, so for string and integer keys it can be looked up again with
+/// [`YamlDocAccess::pointer`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodePath(Vec<PathSegment>);
+
+impl NodePath {
+    /// Creates the path of the root node.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The segments from the root down to the node.
+    #[must_use]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// Whether this is the path of the root node.
+    #[must_use]
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Appends a segment.
+    pub fn push(&mut self, segment: PathSegment) {
+        self.0.push(segment);
+    }
+
+    /// Removes and returns the last segment.
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.0.pop()
+    }
+
+    fn child(&self, segment: PathSegment) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+}
+
+impl Display for NodePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for segment in &self.0 {
+            match segment {
+                PathSegment::Key(key) => {
+                    f.write_char('/')?;
+                    for c in key.chars() {
+                        match c {
+                            '~' => f.write_str("~0")?,
+                            '/' => f.write_str("~1")?,
+                            _ => f.write_char(c)?,
+                        }
+                    }
+                }
+                PathSegment::Index(index) => write!(f, "/{index}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The [`PathSegment::Key`] for a mapping key.
+pub(crate) fn key_segment<'n, 'input, N>(key: &'n N) -> PathSegment
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    PathSegment::Key(key_name(key))
+}
+
+/// A mapping key as a path segment: strings as is, integers in decimal, anything else in flow
+/// style.
+pub(crate) fn key_name<'n, 'input, N>(key: &'n N) -> String
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    match (key.as_str(), key.as_i64()) {
+        (Some(name), _) => String::from(name),
+        (None, Some(i)) => i.to_string(),
+        (None, None) => {
+            let mut name = String::new();
+            let _ = write_inline(&mut name, key);
+            name
+        }
+    }
+}
+
+///
+/// Read-only visitor over a node tree, driven by [`visit`].
+///
+/// Every hook has a default that does nothing, so implementations only override what they need.
+/// `enter_*` hooks run before a node's children and `leave_*` hooks after them; returning `false`
+/// from `enter_sequence`, `enter_mapping` or `enter_tagged` skips the children. Mapping values
+/// are visited, keys are not. Invalid values are visited as scalars.
+///
+/// Loaders replace an alias with a copy of the anchored node. For node types that record the
+/// alias name, such as [`SpannedYaml`](crate::prelude::SpannedYaml), `enter_alias` runs before
+/// the copy is visited and can skip it, e.g. to visit every anchored node only once.
+///
+/// The `'n` lifetime lets a visitor keep references to the nodes it sees.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::{SpannedYaml, YamlDocAccess, YamlLoader};
+/// use yam_core::visit::{visit, NodePath, Visit};
+///
+/// struct Strings<'n>(Vec<(String, &'n SpannedYaml<'n>)>);
+///
+/// impl<'n> Visit<'n, SpannedYaml<'n>> for Strings<'n> {
+///     fn enter_scalar(&mut self, path: &NodePath, node: &'n SpannedYaml<'n>) {
+///         if node.is_string() {
+///             self.0.push((path.to_string(), node));
+///         }
+///     }
+/// }
+///
+/// let docs = YamlLoader::<SpannedYaml>::load_from("a: [x, 1]\nb: {c: y}").unwrap();
+/// let mut strings = Strings(Vec::new());
+/// visit(&docs[0], &mut strings);
+/// assert_eq!(strings.0[0].0, "/a/0");
+/// assert_eq!(strings.0[1].0, "/b/c");
+/// assert_eq!(strings.0[1].1.span.start.line, 2);
+/// ```
+pub trait Visit<'n, N> {
+    /// Called for a scalar, i.e. `null`, a boolean, a number, a string or an invalid value.
+    fn enter_scalar(&mut self, _path: &NodePath, _node: &'n N) {}
+
+    /// Called after [`Visit::enter_scalar`].
+    fn leave_scalar(&mut self, _path: &NodePath, _node: &'n N) {}
+
+    /// Called before the elements of a sequence. Return `false` to skip them.
+    fn enter_sequence(&mut self, _path: &NodePath, _node: &'n N) -> bool {
+        true
+    }
+
+    /// Called after the elements of a sequence.
+    fn leave_sequence(&mut self, _path: &NodePath, _node: &'n N) {}
+
+    /// Called before the values of a mapping. Return `false` to skip them.
+    fn enter_mapping(&mut self, _path: &NodePath, _node: &'n N) -> bool {
+        true
+    }
+
+    /// Called after the values of a mapping.
+    fn leave_mapping(&mut self, _path: &NodePath, _node: &'n N) {}
+
+    /// Called before the node wrapped by a tag, which has the same path. Return `false` to skip it.
+    fn enter_tagged(&mut self, _path: &NodePath, _node: &'n N) -> bool {
+        true
+    }
+
+    /// Called after the node wrapped by a tag.
+    fn leave_tagged(&mut self, _path: &NodePath, _node: &'n N) {}
+
+    /// Called for a copy of an anchored node made in place of an alias, i.e. a node whose
+    /// [`YamlDocAccess::get_alias`] is set, and for an unresolved alias. Return `false` to skip
+    /// the copy; otherwise it is visited like any other node, with the same path.
+    fn enter_alias(&mut self, _path: &NodePath, _node: &'n N) -> bool {
+        true
+    }
+
+    /// Called after the copy made for an alias.
+    fn leave_alias(&mut self, _path: &NodePath, _node: &'n N) {}
+}
+
+///
+/// Mutable counterpart of [`Visit`], driven by [`visit_mut`].
+///
+/// Hooks may change the node they're given; children are visited as they are once the `enter_*`
+/// hook returns.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::{Yaml, YamlDocAccess};
+/// use yam_core::visit::{visit_mut, NodePath, VisitMut};
+///
+/// struct Redact;
+///
+/// impl VisitMut<Yaml<'_>> for Redact {
+///     fn enter_scalar(&mut self, path: &NodePath, node: &mut Yaml<'_>) {
+///         if path.to_string().ends_with("/password") {
+///             *node = Yaml::from("***");
+///         }
+///     }
+/// }
+///
+/// let mut doc = Yaml::load_single("db: {user: app, password: hunter2}").unwrap();
+/// visit_mut(&mut doc, &mut Redact);
+/// assert_eq!(doc["db"]["password"].as_str(), Some("***"));
+/// ```
+pub trait VisitMut<N> {
+    /// Called for a scalar, i.e. `null`, a boolean, a number, a string or an invalid value.
+    fn enter_scalar(&mut self, _path: &NodePath, _node: &mut N) {}
+
+    /// Called after [`VisitMut::enter_scalar`].
+    fn leave_scalar(&mut self, _path: &NodePath, _node: &mut N) {}
+
+    /// Called before the elements of a sequence. Return `false` to skip them.
+    fn enter_sequence(&mut self, _path: &NodePath, _node: &mut N) -> bool {
+        true
+    }
+
+    /// Called after the elements of a sequence.
+    fn leave_sequence(&mut self, _path: &NodePath, _node: &mut N) {}
+
+    /// Called before the values of a mapping. Return `false` to skip them.
+    fn enter_mapping(&mut self, _path: &NodePath, _node: &mut N) -> bool {
+        true
+    }
+
+    /// Called after the values of a mapping.
+    fn leave_mapping(&mut self, _path: &NodePath, _node: &mut N) {}
+
+    /// Called before the node wrapped by a tag, which has the same path. Return `false` to skip it.
+    fn enter_tagged(&mut self, _path: &NodePath, _node: &mut N) -> bool {
+        true
+    }
+
+    /// Called after the node wrapped by a tag.
+    fn leave_tagged(&mut self, _path: &NodePath, _node: &mut N) {}
+
+    /// Called for a copy of an anchored node made in place of an alias, i.e. a node whose
+    /// [`YamlDocAccess::get_alias`] is set, and for an unresolved alias. Return `false` to skip
+    /// the copy; otherwise it is visited like any other node, with the same path.
+    fn enter_alias(&mut self, _path: &NodePath, _node: &mut N) -> bool {
+        true
+    }
+
+    /// Called after the copy made for an alias.
+    fn leave_alias(&mut self, _path: &NodePath, _node: &mut N) {}
+}
+
+/// Walks `root` depth-first, calling the hooks of `visitor`.
+pub fn visit<'n, 'input, N, V>(root: &'n N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+    V: Visit<'n, N> + ?Sized,
+{
+    visit_node(&mut NodePath::new(), root, visitor);
+}
+
+/// Walks `root` depth-first, calling the hooks of `visitor`, which may modify the tree.
+pub fn visit_mut<'input, N, V>(root: &mut N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+    V: VisitMut<N> + ?Sized,
+{
+    visit_node_mut(&mut NodePath::new(), root, visitor);
+}
+
+fn visit_node<'n, 'input, N, V>(path: &mut NodePath, node: &'n N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+    V: Visit<'n, N> + ?Sized,
+{
+    if node.get_alias().is_some() {
+        if visitor.enter_alias(path, node) {
+            visit_value(path, node, visitor);
+        }
+        visitor.leave_alias(path, node);
+    } else {
+        visit_value(path, node, visitor);
+    }
+}
+
+fn visit_value<'n, 'input, N, V>(path: &mut NodePath, node: &'n N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+    V: Visit<'n, N> + ?Sized,
+{
+    if let Some(inner) = node.tagged_node() {
+        if visitor.enter_tagged(path, node) {
+            visit_node(path, inner, visitor);
+        }
+        visitor.leave_tagged(path, node);
+        return;
+    }
+    match node.get_type() {
+        NodeType::Sequence => {
+            if visitor.enter_sequence(path, node) {
+                for (i, elem) in node.sequence().elems().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    visit_node(path, elem, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_sequence(path, node);
+        }
+        NodeType::Mapping => {
+            if visitor.enter_mapping(path, node) {
+                for entry in node.mapping().entries() {
+                    path.push(key_segment(&entry.key));
+                    visit_node(path, &entry.value, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_mapping(path, node);
+        }
+        NodeType::Alias => {
+            visitor.enter_alias(path, node);
+            visitor.leave_alias(path, node);
+        }
+        _ => {
+            visitor.enter_scalar(path, node);
+            visitor.leave_scalar(path, node);
+        }
+    }
+}
+
+fn visit_node_mut<'input, N, V>(path: &mut NodePath, node: &mut N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+    V: VisitMut<N> + ?Sized,
+{
+    if node.get_alias().is_some() {
+        if visitor.enter_alias(path, node) {
+            visit_value_mut(path, node, visitor);
+        }
+        visitor.leave_alias(path, node);
+    } else {
+        visit_value_mut(path, node, visitor);
+    }
+}
+
+fn visit_value_mut<'input, N, V>(path: &mut NodePath, node: &mut N, visitor: &mut V)
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N>,
+    N::MappingNode: MappingLike<N>,
+    V: VisitMut<N> + ?Sized,
+{
+    if node.tagged_node().is_some() {
+        if visitor.enter_tagged(path, node)
+            && let Some(inner) = node.tagged_node_mut()
+        {
+            visit_node_mut(path, inner, visitor);
+        }
+        visitor.leave_tagged(path, node);
+        return;
+    }
+    match node.get_type() {
+        NodeType::Sequence => {
+            if visitor.enter_sequence(path, node)
+                && let Ok(seq) = node.as_sequence_mut()
+            {
+                for (i, elem) in seq.elems_mut().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    visit_node_mut(path, elem, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_sequence(path, node);
+        }
+        NodeType::Mapping => {
+            if visitor.enter_mapping(path, node)
+                && let Ok(map) = node.as_mapping_mut()
+            {
+                for (key, value) in map.entries_mut() {
+                    path.push(key_segment(key));
+                    visit_node_mut(path, value, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_mapping(path, node);
+        }
+        NodeType::Alias => {
+            visitor.enter_alias(path, node);
+            visitor.leave_alias(path, node);
+        }
+        _ => {
+            visitor.enter_scalar(path, node);
+            visitor.leave_scalar(path, node);
+        }
+    }
+}
+
+///
+/// Iterates over `root` and every node below it, depth-first, parents before children.
+///
+/// A tagged node is yielded once, and its children are those of the node it wraps.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::Yaml;
+/// use yam_core::visit::walk;
+///
+/// let doc = Yaml::load_single("a: [x, y]\nb: 1").unwrap();
+/// let paths: Vec<String> = walk(&doc).map(|(path, _)| path.to_string()).collect();
+/// assert_eq!(paths, ["", "/a", "/a/0", "/a/1", "/b"]);
+/// ```
+pub fn walk<'n, 'input, N>(root: &'n N) -> Walk<'n, N>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    Walk {
+        stack: vec![(NodePath::new(), root)],
+    }
+}
+
+/// Iterator returned by [`walk`].
+#[derive(Debug, Clone)]
+pub struct Walk<'n, N> {
+    stack: Vec<(NodePath, &'n N)>,
+}
+
+impl<'n, 'input, N> Iterator for Walk<'n, N>
+where
+    N: YamlDocAccess<'input>,
+    N::SequenceNode: SequenceLike<N> + 'n,
+    N::MappingNode: MappingLike<N> + 'n,
+{
+    type Item = (NodePath, &'n N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        let mut inner = node;
+        while let Some(tagged) = inner.tagged_node() {
+            inner = tagged;
+        }
+        if let Ok(seq) = inner.as_sequence() {
+            for (i, elem) in seq.elems().enumerate().rev() {
+                self.stack.push((path.child(PathSegment::Index(i)), elem));
+            }
+        } else if let Ok(map) = inner.as_mapping() {
+            for entry in map.entries().iter().rev() {
+                self.stack
+                    .push((path.child(key_segment(&entry.key)), &entry.value));
+            }
+        }
+        Some((path, node))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::{SpannedYaml, Yaml, YamlDocAccess, YamlLoader};
+    use crate::visit::{NodePath, PathSegment, Visit, VisitMut, visit, visit_mut, walk};
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+
+    const DOC: &str = "
+a/b: [1, {c: x}]
+tagged: !thing {d: 2}
+skip: {e: 3}
+[k, l]: 4
+";
+
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl<'n> Visit<'n, Yaml<'n>> for Trace {
+        fn enter_scalar(&mut self, path: &NodePath, node: &'n Yaml<'n>) {
+            self.0.push(format!("scalar {path} {:?}", node.as_i64()));
+        }
+
+        fn enter_sequence(&mut self, path: &NodePath, _node: &'n Yaml<'n>) -> bool {
+            self.0.push(format!("+seq {path}"));
+            true
+        }
+
+        fn leave_sequence(&mut self, path: &NodePath, _node: &'n Yaml<'n>) {
+            self.0.push(format!("-seq {path}"));
+        }
+
+        fn enter_mapping(&mut self, path: &NodePath, _node: &'n Yaml<'n>) -> bool {
+            self.0.push(format!("+map {path}"));
+            path.to_string() != "/skip"
+        }
+
+        fn leave_mapping(&mut self, path: &NodePath, _node: &'n Yaml<'n>) {
+            self.0.push(format!("-map {path}"));
+        }
+
+        fn enter_tagged(&mut self, path: &NodePath, node: &'n Yaml<'n>) -> bool {
+            let tag = node.get_tag().unwrap();
+            self.0.push(format!("+tag {path} {}", tag.suffix));
+            true
+        }
+
+        fn leave_tagged(&mut self, path: &NodePath, _node: &'n Yaml<'n>) {
+            self.0.push(format!("-tag {path}"));
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let doc = Yaml::load_single(DOC).unwrap();
+        let mut trace = Trace::default();
+        visit(&doc, &mut trace);
+        assert_eq!(
+            trace.0,
+            [
+                "+map ",
+                "+seq /a~1b",
+                "scalar /a~1b/0 Some(1)",
+                "+map /a~1b/1",
+                "scalar /a~1b/1/c None",
+                "-map /a~1b/1",
+                "-seq /a~1b",
+                "+tag /tagged thing",
+                "+map /tagged",
+                "scalar /tagged/d Some(2)",
+                "-map /tagged",
+                "-tag /tagged",
+                "+map /skip",
+                "-map /skip",
+                "scalar /[k, l] Some(4)",
+                "-map ",
+            ]
+        );
+    }
+
+    struct Double;
+
+    impl<'a> VisitMut<SpannedYaml<'a>> for Double {
+        fn enter_scalar(&mut self, _path: &NodePath, node: &mut SpannedYaml<'a>) {
+            if let Some(i) = node.as_i64_mut() {
+                *i *= 2;
+            }
+        }
+
+        fn leave_sequence(&mut self, _path: &NodePath, node: &mut SpannedYaml<'a>) {
+            node.sequence_mut().push(SpannedYaml::null());
+        }
+    }
+
+    #[test]
+    fn test_visit_mut() {
+        let mut doc = YamlLoader::<SpannedYaml>::load_from(DOC).unwrap().remove(0);
+        visit_mut(&mut doc, &mut Double);
+        assert_eq!(doc.pointer("/a~1b/0").unwrap().as_i64(), Some(2));
+        assert!(doc.pointer("/a~1b/2").unwrap().is_null());
+        assert_eq!(
+            doc.pointer("/tagged").unwrap().get_tag().unwrap().suffix,
+            "thing"
+        );
+        assert_eq!(
+            doc.pointer("/tagged")
+                .unwrap()
+                .tagged_node()
+                .unwrap()
+                .get("d")
+                .unwrap()
+                .as_i64(),
+            Some(4)
+        );
+        assert_eq!(doc.pointer("/skip/e").unwrap().as_i64(), Some(6));
+    }
+
+    #[derive(Default)]
+    struct Aliases {
+        skip: bool,
+        trace: Vec<String>,
+    }
+
+    impl<'n> Visit<'n, SpannedYaml<'n>> for Aliases {
+        fn enter_scalar(&mut self, path: &NodePath, _node: &'n SpannedYaml<'n>) {
+            self.trace.push(format!("scalar {path}"));
+        }
+
+        fn enter_alias(&mut self, path: &NodePath, node: &'n SpannedYaml<'n>) -> bool {
+            self.trace
+                .push(format!("+alias {path} {}", node.get_alias().unwrap()));
+            !self.skip
+        }
+
+        fn leave_alias(&mut self, path: &NodePath, _node: &'n SpannedYaml<'n>) {
+            self.trace.push(format!("-alias {path}"));
+        }
+    }
+
+    impl<'a> VisitMut<SpannedYaml<'a>> for Aliases {
+        fn enter_alias(&mut self, path: &NodePath, node: &mut SpannedYaml<'a>) -> bool {
+            self.trace.push(format!("+alias {path}"));
+            *node = SpannedYaml::null();
+            false
+        }
+    }
+
+    #[test]
+    fn test_visit_alias() {
+        let input = "a: &a {x: 1}\nb: *a\nc: [*a]\n";
+        let mut doc = YamlLoader::<SpannedYaml>::load_from(input)
+            .unwrap()
+            .remove(0);
+        let mut aliases = Aliases::default();
+        visit(&doc, &mut aliases);
+        assert_eq!(
+            aliases.trace,
+            [
+                "scalar /a/x",
+                "+alias /b a",
+                "scalar /b/x",
+                "-alias /b",
+                "+alias /c/0 a",
+                "scalar /c/0/x",
+                "-alias /c/0",
+            ]
+        );
+
+        let mut aliases = Aliases {
+            skip: true,
+            ..Aliases::default()
+        };
+        visit(&doc, &mut aliases);
+        assert_eq!(
+            aliases.trace,
+            [
+                "scalar /a/x",
+                "+alias /b a",
+                "-alias /b",
+                "+alias /c/0 a",
+                "-alias /c/0"
+            ]
+        );
+
+        let mut aliases = Aliases::default();
+        visit_mut(&mut doc, &mut aliases);
+        assert_eq!(aliases.trace, ["+alias /b", "+alias /c/0"]);
+        assert!(doc.pointer("/b").unwrap().is_null());
+        assert!(doc.pointer("/c/0").unwrap().is_null());
+        assert_eq!(doc.pointer("/a/x").unwrap().as_i64(), Some(1));
+    }
+
+    #[test]
+    fn test_walk() {
+        let doc = Yaml::load_single(DOC).unwrap();
+        let paths: Vec<_> = walk(&doc).map(|(path, _)| path.to_string()).collect();
+        assert_eq!(
+            paths,
+            [
+                "",
+                "/a~1b",
+                "/a~1b/0",
+                "/a~1b/1",
+                "/a~1b/1/c",
+                "/tagged",
+                "/tagged/d",
+                "/skip",
+                "/skip/e",
+                "/[k, l]",
+            ]
+        );
+        for (path, node) in walk(&doc).filter(|(path, _)| !path.to_string().contains('[')) {
+            assert_eq!(doc.pointer(&path.to_string()), Some(node));
+        }
+        let (path, _) = walk(&doc).nth(4).unwrap();
+        assert_eq!(
+            path.segments(),
+            [
+                PathSegment::Key("a/b".into()),
+                PathSegment::Index(1),
+                PathSegment::Key("c".into())
+            ]
+        );
+        assert_eq!(
+            walk(&doc).next().map(|(path, _)| path),
+            Some(NodePath::new())
+        );
+        assert!(path.segments().len() == 3 && !path.is_root());
+    }
+}
@@ -1,6 +1,9 @@
+use alloc::borrow::Cow;
 use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt::{Display, Formatter};
+use core::str::FromStr;
 use serde_core::de::{
     DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, StdError, VariantAccess,
 };
@@ -8,7 +11,7 @@ use serde_core::{de, forward_to_deserialize_any};
 use yam_core::node::YamlScalar;
 use yam_core::parsing::parser_iter::YamEvent;
 use yam_core::parsing::{ParserIter, ScalarValue, Source, StrSource};
-use yam_core::prelude::YamlError;
+use yam_core::prelude::{ScalarType, Schema, Tag, TagResolver, Timestamp, YamlError};
 
 pub(crate) struct YamIterDeserializer<'de, R>
 where
@@ -17,6 +20,9 @@ where
     yaml_iter: ParserIter<'de, R>,
     last_event: YamEvent<'de>,
     has_peeked: bool,
+    schema: Schema,
+    resolver: Option<&'de dyn TagResolver>,
+    standard_tags: bool,
 }
 
 impl<'a> YamIterDeserializer<'a, StrSource<'a>> {
@@ -25,6 +31,26 @@ impl<'a> YamIterDeserializer<'a, StrSource<'a>> {
             yaml_iter: ParserIter::new(StrSource::new(source)),
             last_event: YamEvent::DocStart,
             has_peeked: false,
+            schema: Schema::Core,
+            resolver: None,
+            standard_tags: false,
+        }
+    }
+
+    /// Creates a deserializer that resolves plain scalars with the given [`Schema`].
+    pub fn with_schema(source: &'a str, schema: Schema) -> Self {
+        YamIterDeserializer {
+            schema,
+            ..Self::new(source)
+        }
+    }
+
+    /// Creates a deserializer that consults `resolver` for every scalar before `schema`.
+    pub fn with_resolver(source: &'a str, schema: Schema, resolver: &'a dyn TagResolver) -> Self {
+        YamIterDeserializer {
+            schema,
+            resolver: Some(resolver),
+            ..Self::new(source)
         }
     }
 }
@@ -33,6 +59,21 @@ impl<'a, R> YamIterDeserializer<'a, R>
 where
     R: Source,
 {
+    /// Whether to support the `!!timestamp`, `!!set`, `!!omap` and `!!pairs` tags.
+    ///
+    /// When set, `!!timestamp` scalars are checked and read as strings, `!!set` mappings are read
+    /// as sequences of their keys, and `!!omap` and `!!pairs` sequences are read as mappings or
+    /// as sequences of `(key, value)` tuples.
+    pub fn standard_tags(mut self, value: bool) -> Self {
+        self.standard_tags = value;
+        self
+    }
+
+    /// Whether `tag` is the YAML tag with `suffix` and standard tags are enabled.
+    fn is_standard_tag(&self, tag: Option<&Tag>, suffix: &str) -> bool {
+        self.standard_tags && tag.is_some_and(|tag| tag.is_yaml_tag(suffix))
+    }
+
     fn next_el(&mut self) -> Option<YamEvent<'a>> {
         if self.has_peeked {
             self.has_peeked = false;
@@ -56,11 +97,37 @@ where
         }
     }
 
+    /// Runs the custom [`TagResolver`], if any, on `scalar`.
+    fn resolve_custom(
+        &self,
+        scalar: &ScalarValue<'a>,
+    ) -> Result<Option<YamlScalar<'a>>, DeYamlError> {
+        match self.resolver {
+            Some(resolver) => resolver
+                .resolve(&scalar.value, scalar.scalar_type, scalar.tag.as_deref())
+                .map_err(DeYamlError::Custom),
+            None => Ok(None),
+        }
+    }
+
     fn resolve_scalar<V: de::Visitor<'a>>(
-        scalar_value: ScalarValue,
+        &self,
+        scalar_value: ScalarValue<'a>,
         visitor: V,
     ) -> Result<V::Value, DeYamlError> {
-        let scalar = YamlScalar::parse_from_scalar(scalar_value);
+        let scalar = match self.resolve_custom(&scalar_value)? {
+            Some(scalar) => Some(scalar),
+            None if self.is_standard_tag(scalar_value.tag.as_deref(), "timestamp") => {
+                if Timestamp::parse(&scalar_value.value).is_none() {
+                    return Err(DeYamlError::Custom(format!(
+                        "invalid !!timestamp `{}`",
+                        scalar_value.value
+                    )));
+                }
+                Some(YamlScalar::String(scalar_value.value))
+            }
+            None => YamlScalar::parse_from_scalar_with_schema(scalar_value, self.schema),
+        };
         match scalar {
             Some(YamlScalar::Integer(x)) => visitor.visit_i64(x),
             Some(YamlScalar::FloatingPoint(x)) => visitor.visit_f64(x),
@@ -70,13 +137,34 @@ where
             None => Err(DeYamlError::Custom("Failed to parse scalar".to_string())),
         }
     }
+
+    /// Parses an integer scalar, resolving it with the custom resolver and `schema` first so
+    /// that e.g. YAML 1.1 octals are read correctly.
+    fn parse_integer<T>(&self, scalar: &ScalarValue<'a>) -> Result<Option<T>, DeYamlError>
+    where
+        T: FromStr + TryFrom<i64>,
+    {
+        if let Some(resolved) = self.resolve_custom(scalar)? {
+            return Ok(match resolved {
+                YamlScalar::Integer(i) => T::try_from(i).ok(),
+                _ => None,
+            });
+        }
+        if scalar.scalar_type == ScalarType::Plain
+            && let YamlScalar::Integer(i) =
+                YamlScalar::<f64>::parse_from_cow_with_schema(scalar.value.clone(), self.schema)
+        {
+            return Ok(T::try_from(i).ok());
+        }
+        Ok(scalar.value.parse::<T>().ok())
+    }
 }
 
 macro_rules! parse_from_cow {
-    ($e:expr, $v:expr, $method:ident, $t:ty) => {
-        match $e.parse::<$t>() {
-            Ok(i) => $v.$method(i),
-            Err(_) => Err(DeYamlError::Custom(format!(
+    ($de:expr, $e:expr, $v:expr, $method:ident, $t:ty) => {
+        match $de.parse_integer::<$t>($e)? {
+            Some(i) => $v.$method(i),
+            None => Err(DeYamlError::Custom(format!(
                 "Failed to parse {:?} from scalar value",
                 stringify!($t)
             ))),
@@ -95,11 +183,20 @@ where
         V: de::Visitor<'de>,
     {
         match self.skip_doc() {
+            Some(YamEvent::MapStart(_, tag)) if self.is_standard_tag(tag.as_deref(), "set") => {
+                self.deserialize_set(visitor)
+            }
+            Some(YamEvent::SeqStart(_, tag))
+                if self.is_standard_tag(tag.as_deref(), "omap")
+                    || self.is_standard_tag(tag.as_deref(), "pairs") =>
+            {
+                self.deserialize_pairs(PairsVisitor::Map(visitor), tag.as_deref())
+            }
             Some(YamEvent::MapStart(_, _)) => self.deserialize_map(visitor),
             Some(YamEvent::SeqStart(_, _)) => self.deserialize_seq(visitor),
             Some(YamEvent::Scalar(scalar_value)) => {
                 self.skip();
-                YamIterDeserializer::<R>::resolve_scalar(scalar_value, visitor)
+                self.resolve_scalar(scalar_value, visitor)
             }
             e => Err(DeYamlError::Custom(format!(
                 "Unexpected event (can only process DocStart): {e:?}"
@@ -113,7 +210,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.has_peeked = false;
-            parse_from_cow!(&scalar.value, visitor, visit_i8, i8)
+            parse_from_cow!(self, &scalar, visitor, visit_i8, i8)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for i32 deserialization".to_string(),
@@ -127,7 +224,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.has_peeked = false;
-            parse_from_cow!(&scalar.value, visitor, visit_i16, i16)
+            parse_from_cow!(self, &scalar, visitor, visit_i16, i16)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for i16 deserialization".to_string(),
@@ -141,7 +238,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.skip();
-            parse_from_cow!(&scalar.value, visitor, visit_i32, i32)
+            parse_from_cow!(self, &scalar, visitor, visit_i32, i32)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for i32 deserialization".to_string(),
@@ -155,7 +252,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.skip();
-            parse_from_cow!(&scalar.value, visitor, visit_i64, i64)
+            parse_from_cow!(self, &scalar, visitor, visit_i64, i64)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for i64 deserialization".to_string(),
@@ -169,7 +266,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.has_peeked = false;
-            parse_from_cow!(&scalar.value, visitor, visit_u8, u8)
+            parse_from_cow!(self, &scalar, visitor, visit_u8, u8)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for i8 deserialization".to_string(),
@@ -183,7 +280,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.has_peeked = false;
-            parse_from_cow!(&scalar.value, visitor, visit_u16, u16)
+            parse_from_cow!(self, &scalar, visitor, visit_u16, u16)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for u16 deserialization".to_string(),
@@ -197,7 +294,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.skip();
-            parse_from_cow!(&scalar.value, visitor, visit_u32, u32)
+            parse_from_cow!(self, &scalar, visitor, visit_u32, u32)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for u32 deserialization".to_string(),
@@ -211,7 +308,7 @@ where
     {
         if let Some(YamEvent::Scalar(scalar)) = self.skip_doc() {
             self.skip();
-            parse_from_cow!(&scalar.value, visitor, visit_u64, u64)
+            parse_from_cow!(self, &scalar, visitor, visit_u64, u64)
         } else {
             Err(DeYamlError::Custom(
                 "Expected scalar event for u64 deserialization".to_string(),
@@ -253,11 +350,23 @@ where
     where
         V: de::Visitor<'de>,
     {
-        if !matches!(self.skip_doc(), Some(YamEvent::SeqStart(_, _))) {
-            return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
-                expected: "SeqStart",
-                found: self.last_event.as_simple_str(),
-            }));
+        match self.skip_doc() {
+            Some(YamEvent::MapStart(_, tag)) if self.is_standard_tag(tag.as_deref(), "set") => {
+                return self.deserialize_set(visitor);
+            }
+            Some(YamEvent::SeqStart(_, tag))
+                if self.is_standard_tag(tag.as_deref(), "omap")
+                    || self.is_standard_tag(tag.as_deref(), "pairs") =>
+            {
+                return self.deserialize_pairs(PairsVisitor::Seq(visitor), tag.as_deref());
+            }
+            Some(YamEvent::SeqStart(_, _)) => {}
+            _ => {
+                return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
+                    expected: "SeqStart",
+                    found: self.last_event.as_simple_str(),
+                }));
+            }
         }
         self.skip();
         let val = visitor.visit_seq(SeqCollection::new_seq(self))?;
@@ -277,11 +386,20 @@ where
     where
         V: de::Visitor<'de>,
     {
-        if !matches!(self.skip_doc(), Some(YamEvent::MapStart(_, _))) {
-            return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
-                expected: "MapStart",
-                found: self.last_event.as_simple_str(),
-            }));
+        match self.skip_doc() {
+            Some(YamEvent::SeqStart(_, tag))
+                if self.is_standard_tag(tag.as_deref(), "omap")
+                    || self.is_standard_tag(tag.as_deref(), "pairs") =>
+            {
+                return self.deserialize_pairs(PairsVisitor::Map(visitor), tag.as_deref());
+            }
+            Some(YamEvent::MapStart(_, _)) => {}
+            _ => {
+                return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
+                    expected: "MapStart",
+                    found: self.last_event.as_simple_str(),
+                }));
+            }
         }
         self.skip();
         let val = visitor.visit_map(SeqCollection::new_map(self))?;
@@ -348,6 +466,58 @@ where
             ev => ev,
         }
     }
+
+    /// Reads a `!!set` mapping as a sequence of its keys.
+    fn deserialize_set<V>(&mut self, visitor: V) -> Result<V::Value, DeYamlError>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.skip();
+        let val = visitor.visit_seq(SetAccess { de: self })?;
+        if !matches!(self.last_event, YamEvent::MapEnd)
+            && !matches!(self.next_el(), Some(YamEvent::MapEnd))
+        {
+            return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
+                expected: "MapEnd",
+                found: self.last_event.as_simple_str(),
+            }));
+        }
+        self.skip();
+        Ok(val)
+    }
+
+    /// Reads an `!!omap` or `!!pairs` sequence of single-key mappings as a mapping, or as a
+    /// sequence of key-value tuples.
+    fn deserialize_pairs<V>(
+        &mut self,
+        visitor: PairsVisitor<V>,
+        tag: Option<&Tag>,
+    ) -> Result<V::Value, DeYamlError>
+    where
+        V: de::Visitor<'de>,
+    {
+        let omap = tag.is_some_and(|tag| tag.suffix == "omap");
+        self.skip();
+        let mut pairs = PairsAccess {
+            de: self,
+            omap,
+            keys: Vec::new(),
+        };
+        let val = match visitor {
+            PairsVisitor::Map(visitor) => visitor.visit_map(pairs)?,
+            PairsVisitor::Seq(visitor) => visitor.visit_seq(PairsSeq { pairs: &mut pairs })?,
+        };
+        if !matches!(self.last_event, YamEvent::SeqEnd)
+            && !matches!(self.next_el(), Some(YamEvent::SeqEnd))
+        {
+            return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
+                expected: "SeqEnd",
+                found: self.last_event.as_simple_str(),
+            }));
+        }
+        self.skip();
+        Ok(val)
+    }
 }
 
 #[derive(Debug)]
@@ -458,6 +628,197 @@ where
     }
 }
 
+/// Reads the keys of a `!!set`, checking that its values are null.
+struct SetAccess<'a, 'de: 'a, R>
+where
+    R: Source,
+{
+    de: &'a mut YamIterDeserializer<'de, R>,
+}
+
+impl<'de, R> SeqAccess<'de> for SetAccess<'_, 'de, R>
+where
+    R: Source,
+{
+    type Error = DeYamlError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.de.next_el() {
+            Some(YamEvent::MapEnd) => return Ok(None),
+            Some(YamEvent::DocEnd | YamEvent::StreamEnd) | None => {
+                return Err(DeYamlError::ParserError(YamlError::UnExpectedEvent {
+                    expected: "MapEnd",
+                    found: self.de.last_event.as_simple_str(),
+                }));
+            }
+            Some(_) => {}
+        }
+        let key = seed.deserialize(&mut *self.de)?;
+        match self.de.next_el() {
+            Some(YamEvent::Scalar(scalar)) if scalar.is_null() => {
+                self.de.skip();
+                Ok(Some(key))
+            }
+            _ => Err(DeYamlError::Custom(
+                "!!set must be a mapping with null values".to_string(),
+            )),
+        }
+    }
+}
+
+/// Reads the entries of an `!!omap` or `!!pairs`, checking that each is a single-key mapping.
+struct PairsAccess<'a, 'de: 'a, R>
+where
+    R: Source,
+{
+    de: &'a mut YamIterDeserializer<'de, R>,
+    omap: bool,
+    /// Scalar keys seen so far, to reject duplicate `!!omap` keys.
+    keys: Vec<Cow<'de, str>>,
+}
+
+impl<R> PairsAccess<'_, '_, R>
+where
+    R: Source,
+{
+    fn not_single_key(&self) -> DeYamlError {
+        let tag = if self.omap { "omap" } else { "pairs" };
+        DeYamlError::Custom(format!("!!{tag} must be a sequence of single-key mappings"))
+    }
+}
+
+impl<'de, R> MapAccess<'de> for PairsAccess<'_, 'de, R>
+where
+    R: Source,
+{
+    type Error = DeYamlError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.de.next_el() {
+            Some(YamEvent::SeqEnd) => return Ok(None),
+            Some(YamEvent::MapStart(_, _)) => self.de.skip(),
+            _ => return Err(self.not_single_key()),
+        }
+        match self.de.next_el() {
+            Some(YamEvent::MapEnd) | None => return Err(self.not_single_key()),
+            Some(YamEvent::Scalar(scalar)) if self.omap => {
+                if self.keys.contains(&scalar.value) {
+                    return Err(DeYamlError::Custom(
+                        "!!omap keys must be unique".to_string(),
+                    ));
+                }
+                self.keys.push(scalar.value);
+            }
+            Some(_) => {}
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let val = seed.deserialize(&mut *self.de)?;
+        if !matches!(self.de.next_el(), Some(YamEvent::MapEnd)) {
+            return Err(self.not_single_key());
+        }
+        self.de.skip();
+        Ok(val)
+    }
+}
+
+/// How [`YamIterDeserializer::deserialize_pairs`] presents its entries.
+enum PairsVisitor<V> {
+    Map(V),
+    Seq(V),
+}
+
+/// Reads the entries of an `!!omap` or `!!pairs` as key-value tuples.
+struct PairsSeq<'p, 'a, 'de: 'a, R>
+where
+    R: Source,
+{
+    pairs: &'p mut PairsAccess<'a, 'de, R>,
+}
+
+impl<'de, R> SeqAccess<'de> for PairsSeq<'_, '_, 'de, R>
+where
+    R: Source,
+{
+    type Error = DeYamlError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if matches!(self.pairs.de.next_el(), Some(YamEvent::SeqEnd)) {
+            return Ok(None);
+        }
+        seed.deserialize(Pair {
+            pairs: &mut *self.pairs,
+            index: 0,
+        })
+        .map(Some)
+    }
+}
+
+/// A single `!!omap` or `!!pairs` entry, read as a `(key, value)` tuple.
+struct Pair<'p, 'a, 'de: 'a, R>
+where
+    R: Source,
+{
+    pairs: &'p mut PairsAccess<'a, 'de, R>,
+    index: usize,
+}
+
+impl<'de, R> de::Deserializer<'de> for Pair<'_, '_, 'de, R>
+where
+    R: Source,
+{
+    type Error = DeYamlError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, R> SeqAccess<'de> for Pair<'_, '_, 'de, R>
+where
+    R: Source,
+{
+    type Error = DeYamlError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.index += 1;
+        match self.index {
+            1 => match self.pairs.next_key_seed(seed)? {
+                Some(key) => Ok(Some(key)),
+                None => Err(self.pairs.not_single_key()),
+            },
+            2 => self.pairs.next_value_seed(seed).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
 struct Enum<'a, 'de: 'a, R>
 where
     R: Source,
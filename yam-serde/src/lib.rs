@@ -1,6 +1,7 @@
 #![no_std]
 
 extern crate alloc;
+#[allow(dead_code)]
 pub mod binary;
 pub mod de;
 mod escape_str;
@@ -11,6 +12,7 @@ use crate::de::DeYamlError;
 use crate::ser::PrettyFormatter;
 use alloc::string::String;
 use core::fmt::Error;
+use yam_core::prelude::{Schema, TagResolver};
 
 /// Attempts to deserialize a YAML input string into a value of type `T`.
 ///
@@ -71,6 +73,127 @@ where
     Ok(value)
 }
 
+/// Deserializes a YAML input string like [`from_str`], resolving plain scalars with the given
+/// [`Schema`] instead of the YAML 1.2 core schema.
+///
+/// # Errors
+///
+/// Returns a `DeYamlError` under the same conditions as [`from_str`].
+///
+/// # Examples
+///
+/// ```
+/// use yam_core::prelude::Schema;
+/// use yam_serde::from_str_with_schema;
+///
+/// let flags: Vec<bool> = from_str_with_schema("[yes, off]", Schema::Yaml11).unwrap();
+/// assert_eq!(flags, vec![true, false]);
+///
+/// let mode: u32 = from_str_with_schema("0755", Schema::Yaml11).unwrap();
+/// assert_eq!(mode, 0o755);
+/// ```
+pub fn from_str_with_schema<'a, T>(input: &'a str, schema: Schema) -> Result<T, DeYamlError>
+where
+    T: serde_core::de::Deserialize<'a>,
+{
+    let mut de = crate::de::YamIterDeserializer::with_schema(input, schema);
+    let value = T::deserialize(&mut de)?;
+
+    Ok(value)
+}
+
+/// Deserializes a YAML input string like [`from_str_with_schema`], consulting `resolver` for
+/// every scalar before the schema.
+///
+/// # Errors
+///
+/// Returns a `DeYamlError` under the same conditions as [`from_str`], or a
+/// [`DeYamlError::Custom`] with the resolver's message when it rejects a scalar.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use yam_core::prelude::{ScalarType, Schema, Tag, TagResolver, YamlScalar};
+/// use yam_serde::from_str_with_resolver;
+///
+/// struct Kilo;
+///
+/// impl TagResolver for Kilo {
+///     fn resolve<'a>(
+///         &self,
+///         value: &Cow<'a, str>,
+///         _scalar_type: ScalarType,
+///         tag: Option<&Tag>,
+///     ) -> Result<Option<YamlScalar<'a>>, String> {
+///         match tag {
+///             Some(tag) if tag.suffix == "k" => {
+///                 let n: i64 = value.parse().map_err(|_| "invalid number")?;
+///                 Ok(Some(YamlScalar::Integer(n * 1000)))
+///             }
+///             _ => Ok(None),
+///         }
+///     }
+/// }
+///
+/// let sizes: Vec<u32> = from_str_with_resolver("[!k 4, 512]", Schema::Core, &Kilo).unwrap();
+/// assert_eq!(sizes, vec![4000, 512]);
+/// ```
+pub fn from_str_with_resolver<'a, T>(
+    input: &'a str,
+    schema: Schema,
+    resolver: &'a dyn TagResolver,
+) -> Result<T, DeYamlError>
+where
+    T: serde_core::de::Deserialize<'a>,
+{
+    let mut de = crate::de::YamIterDeserializer::with_resolver(input, schema, resolver);
+    let value = T::deserialize(&mut de)?;
+
+    Ok(value)
+}
+
+/// Deserializes a YAML input string like [`from_str_with_schema`], with support for the
+/// `!!timestamp`, `!!set`, `!!omap` and `!!pairs` tags of the
+/// [YAML type repository](https://yaml.org/type/).
+///
+/// `!!timestamp` scalars are read as strings once checked with [`Timestamp::parse`]. `!!set`
+/// mappings are read as sequences of their keys, and `!!omap` and `!!pairs` sequences of
+/// single-key mappings are read as mappings or as sequences of `(key, value)` tuples.
+///
+/// # Errors
+///
+/// Returns a `DeYamlError` under the same conditions as [`from_str`], or a
+/// [`DeYamlError::Custom`] when a node doesn't match its tag.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::{BTreeMap, BTreeSet};
+/// use yam_core::prelude::Schema;
+/// use yam_serde::from_str_with_standard_tags;
+///
+/// let set: BTreeSet<String> = from_str_with_standard_tags("!!set {a, b}", Schema::Core).unwrap();
+/// assert_eq!(set.len(), 2);
+///
+/// let omap: BTreeMap<String, i32> =
+///     from_str_with_standard_tags("!!omap [a: 1, b: 2]", Schema::Core).unwrap();
+/// assert_eq!(omap["b"], 2);
+///
+/// assert!(from_str_with_standard_tags::<String>("!!timestamp 2001-02-30", Schema::Core).is_err());
+/// ```
+///
+/// [`Timestamp::parse`]: yam_core::prelude::Timestamp::parse
+pub fn from_str_with_standard_tags<'a, T>(input: &'a str, schema: Schema) -> Result<T, DeYamlError>
+where
+    T: serde_core::de::Deserialize<'a>,
+{
+    let mut de = crate::de::YamIterDeserializer::with_schema(input, schema).standard_tags(true);
+    let value = T::deserialize(&mut de)?;
+
+    Ok(value)
+}
+
 pub fn to_pretty_string<T>(value: &T, formatter: PrettyFormatter) -> Result<String, Error>
 where
     T: serde_core::ser::Serialize,
@@ -190,3 +190,118 @@ fn test_enum() {
     let expected = E::Struct { a: 1 };
     assert_eq!(expected, yam_serde::from_str(j).unwrap());
 }
+
+#[test]
+fn test_deserialize_with_schema() {
+    use yam_core::prelude::Schema;
+
+    #[derive(Deserialize, Debug)]
+    pub struct Task {
+        privileged: bool,
+        mode: u32,
+        timeout: i64,
+    }
+
+    let input = "privileged: yes\nmode: 0644\ntimeout: 1:30";
+    let task: Task = yam_serde::from_str_with_schema(input, Schema::Yaml11).unwrap();
+    assert!(task.privileged);
+    assert_eq!(task.mode, 0o644);
+    assert_eq!(task.timeout, 90);
+
+    let name: String = yam_serde::from_str_with_schema("true", Schema::Failsafe).unwrap();
+    assert_eq!(name, "true");
+}
+
+#[test]
+fn test_deserialize_with_resolver() {
+    use std::borrow::Cow;
+    use yam_core::prelude::{ScalarType, Schema, Tag, TagResolver, YamlScalar};
+
+    struct Hex;
+
+    impl TagResolver for Hex {
+        fn resolve<'a>(
+            &self,
+            value: &Cow<'a, str>,
+            _scalar_type: ScalarType,
+            tag: Option<&Tag>,
+        ) -> Result<Option<YamlScalar<'a>>, String> {
+            match tag {
+                Some(tag) if tag.suffix == "hex" => i64::from_str_radix(value, 16)
+                    .map(|i| Some(YamlScalar::Integer(i)))
+                    .map_err(|_| format!("{value} is not hexadecimal")),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct Color {
+        r: u8,
+        g: u8,
+        b: i64,
+    }
+
+    let color: Color =
+        yam_serde::from_str_with_resolver("{r: !hex ff, g: 16, b: !hex 0a}", Schema::Core, &Hex)
+            .unwrap();
+    assert_eq!((color.r, color.g, color.b), (255, 16, 10));
+
+    let err =
+        yam_serde::from_str_with_resolver::<Color>("{r: !hex zz, g: 0, b: 0}", Schema::Core, &Hex)
+            .unwrap_err();
+    assert!(err.to_string().contains("zz is not hexadecimal"));
+}
+
+#[test]
+fn test_deserialize_standard_tags() {
+    use std::collections::{BTreeMap, BTreeSet};
+    use yam_core::prelude::{Schema, Timestamp};
+
+    #[derive(Deserialize, Debug)]
+    pub struct Release {
+        at: String,
+        platforms: BTreeSet<String>,
+        steps: Vec<(String, u32)>,
+        limits: BTreeMap<String, u32>,
+    }
+
+    let input = "
+at: !!timestamp 2001-12-14 21:59:43.10 -5
+platforms: !!set {linux, macos}
+steps: !!pairs [build: 1, test: 2, build: 3]
+limits: !!omap [cpu: 2, mem: 512]
+";
+    let release: Release = yam_serde::from_str_with_standard_tags(input, Schema::Core).unwrap();
+    assert_eq!(Timestamp::parse(&release.at).unwrap().offset_minutes, -300);
+    assert!(release.platforms.contains("macos"));
+    assert_eq!(release.steps.len(), 3);
+    assert_eq!(release.limits["mem"], 512);
+
+    for (input, info) in [
+        (
+            "!!omap [a: 1, b]",
+            "!!omap must be a sequence of single-key mappings",
+        ),
+        (
+            "!!pairs [{a: 1, b: 2}]",
+            "!!pairs must be a sequence of single-key mappings",
+        ),
+        ("!!omap [a: 1, a: 2]", "!!omap keys must be unique"),
+    ] {
+        let err = yam_serde::from_str_with_standard_tags::<BTreeMap<String, Option<u32>>>(
+            input,
+            Schema::Core,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(info), "{input}: {err}");
+    }
+
+    let err =
+        yam_serde::from_str_with_standard_tags::<BTreeSet<String>>("!!set {a: 1}", Schema::Core)
+            .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("!!set must be a mapping with null values")
+    );
+}
@@ -0,0 +1,741 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Index, IndexMut};
+use hashbrown::HashTable;
+use ordered_float::OrderedFloat;
+use std::hash::RandomState;
+use yam_core::prelude::{
+    IsEmpty, MappingLike, NodeType, Span, Tag, Timestamp, YamlAccessError, YamlData, YamlDocAccess,
+    YamlEntry, YamlError, YamlLoader, YamlScalar,
+};
+
+///
+/// A mapping that keeps its entries in insertion order and indexes them by a hash of their keys,
+/// so lookups with [`HashedMapping::get`] take O(1) instead of a scan over the entries.
+///
+/// Inserting a key that is already present replaces its value and keeps its position.
+/// Two mappings are equal if they have the same entries in the same order.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::YamlDocAccess;
+/// use yam_std::{HashedMapping, HashedYaml};
+///
+/// let mut map = HashedMapping::new();
+/// map.insert(HashedYaml::from("b"), HashedYaml::from(1));
+/// map.insert(HashedYaml::from("a"), HashedYaml::from(2));
+/// assert_eq!(map.insert(HashedYaml::from("b"), HashedYaml::from(3)), Some(HashedYaml::from(1)));
+///
+/// assert_eq!(map.get(&HashedYaml::from("b")), Some(&HashedYaml::from(3)));
+/// let keys: Vec<_> = map.iter().map(|(k, _)| k.as_str().unwrap()).collect();
+/// assert_eq!(keys, ["b", "a"]);
+/// ```
+pub struct HashedMapping<'input, T> {
+    entries: Vec<YamlEntry<'input, T>>,
+    table: HashTable<usize>,
+    state: RandomState,
+}
+
+impl<T> HashedMapping<'_, T> {
+    /// Creates an empty mapping.
+    #[must_use]
+    pub fn new() -> Self {
+        HashedMapping {
+            entries: Vec::new(),
+            table: HashTable::new(),
+            state: RandomState::new(),
+        }
+    }
+
+    /// Number of entries in the mapping.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the mapping has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the keys and values in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &T)> {
+        self.entries.iter().map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<'input, T: Hash + Eq + Clone> HashedMapping<'input, T> {
+    fn position(&self, key: &T) -> Option<usize> {
+        let hash = self.state.hash_one(key);
+        self.table
+            .find(hash, |&i| self.entries[i].key == *key)
+            .copied()
+    }
+
+    /// Returns the value stored under `key`.
+    #[must_use]
+    pub fn get(&self, key: &T) -> Option<&T> {
+        self.position(key).map(|i| &self.entries[i].value)
+    }
+
+    /// Returns a mutable reference to the value stored under `key`.
+    pub fn get_mut(&mut self, key: &T) -> Option<&mut T> {
+        self.position(key).map(|i| &mut self.entries[i].value)
+    }
+
+    /// Whether the mapping has an entry for `key`.
+    #[must_use]
+    pub fn contains_key(&self, key: &T) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: T, value: T) -> Option<T> {
+        if let Some(i) = self.position(&key) {
+            return Some(mem::replace(&mut self.entries[i].value, value));
+        }
+        let HashedMapping {
+            entries,
+            table,
+            state,
+        } = self;
+        let hash = state.hash_one(&key);
+        table.insert_unique(hash, entries.len(), |&i| state.hash_one(&entries[i].key));
+        entries.push(YamlEntry::new(key, value));
+        None
+    }
+
+    /// Removes the entry for `key`, shifting later entries, and returns its value.
+    pub fn remove(&mut self, key: &T) -> Option<T> {
+        let index = self.position(key)?;
+        Some(self.remove_index(index).1)
+    }
+
+    fn remove_index(&mut self, index: usize) -> (T, T) {
+        let entry = self.entries.remove(index);
+        let hash = self.state.hash_one(&entry.key);
+        if let Ok(slot) = self.table.find_entry(hash, |&i| i == index) {
+            slot.remove();
+        }
+        for i in &mut self.table {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        (entry.key, entry.value)
+    }
+
+    /// Rebuilds the hash table after entries moved.
+    fn reindex(&mut self) {
+        let HashedMapping {
+            entries,
+            table,
+            state,
+        } = self;
+        table.clear();
+        for (i, entry) in entries.iter().enumerate() {
+            let hash = state.hash_one(&entry.key);
+            table.insert_unique(hash, i, |&j| state.hash_one(&entries[j].key));
+        }
+    }
+}
+
+impl<T> Default for HashedMapping<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for HashedMapping<'_, T> {
+    fn clone(&self) -> Self {
+        HashedMapping {
+            entries: self.entries.clone(),
+            table: self.table.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for HashedMapping<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<T: Eq> Eq for HashedMapping<'_, T> {}
+
+impl<T: Hash> Hash for HashedMapping<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.entries.len().hash(state);
+        for entry in &self.entries {
+            entry.key.hash(state);
+            entry.value.hash(state);
+        }
+    }
+}
+
+impl<T: Debug> Debug for HashedMapping<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|entry| (&entry.key, &entry.value)))
+            .finish()
+    }
+}
+
+impl<T: Hash + Eq + Clone> MappingLike<T> for HashedMapping<'_, T> {
+    fn new_map() -> Self {
+        Self::new()
+    }
+
+    fn push_mapping(&mut self, key: T, value: T) {
+        self.insert(key, value);
+    }
+
+    fn entries(&self) -> &Vec<YamlEntry<'_, T>> {
+        &self.entries
+    }
+
+    fn entries_mut<'a>(&'a mut self) -> impl Iterator<Item = (&'a T, &'a mut T)>
+    where
+        T: 'a,
+    {
+        self.entries
+            .iter_mut()
+            .map(|entry| (&entry.key, &mut entry.value))
+    }
+
+    fn remove_at(&mut self, index: usize) -> (T, T) {
+        self.remove_index(index)
+    }
+
+    fn retain_entries(&mut self, mut keep: impl FnMut(&T, &mut T) -> bool) {
+        let len = self.entries.len();
+        self.entries
+            .retain_mut(|entry| keep(&entry.key, &mut entry.value));
+        if self.entries.len() != len {
+            self.reindex();
+        }
+    }
+
+    fn clear_entries(&mut self) {
+        self.entries.clear();
+        self.table.clear();
+    }
+
+    fn get_value(&self, key: &T) -> Option<&T> {
+        self.get(key)
+    }
+
+    fn get_value_mut(&mut self, key: &T) -> Option<&mut T> {
+        self.get_mut(key)
+    }
+
+    fn contains_key(&self, key: &T) -> bool {
+        self.position(key).is_some()
+    }
+
+    fn insert_value(&mut self, key: T, value: T) -> Option<T> {
+        self.insert(key, value)
+    }
+
+    fn remove_key(&mut self, key: &T) -> Option<T> {
+        self.remove(key)
+    }
+
+    fn entry_or_insert_with(&mut self, key: T, default: impl FnOnce() -> T) -> &mut T {
+        let index = match self.position(&key) {
+            Some(index) => index,
+            None => {
+                self.insert(key, default());
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index].value
+    }
+}
+
+impl<T: Clone> IsEmpty for HashedMapping<'_, T> {
+    fn is_collection_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+///
+/// YAML node whose mappings are [`HashedMapping`]s, for O(1) key lookups on large mappings.
+///
+/// Floats are stored as [`OrderedFloat`] so that every node, including mapping keys, implements
+/// [`Eq`] and [`Hash`]. Like [`Yaml`](yam_core::prelude::Yaml), it borrows from the input and
+/// resolves aliases into copies of the anchored node.
+///
+/// # Example
+///
+/// ```rust
+/// use yam_core::prelude::YamlDocAccess;
+/// use yam_std::HashedYaml;
+///
+/// let doc = HashedYaml::load_single("a: 1\n2.5: float key\n[x, y]: complex key").unwrap();
+/// assert_eq!(doc["a"].as_i64(), Some(1));
+/// let key = HashedYaml::from(2.5);
+/// assert_eq!(doc.mapping().get(&key).and_then(HashedYaml::as_str), Some("float key"));
+/// let key = HashedYaml::from(vec![HashedYaml::from("x"), HashedYaml::from("y")]);
+/// assert_eq!(doc.mapping().get(&key).and_then(HashedYaml::as_str), Some("complex key"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct HashedYaml<'input>(
+    pub  YamlData<
+        'input,
+        Self,
+        OrderedFloat<f64>,
+        i64,
+        Cow<'input, str>,
+        HashedMapping<'input, Self>,
+    >,
+);
+
+impl<'input> YamlDocAccess<'input> for HashedYaml<'input> {
+    type OutNode = Self;
+    type SequenceNode = Vec<Self>;
+    type MappingNode = HashedMapping<'input, Self>;
+
+    #[allow(clippy::cast_lossless)]
+    fn key_from_usize(index: usize) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::Integer(
+            index.cast_signed() as i64
+        )))
+    }
+
+    fn key_from_str(index: &str) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::String(Cow::Owned(
+            index.to_string(),
+        ))))
+    }
+
+    fn is_non_empty_collection(&self) -> bool {
+        match &self.0 {
+            YamlData::Sequence(s) => !s.is_empty(),
+            YamlData::Mapping(m) => !m.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn as_bool_mut(&mut self) -> Option<&mut bool> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::Bool(b)) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::Integer(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn as_i64_mut(&mut self) -> Option<&mut i64> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::Integer(i)) => Some(i),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::FloatingPoint(f)) => Some(f.0),
+            _ => None,
+        }
+    }
+
+    fn as_f64_mut(&mut self) -> Option<&mut f64> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::FloatingPoint(f)) => Some(&mut f.0),
+            _ => None,
+        }
+    }
+
+    fn as_sequence(&self) -> Result<&Self::SequenceNode, YamlAccessError> {
+        match &self.0 {
+            YamlData::Sequence(s) => Ok(s),
+            _ => Err(YamlAccessError::ExpectedSequence),
+        }
+    }
+
+    fn as_sequence_mut(&mut self) -> Result<&mut Self::SequenceNode, YamlAccessError> {
+        match &mut self.0 {
+            YamlData::Sequence(s) => Ok(s),
+            _ => Err(YamlAccessError::ExpectedSequence),
+        }
+    }
+
+    fn as_mapping(&self) -> Result<&Self::MappingNode, YamlAccessError> {
+        match &self.0 {
+            YamlData::Mapping(m) => Ok(m),
+            _ => Err(YamlAccessError::ExpectedMapping),
+        }
+    }
+
+    fn as_mapping_mut(&mut self) -> Result<&mut Self::MappingNode, YamlAccessError> {
+        match &mut self.0 {
+            YamlData::Mapping(m) => Ok(m),
+            _ => Err(YamlAccessError::ExpectedMapping),
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match &self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+
+    fn as_str_mut(&mut self) -> Option<&mut str> {
+        match &mut self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s.to_mut().as_mut_str()),
+            _ => None,
+        }
+    }
+
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        self.0.as_timestamp()
+    }
+
+    fn sequence_mut(&mut self) -> &mut Self::SequenceNode {
+        match &mut self.0 {
+            YamlData::Sequence(s) => s,
+            _ => core::panic!("HashedYaml::sequence_mut() called with non-sequence"),
+        }
+    }
+
+    fn sequence(&self) -> &Self::SequenceNode {
+        match &self.0 {
+            YamlData::Sequence(s) => s,
+            _ => core::panic!("HashedYaml::sequence() called with non-sequence"),
+        }
+    }
+
+    fn mapping_mut(&mut self) -> &mut Self::MappingNode {
+        match &mut self.0 {
+            YamlData::Mapping(m) => m,
+            _ => core::panic!("HashedYaml::mapping_mut() called with non-mapping"),
+        }
+    }
+
+    fn mapping(&self) -> &Self::MappingNode {
+        match &self.0 {
+            YamlData::Mapping(m) => m,
+            _ => core::panic!("HashedYaml::mapping() called with non-mapping"),
+        }
+    }
+
+    fn get_tag(&self) -> Option<Tag> {
+        match &self.0 {
+            YamlData::Tagged(tag, ..) => Some(tag.clone().into_owned()),
+            _ => None,
+        }
+    }
+
+    fn tagged_node(&self) -> Option<&Self> {
+        match &self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn tagged_node_mut(&mut self) -> Option<&mut Self> {
+        match &mut self.0 {
+            YamlData::Tagged(_, node) => Some(node),
+            _ => None,
+        }
+    }
+
+    fn get_type(&self) -> NodeType {
+        self.0.get_type()
+    }
+
+    fn into_string(self) -> Option<String> {
+        match self.0 {
+            YamlData::Scalar(YamlScalar::String(s)) => Some(s.into()),
+            _ => None,
+        }
+    }
+
+    fn into_mapping(self) -> Option<Self::MappingNode> {
+        match self.0 {
+            YamlData::Mapping(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    fn into_sequence(self) -> Option<Self::SequenceNode> {
+        match self.0 {
+            YamlData::Sequence(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn into_tagged(self, tag: Cow<'input, Tag>) -> Self {
+        HashedYaml(YamlData::Tagged(tag, Box::new(self)))
+    }
+
+    fn bad_span_value(_span: Span) -> Self {
+        HashedYaml(YamlData::BadValue)
+    }
+
+    fn bad_value() -> Self {
+        HashedYaml(YamlData::BadValue)
+    }
+
+    fn null() -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::Null(PhantomData)))
+    }
+}
+
+fn ordered_scalar(scalar: YamlScalar<'_>) -> YamlScalar<'_, OrderedFloat<f64>> {
+    match scalar {
+        YamlScalar::Null(marker) => YamlScalar::Null(marker),
+        YamlScalar::String(s) => YamlScalar::String(s),
+        YamlScalar::Bool(b) => YamlScalar::Bool(b),
+        YamlScalar::FloatingPoint(f) => YamlScalar::FloatingPoint(OrderedFloat(f)),
+        YamlScalar::Integer(i) => YamlScalar::Integer(i),
+    }
+}
+
+impl<'input> From<YamlData<'input, Self>> for HashedYaml<'input> {
+    fn from(value: YamlData<'input, Self>) -> Self {
+        HashedYaml(match value {
+            YamlData::BadValue => YamlData::BadValue,
+            YamlData::Scalar(scalar) => YamlData::Scalar(ordered_scalar(scalar)),
+            YamlData::Sequence(seq) => YamlData::Sequence(seq),
+            YamlData::Mapping(entries) => {
+                let mut map = HashedMapping::new();
+                for entry in entries {
+                    map.insert(entry.key, entry.value);
+                }
+                YamlData::Mapping(map)
+            }
+            YamlData::Tagged(tag, node) => YamlData::Tagged(tag, node),
+            YamlData::Alias(id) => YamlData::Alias(id),
+        })
+    }
+}
+
+impl<'input> From<YamlScalar<'input>> for HashedYaml<'input> {
+    fn from(value: YamlScalar<'input>) -> Self {
+        HashedYaml(YamlData::Scalar(ordered_scalar(value)))
+    }
+}
+
+impl<'input> HashedYaml<'input> {
+    ///
+    /// Loads all YAML documents from the given input string.
+    ///
+    /// # Errors
+    /// Returns an error if the input isn't valid YAML.
+    pub fn load_from<S: AsRef<str>>(input: S) -> Result<Vec<Self>, YamlError> {
+        YamlLoader::<Self>::load_from(input)
+    }
+
+    ///
+    /// Loads the first YAML document from the given input string.
+    ///
+    /// # Errors
+    /// Returns [`YamlError::NoDocument`] if the input has no documents, or an error if the input
+    /// isn't valid YAML.
+    pub fn load_single<S: AsRef<str>>(input: S) -> Result<Self, YamlError> {
+        YamlLoader::<Self>::load_single(input)
+    }
+}
+
+impl Index<usize> for HashedYaml<'_> {
+    type Output = Self;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match &self.0 {
+            YamlData::Mapping(m) => m.get(&HashedYaml::key_from_usize(index)).unwrap(),
+            YamlData::Sequence(s) => &s[index],
+            _ => panic!(
+                "Expected Mapping and Sequence got {0:?} instead",
+                self.get_type()
+            ),
+        }
+    }
+}
+
+impl IndexMut<usize> for HashedYaml<'_> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let typ = self.get_type();
+        match &mut self.0 {
+            YamlData::Mapping(m) => m.get_mut(&HashedYaml::key_from_usize(index)).unwrap(),
+            YamlData::Sequence(s) => &mut s[index],
+            _ => panic!("Expected Mapping and Sequence got {typ:?} instead"),
+        }
+    }
+}
+
+impl<'k> Index<&'k str> for HashedYaml<'_> {
+    type Output = Self;
+
+    fn index(&self, index: &'k str) -> &Self::Output {
+        match &self.0 {
+            YamlData::Mapping(m) => m.get(&HashedYaml::key_from_str(index)).unwrap(),
+            _ => panic!("Expected Mapping got {0:?} instead", self.get_type()),
+        }
+    }
+}
+
+impl<'k> IndexMut<&'k str> for HashedYaml<'_> {
+    fn index_mut(&mut self, index: &'k str) -> &mut Self::Output {
+        let typ = self.get_type();
+        match &mut self.0 {
+            YamlData::Mapping(m) => m.get_mut(&HashedYaml::key_from_str(index)).unwrap(),
+            _ => panic!("Expected Mapping got {typ:?} instead"),
+        }
+    }
+}
+
+impl<'input> From<&'input str> for HashedYaml<'input> {
+    fn from(value: &'input str) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::String(Cow::Borrowed(value))))
+    }
+}
+
+impl From<bool> for HashedYaml<'_> {
+    fn from(value: bool) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::Bool(value)))
+    }
+}
+
+impl From<f64> for HashedYaml<'_> {
+    fn from(value: f64) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::FloatingPoint(OrderedFloat(
+            value,
+        ))))
+    }
+}
+
+#[allow(clippy::cast_lossless)]
+impl From<i32> for HashedYaml<'_> {
+    fn from(value: i32) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::Integer(value as i64)))
+    }
+}
+
+impl From<i64> for HashedYaml<'_> {
+    fn from(value: i64) -> Self {
+        HashedYaml(YamlData::Scalar(YamlScalar::Integer(value)))
+    }
+}
+
+impl<'input> From<Vec<HashedYaml<'input>>> for HashedYaml<'input> {
+    fn from(value: Vec<HashedYaml<'input>>) -> Self {
+        HashedYaml(YamlData::Sequence(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{HashedMapping, HashedYaml};
+    use yam_core::prelude::{MappingLike, Yaml, YamlDocAccess, YamlLoader};
+
+    #[test]
+    fn test_hashed_load() {
+        let input = "
+base: &base {x: 1, y: 2}
+derived: *base
+.nan: nan
+0.0: zero
+seq: [a, {b: c}]
+";
+        let doc = YamlLoader::<HashedYaml>::load_single(input).unwrap();
+        let plain = Yaml::load_single(input).unwrap();
+        assert_eq!(doc.mapping().len(), plain.mapping().len());
+        assert_eq!(doc["derived"], doc["base"]);
+        assert_eq!(doc["derived"]["y"].as_i64(), Some(2));
+        assert_eq!(doc["seq"][1]["b"].as_str(), Some("c"));
+        assert_eq!(
+            doc.pointer("/seq/1/b").and_then(HashedYaml::as_str),
+            Some("c")
+        );
+        assert_eq!(
+            doc.mapping()
+                .get(&HashedYaml::from(0.0))
+                .and_then(HashedYaml::as_str),
+            Some("zero")
+        );
+        let nan = HashedYaml::from(f64::NAN);
+        assert_eq!(
+            doc.mapping().get(&nan).and_then(HashedYaml::as_str),
+            Some("nan")
+        );
+
+        let keys: Vec<_> = doc
+            .mapping()
+            .iter()
+            .filter_map(|(k, _)| k.as_str())
+            .collect();
+        assert_eq!(keys, ["base", "derived", "seq"]);
+    }
+
+    #[test]
+    fn test_hashed_mapping() {
+        let mut map = HashedMapping::new();
+        for i in 0..1000 {
+            map.insert(HashedYaml::from(i), HashedYaml::from(i * 2));
+        }
+        assert_eq!(map.len(), 1000);
+        assert_eq!(
+            map.get(&HashedYaml::from(999)),
+            Some(&HashedYaml::from(1998))
+        );
+        assert!(!map.contains_key(&HashedYaml::from(1000)));
+        assert!(!map.contains_key(&HashedYaml::from(1.0)));
+
+        *map.get_mut(&HashedYaml::from(0)).unwrap() = HashedYaml::from("zero");
+        let copy = map.clone();
+        assert_eq!(copy, map);
+        assert_eq!(copy.iter().next().unwrap().1.as_str(), Some("zero"));
+    }
+
+    #[test]
+    fn test_hashed_mapping_edits() {
+        let mut map = HashedMapping::new();
+        for i in 0..10 {
+            map.insert(HashedYaml::from(i), HashedYaml::from(i));
+        }
+        assert_eq!(
+            map.remove_key(&HashedYaml::from(3)),
+            Some(HashedYaml::from(3))
+        );
+        map.retain_entries(|k, _| k.as_i64().is_some_and(|i| i % 2 == 0));
+        *map.entry_or_insert_with(HashedYaml::from(4), HashedYaml::null) = HashedYaml::from("four");
+        map.entry_or_insert_with(HashedYaml::from(11), HashedYaml::null);
+
+        let keys: Vec<_> = map.keys().filter_map(HashedYaml::as_i64).collect();
+        assert_eq!(keys, [0, 2, 4, 6, 8, 11]);
+        for (i, key) in map.keys().enumerate() {
+            assert_eq!(map.entries()[i].key, *key);
+            assert!(map.contains_key(key));
+        }
+        assert_eq!(
+            map.get(&HashedYaml::from(4)).and_then(HashedYaml::as_str),
+            Some("four")
+        );
+        assert_eq!(map.remove_at(0).0, HashedYaml::from(0));
+        assert_eq!(map.get(&HashedYaml::from(8)), Some(&HashedYaml::from(8)));
+    }
+}
@@ -6,5 +6,18 @@ extern crate alloc;
 extern crate core;
 extern crate yam_core;
 
-// #[cfg(feature = "hashed_node")]
-// mod hash_node;
+#[cfg(feature = "hashed_node")]
+mod hash_node;
+
+#[cfg(feature = "threads")]
+mod parallel;
+#[cfg(feature = "std")]
+mod read;
+
+#[cfg(feature = "hashed_node")]
+pub use hash_node::{HashedMapping, HashedYaml};
+#[cfg(feature = "threads")]
+pub use parallel::{ParallelLoader, document_boundaries};
+
+#[cfg(feature = "std")]
+pub use read::{IoRead, source_from_reader};
@@ -0,0 +1,378 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::string::ToString;
+use std::thread;
+use yam_core::parsing::{Event, Parser, SpannedEventReceiver};
+use yam_core::prelude::{
+    IsEmpty, MappingLike, Marker, SequenceLike, Span, StrSource, YamlData, YamlDocAccess,
+    YamlError, YamlLoader, YamlScalar,
+};
+
+///
+/// Returns the byte offsets at which `input` can be split into parts that parse independently,
+/// starting with `0` for a non-empty input.
+///
+/// A part starts at a `---` line, unless only directives, comments and blank lines precede it
+/// since the last split, or right after a `...` line. Document markers always start at column 1
+/// and end any scalar, so in valid YAML no node spans two parts.
+///
+/// # Example
+/// ```rust
+/// use yam_std::document_boundaries;
+///
+/// let input = "a: 1\n---\nb: 2\n...\n%YAML 1.2\n---\nc: 3\n";
+/// assert_eq!(document_boundaries(input), vec![0, 5, 18]);
+/// ```
+#[must_use]
+pub fn document_boundaries(input: &str) -> Vec<usize> {
+    Parts::scan(input)
+        .parts
+        .iter()
+        .map(|part| part.start)
+        .collect()
+}
+
+///
+/// Loads multi-document streams, parsing groups of documents on separate threads.
+///
+/// The result is the same as loading the whole stream with [`YamlLoader`], including the error
+/// returned for invalid input: when any part fails, the stream is parsed again sequentially to
+/// report the error at its position in the input.
+///
+/// # Example
+/// ```rust
+/// use yam_core::prelude::{Yaml, YamlDocAccess};
+/// use yam_std::ParallelLoader;
+///
+/// let input = "--- a\n--- b\n--- c\n";
+/// let docs = ParallelLoader::new().threads(2).load::<Yaml>(input).unwrap();
+/// assert_eq!(docs.len(), 3);
+/// assert_eq!(docs[2].as_str(), Some("c"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParallelLoader {
+    threads: usize,
+    keep_tags: bool,
+}
+
+impl Default for ParallelLoader {
+    fn default() -> Self {
+        ParallelLoader {
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            keep_tags: false,
+        }
+    }
+}
+
+impl ParallelLoader {
+    /// Creates a loader using as many threads as [`thread::available_parallelism`] reports.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of threads used to parse the input, at least one.
+    #[must_use]
+    pub fn threads(mut self, value: usize) -> Self {
+        self.threads = value.max(1);
+        self
+    }
+
+    /// Whether `%TAG` directives carry over to later documents, see [`Parser::keep_tags`].
+    #[must_use]
+    pub fn keep_tags(mut self, value: bool) -> Self {
+        self.keep_tags = value;
+        self
+    }
+
+    ///
+    /// Parses all documents in `input`.
+    ///
+    /// # Errors
+    /// Returns the same error as [`YamlLoader::load_from_parser`] would for the whole `input`.
+    pub fn load<'input, Node>(&self, input: &'input str) -> Result<Vec<Node>, YamlError>
+    where
+        Node: YamlDocAccess<'input, OutNode = Node>
+            + From<YamlData<'input, Node>>
+            + From<YamlScalar<'input>>
+            + Send,
+        Node::SequenceNode: SequenceLike<Node> + IsEmpty + Clone,
+        Node::MappingNode: MappingLike<Node> + IsEmpty + Clone,
+    {
+        let lines = Parts::scan(input);
+        let batches = lines.batches(input.len(), self.threads);
+        if batches.len() < 2 {
+            return self.load_sequential(input);
+        }
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = batches
+                .iter()
+                .map(|batch| scope.spawn(|| self.load_batch::<Node>(input, batch)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("parser thread panicked"))
+                .collect()
+        });
+
+        let mut docs = Vec::new();
+        let mut tags = BTreeMap::new();
+        for (batch, result) in batches.iter().zip(results) {
+            // The tags guessed from the `%TAG` lines must match the ones the previous batch
+            // actually ended with, otherwise the batch was parsed with the wrong handles.
+            let Ok((batch_docs, end_tags)) = result else {
+                return self.load_sequential(input);
+            };
+            if self.keep_tags && batch.tags != tags {
+                return self.load_sequential(input);
+            }
+            tags = end_tags;
+            docs.extend(batch_docs);
+        }
+        Ok(docs)
+    }
+
+    fn parser<'input>(&self, input: &'input str) -> Parser<'input, StrSource<'input>> {
+        Parser::new_from_str(input).keep_tags(self.keep_tags)
+    }
+
+    fn load_sequential<'input, Node>(&self, input: &'input str) -> Result<Vec<Node>, YamlError>
+    where
+        Node: YamlDocAccess<'input, OutNode = Node>
+            + From<YamlData<'input, Node>>
+            + From<YamlScalar<'input>>,
+        Node::SequenceNode: SequenceLike<Node> + IsEmpty + Clone,
+        Node::MappingNode: MappingLike<Node> + IsEmpty + Clone,
+    {
+        YamlLoader::load_from_parser(&mut self.parser(input))
+    }
+
+    /// Loads the documents of `batch`, and returns them with the tags in effect at its end.
+    fn load_batch<'input, Node>(
+        &self,
+        input: &'input str,
+        batch: &Batch,
+    ) -> Result<(Vec<Node>, BTreeMap<String, String>), YamlError>
+    where
+        Node: YamlDocAccess<'input, OutNode = Node>
+            + From<YamlData<'input, Node>>
+            + From<YamlScalar<'input>>,
+        Node::SequenceNode: SequenceLike<Node> + IsEmpty + Clone,
+        Node::MappingNode: MappingLike<Node> + IsEmpty + Clone,
+    {
+        // without `keep_tags`, the handles of earlier documents don't apply to this one
+        let tags = if self.keep_tags {
+            batch.tags.clone()
+        } else {
+            BTreeMap::new()
+        };
+        let mut parser = self
+            .parser(&input[batch.start.pos..batch.end.map_or(input.len(), |end| end.pos)])
+            .tag_directives(tags.clone());
+        let mut recv = Rebase {
+            loader: YamlLoader::default(),
+            start: batch.start,
+            end: batch.end,
+            tags,
+        };
+        parser.load(&mut recv, true)?;
+        Ok((recv.loader.into_documents()?, recv.tags))
+    }
+}
+
+/// Moves the spans of a batch to its position in the whole input and tracks the active tags.
+struct Rebase<'input, Node: YamlDocAccess<'input>> {
+    loader: YamlLoader<'input, Node>,
+    start: Marker,
+    end: Option<Marker>,
+    tags: BTreeMap<String, String>,
+}
+
+impl<'input, Node> SpannedEventReceiver<'input> for Rebase<'input, Node>
+where
+    Node: YamlDocAccess<'input>,
+    YamlLoader<'input, Node>: SpannedEventReceiver<'input>,
+{
+    fn on_event(&mut self, ev: Event<'input>, span: Span) {
+        if let Event::DocumentStart(_, directives) = &ev {
+            self.tags.clone_from(&directives.tags);
+        }
+        let rebase = |mark: Marker| match self.end {
+            // the scanner moves to a new line at the end of the input, but not before `---`
+            Some(end) if mark.pos + self.start.pos == end.pos => end,
+            // batches start at a line start, so columns stay the same
+            _ => Marker::new(
+                mark.pos + self.start.pos,
+                mark.col,
+                mark.line + self.start.line - 1,
+            ),
+        };
+        let span = Span::new(rebase(span.start), rebase(span.end));
+        self.loader.on_event(ev, span);
+    }
+}
+
+/// A range of parts parsed together, with the `%TAG` handles expected at its start.
+struct Batch {
+    start: Marker,
+    /// Start of the next batch, if any.
+    end: Option<Marker>,
+    tags: BTreeMap<String, String>,
+}
+
+/// Start of a part, with the `%TAG` directives declared before it.
+struct Part {
+    start: usize,
+    line: u32,
+    tags: Vec<(String, String)>,
+}
+
+struct Parts {
+    parts: Vec<Part>,
+}
+
+impl Parts {
+    fn scan(input: &str) -> Self {
+        let mut parts: Vec<Part> = Vec::new();
+        // whether only directives, comments and blank lines were seen since the last split
+        let mut prologue = true;
+        let mut pos = 0;
+        let split = |parts: &mut Vec<Part>, start, line| {
+            if start < input.len() && parts.last().is_none_or(|part| part.start < start) {
+                parts.push(Part {
+                    start,
+                    line,
+                    tags: Vec::new(),
+                });
+            }
+        };
+        split(&mut parts, 0, 1);
+
+        for (line, text) in (1..).zip(input.split_inclusive('\n')) {
+            let bytes = text.as_bytes();
+            let next = pos + text.len();
+            if is_marker(bytes, b"---") {
+                if !prologue {
+                    split(&mut parts, pos, line);
+                }
+                prologue = false;
+            } else if is_marker(bytes, b"...") {
+                split(&mut parts, next, line + 1);
+                prologue = true;
+            } else if prologue && bytes.starts_with(b"%TAG") {
+                let mut words = text.split_whitespace().skip(1);
+                if let (Some(handle), Some(prefix), Some(part)) =
+                    (words.next(), words.next(), parts.last_mut())
+                {
+                    part.tags.push((handle.to_string(), prefix.to_string()));
+                }
+            } else if !matches!(
+                bytes
+                    .iter()
+                    .find(|b| !matches!(b, b' ' | b'\t' | b'\r' | b'\n')),
+                None | Some(b'#' | b'%')
+            ) {
+                prologue = false;
+            }
+            pos = next;
+        }
+        Parts { parts }
+    }
+
+    /// Groups the parts into at most `threads` batches of similar length.
+    fn batches(&self, len: usize, threads: usize) -> Vec<Batch> {
+        let mut batches: Vec<Batch> = Vec::new();
+        let mut tags = BTreeMap::new();
+        let target = len.div_ceil(threads);
+        for part in &self.parts {
+            let start_new = batches
+                .last()
+                .is_none_or(|batch| part.start - batch.start.pos >= target);
+            if start_new {
+                let start = Marker::new(part.start, 1, part.line);
+                if let Some(batch) = batches.last_mut() {
+                    batch.end = Some(start);
+                }
+                batches.push(Batch {
+                    start,
+                    end: None,
+                    tags: tags.clone(),
+                });
+            }
+            tags.extend(part.tags.iter().cloned());
+        }
+        batches
+    }
+}
+
+/// Whether `line` is the document marker `marker` followed by a blank, a break or the end.
+fn is_marker(line: &[u8], marker: &[u8; 3]) -> bool {
+    line.starts_with(marker) && matches!(line.get(3), None | Some(b' ' | b'\t' | b'\r' | b'\n'))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ParallelLoader, document_boundaries};
+    use std::string::ToString;
+    use yam_core::parsing::Parser;
+    use yam_core::prelude::{SpannedYaml, Yaml, YamlLoader, YamlOwned};
+
+    #[test]
+    fn test_document_boundaries() {
+        assert!(document_boundaries("").is_empty());
+        assert_eq!(document_boundaries("# c\n%YAML 1.2\n--- a\n"), [0]);
+        assert_eq!(document_boundaries("--- |+\n  a\n\n# c\n--- b"), [0, 16]);
+        assert_eq!(
+            document_boundaries("a\n...\n# c\n%TAG ! x\n--- b\n..."),
+            [0, 6]
+        );
+        assert_eq!(document_boundaries("---a\n----\n--- \"\n---\"\n"), [0, 10]);
+    }
+
+    #[test]
+    fn test_parallel_load() {
+        let input = "- a: &a [1, 2]\n  b: *a\n---\n\"x\n y\"\n--- |+\n  z\n\n...\n".repeat(50);
+        let expected = YamlLoader::<Yaml>::load_from(&input).unwrap();
+        for threads in [1, 2, 7] {
+            let loader = ParallelLoader::new().threads(threads);
+            assert_eq!(loader.load::<Yaml>(&input).unwrap(), expected);
+        }
+        let owned = ParallelLoader::new().threads(3).load::<YamlOwned>(&input);
+        assert_eq!(owned, YamlOwned::load_from(&input));
+
+        let spans = |docs: Vec<SpannedYaml>| docs.iter().map(|doc| doc.span).collect::<Vec<_>>();
+        let spanned = ParallelLoader::new().threads(3).load(&input).unwrap();
+        let expected = YamlLoader::<SpannedYaml>::load_from(&input).unwrap();
+        assert_eq!(spans(spanned), spans(expected));
+
+        let invalid = input.clone() + "--- [a\n--- b\n";
+        let err = ParallelLoader::new().threads(4).load::<Yaml>(&invalid);
+        assert_eq!(err, YamlLoader::<Yaml>::load_from(&invalid));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_parallel_keep_tags() {
+        let input =
+            "%TAG !a! tag:a:\n--- !a!x 1\n...\n%TAG !b! tag:b:\n--- !b!y 2\n--- !a!z 3\n...\n"
+                .repeat(20);
+        let mut parser = Parser::new_from_str(&input).keep_tags(true);
+        let expected = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap();
+        let loader = ParallelLoader::new().threads(4).keep_tags(true);
+        assert_eq!(loader.load::<Yaml>(&input).unwrap(), expected);
+
+        // without `keep_tags`, `!a!z` is not declared in its document
+        let err = ParallelLoader::new().threads(4).load::<Yaml>(&input);
+        assert_eq!(err, YamlLoader::<Yaml>::load_from(&input));
+        assert!(err.unwrap_err().to_string().contains("wasn't declared"));
+
+        let input = "%TAG !a! tag:a:\n--- !a!x 1\n--- !a!y 2\n";
+        let expected = YamlLoader::<Yaml>::load_from(input);
+        assert!(expected.is_err());
+        for threads in [1, 2, 4] {
+            let loader = ParallelLoader::new().threads(threads);
+            assert_eq!(loader.load::<Yaml>(input), expected, "{threads} threads");
+        }
+    }
+}
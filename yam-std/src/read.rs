@@ -0,0 +1,97 @@
+use std::io::{ErrorKind, Read};
+use std::string::ToString;
+use yam_core::parsing::{BufferedBytesSource, ByteRead};
+use yam_core::prelude::YamlError;
+
+///
+/// Adapts a [`Read`] to the [`ByteRead`] used by [`BufferedBytesSource`].
+///
+/// Interrupted reads are retried; other read errors are reported as [`YamlError::Io`].
+pub struct IoRead<R> {
+    reader: R,
+}
+
+impl<R: Read> IoRead<R> {
+    pub fn new(reader: R) -> Self {
+        IoRead { reader }
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> ByteRead for IoRead<R> {
+    fn read_bytes(&mut self, buf: &mut [u8]) -> Result<usize, YamlError> {
+        loop {
+            match self.reader.read(buf) {
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                res => return res.map_err(|err| YamlError::Io(err.to_string())),
+            }
+        }
+    }
+}
+
+///
+/// Creates a [`yam_core::parsing::Source`] that reads `reader` in blocks, e.g. a file or a
+/// network stream, without reading all of it in memory first.
+///
+/// # Example
+/// ```rust
+/// use yam_core::parsing::Parser;
+/// use yam_core::prelude::{Yaml, YamlDocAccess, YamlLoader};
+/// use yam_std::source_from_reader;
+///
+/// let file: &[u8] = b"- a\n- b\n";
+/// let mut parser = Parser::new(source_from_reader(file));
+/// let docs = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap();
+/// assert_eq!(docs[0][1].as_str(), Some("b"));
+/// ```
+pub fn source_from_reader<R: Read>(reader: R) -> BufferedBytesSource<IoRead<R>> {
+    BufferedBytesSource::new(IoRead::new(reader))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::source_from_reader;
+    use std::io::{self, Read};
+    use yam_core::parsing::Parser;
+    use yam_core::prelude::{Yaml, YamlError, YamlLoader};
+
+    /// Returns its input a few bytes at a time, then fails.
+    struct Flaky<'a> {
+        input: &'a [u8],
+    }
+
+    impl Read for Flaky<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.input.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            let len = buf.len().min(3).min(self.input.len());
+            buf[..len].copy_from_slice(&self.input[..len]);
+            self.input = &self.input[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_load_from_reader() {
+        let input = "key: [1, 2]\nname: ünïcödé\n".repeat(1000);
+        let mut parser = Parser::new(source_from_reader(input.as_bytes()));
+        let docs = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap();
+        assert_eq!(docs, YamlLoader::<Yaml>::load_from(&input).unwrap());
+
+        let mut parser = Parser::new(source_from_reader(Flaky {
+            input: b"key: value\n",
+        }));
+        let err = YamlLoader::<Yaml>::load_from_parser(&mut parser).unwrap_err();
+        assert_eq!(err, YamlError::Io("disk on fire".to_string()));
+
+        let mut reader = &b"a: b"[..];
+        let mut parser = Parser::new(source_from_reader(&mut reader));
+        assert!(YamlLoader::<Yaml>::load_from_parser(&mut parser).is_ok());
+        assert!(reader.read(&mut [0]).unwrap() == 0);
+    }
+}
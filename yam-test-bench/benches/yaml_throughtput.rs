@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
-use yam_core::parsing::Parser;
+use yam_core::parsing::{Parser, PushEvent, PushParser};
+use yam_core::prelude::{Yaml, YamlArena, YamlLoader};
 use yam_test_bench::{write_str_from_event, FormatOpts};
 
 const NESTED_YAML: &str = include_str!("nested.yaml");
@@ -30,6 +31,18 @@ fn bench_big_text(c: &mut Criterion) {
             assert!(!buff.is_empty());
         })
     });
+    group.bench_function("load-yaml", |b| {
+        b.iter(|| {
+            let docs = YamlLoader::<Yaml>::load_from(BIG_TEXT).unwrap();
+            assert!(!docs.is_empty());
+        })
+    });
+    group.bench_function("load-arena", |b| {
+        b.iter(|| {
+            let arena = YamlArena::load_from(BIG_TEXT).unwrap();
+            assert!(!arena.is_empty());
+        })
+    });
 
     group.finish();
 }
@@ -50,6 +63,18 @@ fn bench_nested(c: &mut Criterion) {
             assert!(!buff.is_empty());
         })
     });
+    group.bench_function("load-yaml", |b| {
+        b.iter(|| {
+            let docs = YamlLoader::<Yaml>::load_from(NESTED_YAML).unwrap();
+            assert!(!docs.is_empty());
+        })
+    });
+    group.bench_function("load-arena", |b| {
+        b.iter(|| {
+            let arena = YamlArena::load_from(NESTED_YAML).unwrap();
+            assert!(!arena.is_empty());
+        })
+    });
 
     group.finish();
 }
@@ -74,6 +99,52 @@ fn bench_small_object(c: &mut Criterion) {
     group.finish();
 }
 
+/// Feeds `input` to a [`PushParser`] in chunks of `size` bytes and counts the events.
+fn push_events(input: &str, size: usize) -> usize {
+    let mut parser = PushParser::new();
+    let mut events = 0;
+    for chunk in input.as_bytes().chunks(size) {
+        parser.feed(chunk);
+        while let Some(PushEvent::Event(..)) = parser.next_event().transpose().unwrap() {
+            events += 1;
+        }
+    }
+    parser.finish();
+    while let Some(PushEvent::Event(..)) = parser.next_event().transpose().unwrap() {
+        events += 1;
+    }
+    events
+}
+
+fn bench_push(c: &mut Criterion) {
+    // About 1.2 MB of small objects, in separate documents.
+    let stream = [SMALL_OBJECTS_YAML; 11].join("\n---\n");
+    // A single document defining and using many anchors.
+    let anchors: String = (0..20_000)
+        .map(|i| format!("- &a{i} {{k: {i}}}\n- *a{i}\n"))
+        .collect();
+
+    let mut group = c.benchmark_group("push");
+    group.sample_size(10);
+    for (name, input) in [("stream", &stream), ("anchors", &anchors)] {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(format!("{name}-str"), |b| {
+            b.iter(|| {
+                let events = Parser::new_from_str(input).count();
+                assert!(events > 0);
+            })
+        });
+        group.bench_function(format!("{name}-push-64"), |b| {
+            b.iter(|| {
+                let events = push_events(input, 64);
+                assert!(events > 0);
+            })
+        });
+    }
+
+    group.finish();
+}
+
 fn noop() {}
 
 criterion_group!(
@@ -81,6 +152,7 @@ criterion_group!(
     bench_noop,
     bench_big_text,
     bench_nested,
-    bench_small_object
+    bench_small_object,
+    bench_push
 );
 criterion_main!(benches);
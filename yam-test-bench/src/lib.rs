@@ -3,7 +3,7 @@ pub mod consts;
 use std::borrow::Cow;
 use std::fmt::Write;
 use yam_core::parsing::Event;
-use yam_core::parsing::{Parser, ScalarValue, Source};
+use yam_core::parsing::{BufferedBytesSource, Parser, ScalarValue, Source};
 use yam_core::prelude::Tag;
 
 ///
@@ -38,6 +38,27 @@ pub fn assert_eq_event_case_with_opts(input: &str, events: &str, emit_opt: &Form
     if !expected_err {
         assert_eq!(line, unescape_text(events), "Error in case: {input}");
     }
+    assert_eq_buffered(input, &line, emit_opt);
+}
+
+///
+/// Assert that parsing `input` from a [`BufferedBytesSource`] generates the same events as
+/// parsing it from a string, for several block lengths.
+///
+/// # Panics
+///
+///    Function panics if the events differ from `expected`.
+pub fn assert_eq_buffered(input: &str, expected: &str, emit_opt: &FormatOpts) {
+    for block_len in [1, 33, 8 * 1024] {
+        let mut line = String::new();
+        let source = BufferedBytesSource::with_block_len(input.bytes(), block_len);
+        let mut parser = Parser::new(source);
+        write_str_from_event(&mut line, &mut parser, emit_opt);
+        assert_eq!(
+            line, expected,
+            "Error in case (block length {block_len}): {input}"
+        );
+    }
 }
 
 #[derive(Default)]
@@ -86,33 +107,29 @@ pub fn write_str_from_event<T: Source>(
         let _ = match ev {
             Event::StreamStart if emit_opt.emit_stream() => writeln!(line, "+STR"),
             Event::StreamEnd if emit_opt.emit_stream() => writeln!(line, "-STR"),
-            Event::DocumentStart(_) => writeln!(line, "+DOC"),
+            Event::DocumentStart(..) => writeln!(line, "+DOC"),
             Event::DocumentEnd => writeln!(line, "-DOC"),
-            Event::Alias(anchor_id) => {
-                let anchor = parser
-                    .get_anchor(anchor_id)
-                    .map_or(String::default(), ToString::to_string);
-                writeln!(line, "=ALI *{anchor}")
-            }
+            Event::Alias(_, anchor) => writeln!(line, "=ALI *{anchor}"),
             Event::Scalar(ScalarValue {
                 value,
                 scalar_type,
-                anchor_id,
+                anchor_name,
                 tag,
+                ..
             }) => {
-                let anchor = extract_anchor(parser, anchor_id);
+                let anchor = extract_anchor(anchor_name);
                 let tag_info = extract_tag_full(tag);
                 writeln!(line, "=VAL{anchor}{tag_info} {scalar_type}{value}")
             }
-            Event::SequenceStart(anchor_id, tag) => {
+            Event::SequenceStart(_, anchor_name, tag, _) => {
                 let tag_info = extract_tag_full(tag);
-                let anchor = extract_anchor(parser, anchor_id);
+                let anchor = extract_anchor(anchor_name);
                 writeln!(line, "+SEQ{anchor}{tag_info}")
             }
             Event::SequenceEnd => writeln!(line, "-SEQ"),
-            Event::MappingStart(anchor_id, tag) => {
+            Event::MappingStart(_, anchor_name, tag, _) => {
                 let tag_info = extract_tag_full(tag);
-                let anchor = extract_anchor(parser, anchor_id);
+                let anchor = extract_anchor(anchor_name);
                 writeln!(line, "+MAP{anchor}{tag_info}")
             }
             Event::MappingEnd => writeln!(line, "-MAP"),
@@ -143,8 +160,8 @@ fn extract_tag_full(tag: Option<Cow<Tag>>) -> String {
     }
 }
 
-fn extract_anchor<T: Source>(parser: &Parser<T>, anchor_id: usize) -> String {
-    let anchor = if let Some(cow) = parser.get_anchor(anchor_id) {
+fn extract_anchor(anchor_name: Option<Cow<str>>) -> String {
+    let anchor = if let Some(cow) = anchor_name {
         let mut out = String::with_capacity(cow.len() + 2);
         write!(out, " &{cow}").unwrap();
         out
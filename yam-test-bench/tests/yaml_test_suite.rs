@@ -7,7 +7,7 @@ use std::{fs, io};
 
 use libtest_mimic::{Arguments, Failed, Trial};
 use yam_core::parsing::Parser;
-use yam_test_bench::{unescape_text, write_str_from_event, FormatOpts};
+use yam_test_bench::{assert_eq_buffered, unescape_text, write_str_from_event, FormatOpts};
 
 const TEST_SIZE: usize = 440;
 
@@ -43,6 +43,7 @@ fn perform_test_saphyr(data: TestData) -> Result<(), Failed> {
     if !data.is_error {
         assert_eq!(actual_event, expected_event);
     }
+    assert_eq_buffered(&input_yaml, &actual_event, &FormatOpts::YamlFormat);
 
     Ok(())
 }
@@ -145,6 +146,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     path.push("yaml-test-suite");
 
     let tests = collect_tests(&path, filter_list)?;
+    if tests.is_empty() {
+        return Err(format!(
+            "no test cases in {}, run `git submodule update --init` to fetch yaml-test-suite",
+            path.display()
+        )
+        .into());
+    }
 
     libtest_mimic::run(&args, tests).exit();
     // Ok(())